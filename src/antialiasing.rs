@@ -0,0 +1,312 @@
+use crate::color::{Color, Rgba32F};
+use crate::material::Material;
+use crate::shading::SurfaceTriangle;
+use crate::texture::Texture;
+use crate::{Framebuffer, Point2D};
+use rayon::prelude::*;
+
+/// Anzahl der Coverage-Samples pro Pixel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Msaa {
+    #[default]
+    Off,
+    X2,
+    X4,
+    X8,
+}
+
+impl Msaa {
+    pub fn sample_count(self) -> usize {
+        self.sample_offsets().len()
+    }
+
+    /// Sample-Positionen relativ zur Pixelmitte (Standardmuster wie bei D3D, in 1/16 Pixel)
+    pub fn sample_offsets(self) -> &'static [(f32, f32)] {
+        const OFF: [(f32, f32); 1] = [(0.0, 0.0)];
+        const X2: [(f32, f32); 2] = [(4.0 / 16.0, 4.0 / 16.0), (-4.0 / 16.0, -4.0 / 16.0)];
+        const X4: [(f32, f32); 4] = [
+            (-2.0 / 16.0, -6.0 / 16.0),
+            (6.0 / 16.0, -2.0 / 16.0),
+            (-6.0 / 16.0, 2.0 / 16.0),
+            (2.0 / 16.0, 6.0 / 16.0),
+        ];
+        const X8: [(f32, f32); 8] = [
+            (1.0 / 16.0, -3.0 / 16.0),
+            (-1.0 / 16.0, 3.0 / 16.0),
+            (5.0 / 16.0, 1.0 / 16.0),
+            (-3.0 / 16.0, -5.0 / 16.0),
+            (-5.0 / 16.0, 5.0 / 16.0),
+            (-7.0 / 16.0, -1.0 / 16.0),
+            (3.0 / 16.0, 7.0 / 16.0),
+            (7.0 / 16.0, -7.0 / 16.0),
+        ];
+
+        match self {
+            Msaa::Off => &OFF,
+            Msaa::X2 => &X2,
+            Msaa::X4 => &X4,
+            Msaa::X8 => &X8,
+        }
+    }
+}
+
+/// Mindestkontrast, ab dem FXAA eine Kante annimmt
+const FXAA_EDGE_THRESHOLD: f32 = 0.125;
+const FXAA_EDGE_THRESHOLD_MIN: f32 = 0.0312;
+/// Wie stark Subpixel-Aliasing geglättet wird (0 = aus, 1 = maximal)
+const FXAA_SUBPIXEL_QUALITY: f32 = 0.75;
+/// Maximale Schritte bei der Suche nach dem Kantenende
+const FXAA_SEARCH_STEPS: usize = 8;
+
+impl<C: Color> Framebuffer<C> {
+    /// Sorgt dafür, dass die Sample-Buffer zur aktuellen Größe und MSAA-Stufe passen
+    pub(crate) fn prepare_samples(&mut self) {
+        let len = if self.msaa == Msaa::Off {
+            0
+        } else {
+            self.width * self.height * self.msaa.sample_count()
+        };
+        self.sample_colors.resize(len, C::BLACK);
+        self.sample_depths.resize(len, f32::INFINITY);
+    }
+
+    ///Rasterisiert ein Dreieck mit mehreren Coverage- und Tiefen-Samples pro Pixel.
+    ///Die Farbe wird nur einmal pro Pixel im Schwerpunkt der abgedeckten Samples bestimmt
+    pub(crate) fn rasterize_triangle_msaa(
+        &mut self,
+        v0: Point2D,
+        v1: Point2D,
+        v2: Point2D,
+        uv0: (f32, f32),
+        uv1: (f32, f32),
+        uv2: (f32, f32),
+        texture: Option<&Texture>,
+        color: Rgba32F,
+        material: &Material,
+        surface: Option<&SurfaceTriangle>,
+    ) {
+        let det = (v1.x - v0.x) * (v2.y - v0.y) - (v2.x - v0.x) * (v1.y - v0.y);
+        if det.abs() < f32::EPSILON {
+            return; // Degeneriertes Dreieck
+        }
+        let inv_det = 1.0 / det;
+
+        let min_x = v0.x.min(v1.x).min(v2.x).floor().max(0.0) as usize;
+        let max_x = (v0.x.max(v1.x).max(v2.x).ceil() as usize).min(self.width);
+        let min_y = v0.y.min(v1.y).min(v2.y).floor().max(0.0) as usize;
+        let max_y = (v0.y.max(v1.y).max(v2.y).ceil() as usize).min(self.height);
+
+        let offsets = self.msaa.sample_offsets();
+        let samples = offsets.len();
+
+        // Baryzentrische Koordinaten, unabhängig von der Umlaufrichtung
+        let barycentric = |px: f32, py: f32| {
+            let w0 = ((v1.x - px) * (v2.y - py) - (v2.x - px) * (v1.y - py)) * inv_det;
+            let w1 = ((v2.x - px) * (v0.y - py) - (v0.x - px) * (v2.y - py)) * inv_det;
+            (w0, w1, 1.0 - w0 - w1)
+        };
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let mut covered = 0u32; // Bitmaske der abgedeckten Samples
+                let mut centroid = (0.0, 0.0, 0.0);
+
+                for (s, (ox, oy)) in offsets.iter().enumerate() {
+                    let (b0, b1, b2) = barycentric(x as f32 + 0.5 + ox, y as f32 + 0.5 + oy);
+                    if b0 >= 0.0 && b1 >= 0.0 && b2 >= 0.0 {
+                        covered |= 1 << s;
+                        centroid = (centroid.0 + b0, centroid.1 + b1, centroid.2 + b2);
+                    }
+                }
+                if covered == 0 {
+                    continue;
+                }
+
+                // Einmal pro Pixel schattieren
+                let n = covered.count_ones() as f32;
+                let (b0, b1, b2) = (centroid.0 / n, centroid.1 / n, centroid.2 / n);
+                let shaded = match texture {
+                    Some(texture) => {
+                        let u = b0 * uv0.0 + b1 * uv1.0 + b2 * uv2.0;
+                        let v = b0 * uv0.1 + b1 * uv1.1 + b2 * uv2.1;
+                        texture.sample(u, v)
+                    }
+                    None => color,
+                };
+                let shaded = match surface {
+                    Some(surface) => surface.shade(shaded, b0, b1, b2),
+                    None => shaded,
+                };
+
+                // Tiefe pro abgedecktem Sample
+                let mut depths = [f32::INFINITY; 8];
+                for (s, (ox, oy)) in offsets.iter().enumerate() {
+                    if covered & (1 << s) != 0 {
+                        let (b0, b1, b2) = barycentric(x as f32 + 0.5 + ox, y as f32 + 0.5 + oy);
+                        depths[s] = b0 * v0.z + b1 * v1.z + b2 * v2.z;
+                    }
+                }
+
+                if self.collects_fragments(material) {
+                    let index = y * self.width + x;
+                    self.push_transparent_fragment_msaa(
+                        index,
+                        &depths[..samples],
+                        shaded,
+                        material,
+                    );
+                    continue;
+                }
+
+                // Tiefentest und Schreiben pro Sample
+                let base = (y * self.width + x) * samples;
+                for (s, &z) in depths[..samples].iter().enumerate() {
+                    if covered & (1 << s) == 0 {
+                        continue;
+                    }
+                    material.write_fragment(
+                        &mut self.sample_colors[base + s],
+                        &mut self.sample_depths[base + s],
+                        z,
+                        shaded,
+                    );
+                }
+            }
+        }
+    }
+
+    ///Mittelt die Samples jedes Pixels in `pixels`, der Z-Buffer bekommt die nächste Tiefe
+    pub fn resolve_msaa(&mut self) {
+        if self.msaa == Msaa::Off {
+            return;
+        }
+        let samples = self.msaa.sample_count();
+
+        self.pixels
+            .par_iter_mut()
+            .zip(self.z_buffer.par_iter_mut())
+            .zip(self.sample_colors.par_chunks(samples))
+            .zip(self.sample_depths.par_chunks(samples))
+            .for_each(|(((pixel, depth), colors), depths)| {
+                let sum = colors
+                    .iter()
+                    .fold(Rgba32F::default(), |sum, c| sum + c.to_linear());
+                *pixel = C::from_linear(sum * (1.0 / samples as f32));
+                *depth = depths.iter().copied().fold(f32::INFINITY, f32::min);
+            });
+    }
+
+    ///FXAA-artiges Post-Processing: erkennt Kanten über den Helligkeitskontrast,
+    ///sucht entlang der Kante deren Enden und mischt quer zur Kante
+    pub fn apply_fxaa(&mut self) {
+        let (width, height) = (self.width, self.height);
+        if width < 3 || height < 3 {
+            return;
+        }
+
+        let source = self.pixels.clone();
+        // Kontrast wahrnehmungsnah bewerten, die Wurzel nähert die Gammakurve an
+        let luma: Vec<f32> = source
+            .par_iter()
+            .map(|p| p.to_linear().luma().max(0.0).sqrt())
+            .collect();
+        let luma_at = |x: isize, y: isize| {
+            let x = x.clamp(0, width as isize - 1) as usize;
+            let y = y.clamp(0, height as isize - 1) as usize;
+            luma[y * width + x]
+        };
+
+        self.pixels
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(y, row)| {
+                let y = y as isize;
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    let x = x as isize;
+
+                    let m = luma_at(x, y);
+                    let n = luma_at(x, y - 1);
+                    let s = luma_at(x, y + 1);
+                    let w = luma_at(x - 1, y);
+                    let e = luma_at(x + 1, y);
+
+                    let max = m.max(n).max(s).max(w).max(e);
+                    let min = m.min(n).min(s).min(w).min(e);
+                    let range = max - min;
+                    if range < FXAA_EDGE_THRESHOLD_MIN.max(max * FXAA_EDGE_THRESHOLD) {
+                        continue; // Kein Kontrast, keine Kante
+                    }
+
+                    let nw = luma_at(x - 1, y - 1);
+                    let ne = luma_at(x + 1, y - 1);
+                    let sw = luma_at(x - 1, y + 1);
+                    let se = luma_at(x + 1, y + 1);
+
+                    // Kantenrichtung über die Gradienten bestimmen
+                    let horizontal = (nw + ne - 2.0 * n).abs()
+                        + 2.0 * (w + e - 2.0 * m).abs()
+                        + (sw + se - 2.0 * s).abs();
+                    let vertical = (nw + sw - 2.0 * w).abs()
+                        + 2.0 * (n + s - 2.0 * m).abs()
+                        + (ne + se - 2.0 * e).abs();
+                    let is_horizontal = horizontal >= vertical;
+
+                    // Auf welcher Seite der Kante liegt der stärkere Kontrast
+                    let (luma_a, luma_b) = if is_horizontal { (n, s) } else { (w, e) };
+                    let (step, edge_luma) = if (luma_a - m).abs() >= (luma_b - m).abs() {
+                        (-1, (luma_a + m) * 0.5)
+                    } else {
+                        (1, (luma_b + m) * 0.5)
+                    };
+                    let gradient = (luma_a - m).abs().max((luma_b - m).abs()) * 0.25;
+
+                    // Entlang der Kante in beide Richtungen bis zum Ende suchen
+                    let along = |i: isize| {
+                        if is_horizontal {
+                            let a = luma_at(x + i, y);
+                            let b = luma_at(x + i, y + step);
+                            (a + b) * 0.5
+                        } else {
+                            let a = luma_at(x, y + i);
+                            let b = luma_at(x + step, y + i);
+                            (a + b) * 0.5
+                        }
+                    };
+                    let search = |direction: isize| {
+                        let mut distance = 1;
+                        while distance < FXAA_SEARCH_STEPS as isize {
+                            if (along(direction * distance) - edge_luma).abs() >= gradient {
+                                break;
+                            }
+                            distance += 1;
+                        }
+                        distance as f32
+                    };
+                    let negative = search(-1);
+                    let positive = search(1);
+                    let edge_blend = 0.5 - negative.min(positive) / (negative + positive);
+
+                    // Subpixel-Aliasing über den Durchschnitt der Nachbarn
+                    let average = (2.0 * (n + s + w + e) + nw + ne + sw + se) / 12.0;
+                    let subpixel = ((average - m).abs() / range).clamp(0.0, 1.0);
+                    let subpixel = subpixel * subpixel * (3.0 - 2.0 * subpixel);
+                    let subpixel_blend = subpixel * subpixel * FXAA_SUBPIXEL_QUALITY;
+
+                    let blend = edge_blend.max(subpixel_blend);
+                    if blend <= 0.0 {
+                        continue;
+                    }
+
+                    let (ox, oy) = if is_horizontal {
+                        (x, y + step)
+                    } else {
+                        (x + step, y)
+                    };
+                    let ox = ox.clamp(0, width as isize - 1) as usize;
+                    let oy = oy.clamp(0, height as isize - 1) as usize;
+                    let neighbor = source[oy * width + ox].to_linear();
+                    *pixel = C::from_linear(pixel.to_linear().lerp(neighbor, blend.min(1.0)));
+                }
+            });
+    }
+}
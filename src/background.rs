@@ -0,0 +1,150 @@
+use crate::Framebuffer;
+use crate::antialiasing::Msaa;
+use crate::camera::Camera;
+use crate::color::{Color, Rgba32F};
+use crate::point::Point;
+use crate::texture::HdrTexture;
+use rayon::prelude::*;
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+/// Was an Stellen ohne Geometrie zu sehen ist (dort bleibt der Z-Buffer unendlich)
+#[derive(Debug, Clone)]
+pub enum Background {
+    Solid(Rgba32F),                             // Einfarbig, wie bisher beim Leeren
+    Gradient { top: Rgba32F, bottom: Rgba32F }, // Senkrechter Verlauf über das Bild
+    Cubemap(Arc<Cubemap>),                      // Skybox aus sechs Seiten
+    Equirectangular(Arc<HdrTexture>), // Panorama (Längen- und Breitengrad), z.B. aus einer .hdr-Datei
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Solid(Rgba32F::BLACK)
+    }
+}
+
+impl Background {
+    ///Farbe in Blickrichtung `direction` (Weltkoordinaten) an der Bildzeile `v` (0 oben, 1 unten)
+    #[inline(always)]
+    pub fn sample(&self, direction: Point, v: f32) -> Rgba32F {
+        match self {
+            Background::Solid(color) => *color,
+            Background::Gradient { top, bottom } => top.lerp(*bottom, v),
+            Background::Cubemap(cubemap) => cubemap.sample(direction),
+            Background::Equirectangular(texture) => {
+                let (u, v) = equirectangular_uv(direction);
+                texture.sample(u, v)
+            }
+        }
+    }
+
+    ///Ob das Bild vom Blickwinkel abhängt und pro Pixel berechnet werden muss
+    fn depends_on_view(&self) -> bool {
+        matches!(
+            self,
+            Background::Cubemap(_) | Background::Equirectangular(_)
+        )
+    }
+}
+
+/// Würfelumgebung, Seiten in der Reihenfolge +X, -X, +Y, -Y, +Z, -Z (wie bei OpenGL)
+#[derive(Debug, Clone)]
+pub struct Cubemap {
+    pub faces: [HdrTexture; 6],
+}
+
+impl Cubemap {
+    pub fn new(faces: [HdrTexture; 6]) -> Self {
+        Cubemap { faces }
+    }
+
+    ///Lädt die sechs Seiten, Reihenfolge wie bei `faces`
+    pub fn from_files(filepaths: [&str; 6]) -> Self {
+        Cubemap::new(filepaths.map(HdrTexture::from_file))
+    }
+
+    ///Liest die Umgebung in Richtung `direction` (muss nicht normiert sein)
+    #[inline(always)]
+    pub fn sample(&self, direction: Point) -> Rgba32F {
+        let (face, u, v) = cubemap_face(direction);
+        self.faces[face].sample(u, v)
+    }
+}
+
+///Wählt die Würfelseite nach der größten Komponente und projiziert auf deren UV-Koordinaten
+#[inline(always)]
+pub fn cubemap_face(d: Point) -> (usize, f32, f32) {
+    let (ax, ay, az) = (d.x.abs(), d.y.abs(), d.z.abs());
+    let (face, sc, tc, ma) = if ax >= ay && ax >= az {
+        if d.x > 0.0 {
+            (0, -d.z, -d.y, ax)
+        } else {
+            (1, d.z, -d.y, ax)
+        }
+    } else if ay >= az {
+        if d.y > 0.0 {
+            (2, d.x, d.z, ay)
+        } else {
+            (3, d.x, -d.z, ay)
+        }
+    } else if d.z > 0.0 {
+        (4, d.x, -d.y, az)
+    } else {
+        (5, -d.x, -d.y, az)
+    };
+    (face, 0.5 * (sc / ma + 1.0), 0.5 * (tc / ma + 1.0))
+}
+
+///UV-Koordinaten eines Panoramas: u läuft einmal um die Hochachse, v von oben nach unten
+#[inline(always)]
+pub fn equirectangular_uv(direction: Point) -> (f32, f32) {
+    let d = direction.normalize();
+    let u = 0.5 + d.x.atan2(d.z) / (2.0 * PI);
+    let v = 0.5 - d.y.clamp(-1.0, 1.0).asin() / PI;
+    (u, v)
+}
+
+impl<C: Color> Framebuffer<C> {
+    ///Farbe, mit der `clear` den Framebuffer füllt
+    pub(crate) fn clear_color(&self) -> C {
+        match self.background {
+            Background::Solid(color) => C::from_linear(color),
+            _ => C::BLACK,
+        }
+    }
+
+    ///Zeichnet Verlauf oder Skybox in alle Pixel (und MSAA-Samples), vor der Geometrie,
+    ///damit transparente Flächen darüber geblendet werden. Einfarbig erledigt das `clear`
+    pub fn draw_background(&mut self, camera: &Camera) {
+        let (width, height) = (self.width, self.height);
+        if matches!(self.background, Background::Solid(_)) || width == 0 {
+            return;
+        }
+
+        let background = &self.background;
+        let per_pixel = background.depends_on_view();
+        self.pixels
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(y, row)| {
+                let v = (y as f32 + 0.5) / height as f32;
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    let direction = if per_pixel {
+                        let x_ndc = 2.0 * (x as f32 + 0.5) / width as f32 - 1.0;
+                        camera.view_ray(x_ndc, 1.0 - 2.0 * v)
+                    } else {
+                        Point::new(0.0, 0.0, 0.0)
+                    };
+                    *pixel = C::from_linear(background.sample(direction, v));
+                }
+            });
+
+        if self.msaa != Msaa::Off {
+            let samples = self.msaa.sample_count();
+            self.sample_colors
+                .par_chunks_mut(samples)
+                .zip(self.pixels.par_iter())
+                .for_each(|(sample_colors, pixel)| sample_colors.fill(*pixel));
+        }
+    }
+}
@@ -1,24 +1,284 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+//! Benchmarks für die einzelnen Pipeline-Stufen und komplette Frames.
+//!
+//! Alle Szenen sind fest vorgegeben (Kameraposen, Auflösungen, Assets), damit Messungen
+//! zwischen zwei Commits vergleichbar bleiben. Ausführen mit `cargo bench`.
 
-fn benchmark_render_scene(c: &mut Criterion) {
-    // You might need to initialize or mock a framebuffer
-    let mut framebuffer = initialize_framebuffer(); // Replace with your actual framebuffer init
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use rake::framebuffer::triangulate_ear_clipping;
+use rake::render::render_scene;
+use rake::{
+    Camera, Framebuffer, Point, Point2D, Polygon, Polygon2D, Rgba8, Rgba32F, Texture, object,
+    polygon,
+};
+use std::hint::black_box;
+use std::path::Path;
+use std::sync::Arc;
 
-    c.bench_function("render_scene_with_polygons", |b| {
-        b.iter(|| {
-            unsafe {
-                if let Some(ref polygons) = POLYGONS {
-                    render_scene(polygons, &mut framebuffer);
+const RESOLUTIONS: [(usize, usize); 3] = [(320, 180), (800, 600), (1920, 1080)];
+
+struct Asset {
+    name: &'static str,
+    obj: &'static str,
+    texture: Option<&'static str>,
+    camera_position: Point,
+    target: Point,
+}
+
+const ASSETS: [Asset; 2] = [
+    Asset {
+        name: "capsule",
+        obj: "capsule.obj",
+        texture: Some("capsule0.jpg"),
+        camera_position: Point {
+            x: 2.0,
+            y: 1.5,
+            z: -4.0,
+        },
+        target: Point {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        },
+    },
+    Asset {
+        name: "example",
+        obj: "example.obj",
+        texture: None,
+        camera_position: Point {
+            x: 4.0,
+            y: 3.0,
+            z: -6.0,
+        },
+        target: Point {
+            x: 0.2,
+            y: 0.0,
+            z: 1.6,
+        },
+    },
+];
+
+fn asset_path(file: &str) -> String {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join(file)
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+fn load_polygons(asset: &Asset) -> Vec<Polygon> {
+    let (vertices, faces, tex) = object::parse_obj_file(&asset_path(asset.obj)).unwrap();
+    let mut polygons = object::process_faces(&vertices, &faces, &tex);
+
+    if let Some(texture) = asset.texture {
+        let texture = Arc::new(Texture::from_file(&asset_path(texture)));
+        for polygon in polygons.iter_mut() {
+            polygon.set_texture(texture.clone());
+        }
+    }
+    polygons
+}
+
+fn look_at(position: Point, target: Point, width: usize, height: usize) -> Camera {
+    let mut camera = Camera::new(
+        position,
+        (position - target).normalize(),
+        Point::new(0.0, 1.0, 0.0),
+        60.0,
+        width as f32 / height as f32,
+        0.1,
+        100.0,
+    );
+    let forward = camera.forward;
+    camera.yaw = forward.z.atan2(forward.x);
+    camera.pitch = forward.y.asin();
+    camera.update_forward();
+    camera
+}
+
+fn checkerboard(size: usize) -> Texture {
+    let mut data = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let value = if (x / 8 + y / 8).is_multiple_of(2) {
+                230
+            } else {
+                40
+            };
+            data.extend_from_slice(&[value, value, value, 255]);
+        }
+    }
+    Texture::new(size, size, data)
+}
+
+/// Ein großes Dreieck in Bildschirmkoordinaten, deckt ca. ein Viertel des Framebuffers ab
+fn screen_triangle(width: usize, height: usize) -> Polygon2D {
+    let (w, h) = (width as f32, height as f32);
+    Polygon2D {
+        vertices: vec![
+            Point2D {
+                x: w * 0.25,
+                y: h * 0.25,
+                z: 1.0,
+            },
+            Point2D {
+                x: w * 0.75,
+                y: h * 0.25,
+                z: 1.0,
+            },
+            Point2D {
+                x: w * 0.5,
+                y: h * 0.75,
+                z: 1.0,
+            },
+        ],
+        uv_coords: vec![(0.0, 0.0), (1.0, 0.0), (0.5, 1.0)],
+        normals: Vec::new(),
+        to_camera: Vec::new(),
+        tangents: Vec::new(),
+    }
+}
+
+/// Regelmäßiges, nicht konvexes Sternpolygon mit `points` Zacken
+fn star_polygon(points: usize) -> Polygon2D {
+    let mut vertices = Vec::with_capacity(points * 2);
+    let mut uv_coords = Vec::with_capacity(points * 2);
+    for i in 0..points * 2 {
+        let angle = i as f32 / (points * 2) as f32 * std::f32::consts::TAU;
+        let radius = if i % 2 == 0 { 100.0 } else { 40.0 };
+        vertices.push(Point2D {
+            x: 200.0 + radius * angle.cos(),
+            y: 200.0 + radius * angle.sin(),
+            z: 1.0,
+        });
+        uv_coords.push((0.5 + 0.5 * angle.cos(), 0.5 + 0.5 * angle.sin()));
+    }
+    Polygon2D {
+        vertices,
+        uv_coords,
+        normals: Vec::new(),
+        to_camera: Vec::new(),
+        tangents: Vec::new(),
+    }
+}
+
+fn bench_obj_parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_obj_file");
+    group.sample_size(20);
+    for asset in &ASSETS {
+        let path = asset_path(asset.obj);
+        group.bench_function(asset.name, |b| {
+            b.iter(|| object::parse_obj_file(black_box(&path)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_process_faces(c: &mut Criterion) {
+    let mut group = c.benchmark_group("process_faces");
+    for asset in &ASSETS {
+        let (vertices, faces, tex) = object::parse_obj_file(&asset_path(asset.obj)).unwrap();
+        group.throughput(Throughput::Elements(faces.len() as u64));
+        group.bench_function(asset.name, |b| {
+            b.iter(|| object::process_faces(black_box(&vertices), &faces, &tex))
+        });
+    }
+    group.finish();
+}
+
+fn bench_projection(c: &mut Criterion) {
+    let mut group = c.benchmark_group("project_polygon");
+    for asset in &ASSETS {
+        let polygons = load_polygons(asset);
+        let camera = look_at(asset.camera_position, asset.target, 800, 600);
+        let view_matrix = camera.view_matrix();
+        let projection_matrix = camera.projection_matrix();
+
+        group.throughput(Throughput::Elements(polygons.len() as u64));
+        group.bench_function(asset.name, |b| {
+            b.iter(|| {
+                for polygon in &polygons {
+                    black_box(polygon::project_polygon(
+                        polygon,
+                        &view_matrix,
+                        &projection_matrix,
+                        camera.position,
+                        camera.near,
+                        800,
+                        600,
+                    ));
                 }
-            }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_triangulation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("triangulate_ear_clipping");
+    for points in [3, 8, 32] {
+        let polygon = star_polygon(points);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(points * 2),
+            &polygon,
+            |b, polygon| b.iter(|| triangulate_ear_clipping(black_box(polygon))),
+        );
+    }
+    group.finish();
+}
+
+fn bench_rasterization(c: &mut Criterion) {
+    let texture = checkerboard(256);
+    let mut group = c.benchmark_group("rasterize_triangle");
+    for (width, height) in RESOLUTIONS {
+        let triangle = screen_triangle(width, height);
+        let mut framebuffer = Framebuffer::new(width, height);
+        let id = format!("{width}x{height}");
+        group.throughput(Throughput::Elements((width * height / 4) as u64));
+
+        group.bench_function(BenchmarkId::new("flat", &id), |b| {
+            b.iter(|| {
+                framebuffer.clear();
+                framebuffer.draw_polygon(
+                    black_box(&triangle),
+                    None,
+                    Rgba8::from_argb(0xFFFF8000).to_linear(),
+                );
+            })
+        });
+        group.bench_function(BenchmarkId::new("textured", &id), |b| {
+            b.iter(|| {
+                framebuffer.clear();
+                framebuffer.draw_polygon(black_box(&triangle), Some(&texture), Rgba32F::WHITE);
+            })
         });
-    });
+    }
+    group.finish();
 }
 
-// Replace this with actual framebuffer initialization
-fn initialize_framebuffer() -> FramebufferType {
-    // Your framebuffer creation code here
+fn bench_full_frame(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render_scene");
+    group.sample_size(20);
+    for asset in &ASSETS {
+        let polygons = load_polygons(asset);
+        for (width, height) in RESOLUTIONS {
+            let camera = look_at(asset.camera_position, asset.target, width, height);
+            let mut framebuffer = Framebuffer::new(width, height);
+            group.bench_function(
+                BenchmarkId::new(asset.name, format!("{width}x{height}")),
+                |b| b.iter(|| render_scene(black_box(&polygons), &camera, &mut framebuffer)),
+            );
+        }
+    }
+    group.finish();
 }
 
-criterion_group!(benches, benchmark_render_scene);
+criterion_group!(
+    benches,
+    bench_obj_parsing,
+    bench_process_faces,
+    bench_projection,
+    bench_triangulation,
+    bench_rasterization,
+    bench_full_frame
+);
 criterion_main!(benches);
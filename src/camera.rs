@@ -1,4 +1,97 @@
-use crate::{Matrix4x4, Point, point::cross_product, point::dot_product, point::normalize};
+use crate::collision::{Capsule, CollisionMesh};
+use crate::cvar::{Cvar, Cvars};
+use crate::input::{Action, ActionState};
+use crate::{Mat3, Mat4, Point, Quat};
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+use std::sync::Arc;
+
+/// Art der Projektion
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    Perspective,                  // Öffnungswinkel aus `Camera::fov`
+    Orthographic { height: f32 }, // Parallelprojektion, sichtbare Bildhöhe in Welteinheiten
+}
+
+impl Projection {
+    ///Rechnet die gespeicherte Tiefe (0 an der Near-, 1 an der Far-Plane) in die Entfernung
+    ///entlang der Blickrichtung um
+    #[inline(always)]
+    pub fn view_depth(&self, depth: f32, near: f32, far: f32) -> f32 {
+        match self {
+            Projection::Perspective => far * near / (far - depth * (far - near)),
+            Projection::Orthographic { .. } => near + depth * (far - near),
+        }
+    }
+}
+
+/// Feste Ansichten wie in technischen Zeichnungen. Vorne ist -Z, rechts +X und oben +Y
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StandardView {
+    Front,
+    Back,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Isometric, // Von vorne rechts oben, alle drei Achsen gleich verkürzt
+}
+
+impl StandardView {
+    pub const ALL: [StandardView; 7] = [
+        StandardView::Front,
+        StandardView::Back,
+        StandardView::Left,
+        StandardView::Right,
+        StandardView::Top,
+        StandardView::Bottom,
+        StandardView::Isometric,
+    ];
+
+    ///Gier- und Nickwinkel wie `Camera::yaw` und `Camera::pitch`. Oben und unten liegt
+    ///+X rechts im Bild
+    pub fn angles(self) -> (f32, f32) {
+        match self {
+            StandardView::Front => (-FRAC_PI_2, 0.0),
+            StandardView::Back => (FRAC_PI_2, 0.0),
+            StandardView::Left => (PI, 0.0),
+            StandardView::Right => (0.0, 0.0),
+            StandardView::Top => (-FRAC_PI_2, FRAC_PI_2),
+            StandardView::Bottom => (-FRAC_PI_2, -FRAC_PI_2),
+            StandardView::Isometric => (-FRAC_PI_4, (1.0 / 2f32.sqrt()).atan()),
+        }
+    }
+}
+
+/// Parameter der Ego-Bewegung in `Camera::update_movement`, zur Laufzeit über die
+/// Konsolenvariablen `sv_*` änderbar, siehe `Camera::register_cvars`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovementSettings {
+    pub max_speed: f32,            // Max speed on ground
+    pub air_max_speed: f32,        // Max speed while air strafing
+    pub accelerate: f32,           // Ground acceleration
+    pub air_accelerate: f32,       // Air strafing acceleration
+    pub friction: f32,             // Ground friction
+    pub jump_strength: f32,        // Jump power
+    pub air_drag: f32,             // Air drag to slow player slightly in air
+    pub diagonal_speed_scale: f32, // Slightly faster diagonal movement
+    pub strafe_boost_factor: f32,  // Small boost for strafing on the ground
+}
+
+impl Default for MovementSettings {
+    fn default() -> Self {
+        Self {
+            max_speed: 320.0,
+            air_max_speed: 950.0,
+            accelerate: 5.5,
+            air_accelerate: 12.0,
+            friction: 0.6,
+            jump_strength: 27.0,
+            air_drag: 0.01,
+            diagonal_speed_scale: 1.1,
+            strafe_boost_factor: 0.02,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Camera {
@@ -9,16 +102,31 @@ pub struct Camera {
     pub aspect_ratio: f32, // Breite / Höhe des Fensters
     pub near: f32,         // Near-Clipping-Plane
     pub far: f32,          // Far-Clipping-Plane
+    pub projection: Projection,
 
     pub velocity: Point,        // Current velocity of the camera
-    pub acceleration: f32,      // Acceleration for forward/backward/strafe
+    pub acceleration: f32,      // Multiplier for ground and air acceleration
     pub gravity: f32,           // Gravity applied during jumps
     pub vertical_velocity: f32, // Velocity for jumping
     pub is_jumping: bool,       // Whether the camera is currently jumping
+    pub movement: MovementSettings,
+
+    pub collision: Option<Arc<CollisionMesh>>, // Szene, gegen die die Kamera läuft, ohne gilt der Boden y = 0
+    pub capsule: Capsule,                      // Körper der Kamera für `collision`
+    pub on_ground: bool,                       // Stand beim letzten Schritt auf begehbarem Boden
 
     pub look_sensitivity: f32,
-    pub yaw: f32,
+    pub yaw: f32, // Eulerwinkel für Ego- und Orbit-Steuerung, siehe `update_forward`
     pub pitch: f32,
+    pub roll: f32,
+    pub orientation: Quat, // Drehung aus dem Kamera-Raum (Blick entlang +z) in die Welt
+}
+
+impl Default for Camera {
+    ///Im Ursprung mit `forward` entlang +z, siehe `with_default_lens`
+    fn default() -> Self {
+        Camera::with_default_lens(Point::new(0.0, 0.0, 0.0), Point::new(0.0, 0.0, 1.0))
+    }
 }
 
 impl Camera {
@@ -31,7 +139,7 @@ impl Camera {
         near: f32,
         far: f32,
     ) -> Self {
-        Self {
+        let mut camera = Self {
             position,
             forward,
             up,
@@ -39,106 +147,188 @@ impl Camera {
             aspect_ratio,
             near,
             far,
+            projection: Projection::Perspective,
 
             velocity: Point::new(0.0, 0.0, 0.0),
             acceleration: 1.0,
             gravity: 80.0,
             vertical_velocity: 0.0,
             is_jumping: false,
+            movement: MovementSettings::default(),
+
+            collision: None,
+            capsule: Capsule::default(),
+            on_ground: false,
 
             look_sensitivity: 0.001,
             yaw: 0.0,
             pitch: 0.0,
-        }
+            roll: 0.0,
+            orientation: Quat::IDENTITY,
+        };
+        // `forward` und `up` bleiben wie übergeben, damit die Ansicht exakt stimmt
+        let orientation = look_rotation(forward, up);
+        camera.set_orientation(orientation);
+        (camera.forward, camera.up) = (forward, up);
+        camera
+    }
+
+    ///Mit dem Objektiv des Fensters: 60° Öffnungswinkel, 16:9, Near 0.1 und Far 100, oben ist +Y
+    pub fn with_default_lens(position: Point, forward: Point) -> Self {
+        Camera::new(
+            position,
+            forward,
+            Point::new(0.0, 1.0, 0.0),
+            60.0,
+            16.0 / 9.0,
+            0.1,
+            100.0,
+        )
     }
 
-    pub fn view_matrix(&self) -> Matrix4x4 {
-        let forward = normalize(self.forward);
-        let right = normalize(cross_product(forward, self.up));
-        let up = cross_product(right, forward);
+    ///`forward` zeigt von der Blickrichtung weg, die Kamera schaut also auf `position - forward`
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::look_at(self.position, self.position - self.forward, self.up)
+    }
 
-        let tx = -dot_product(right, self.position);
-        let ty = -dot_product(up, self.position);
-        let tz = dot_product(forward, self.position);
+    ///Richtung in Weltkoordinaten durch den Bildpunkt (x und y von -1 bis 1, y nach oben).
+    ///Bei der Parallelprojektion ist das für alle Bildpunkte die Blickrichtung
+    pub fn view_ray(&self, x_ndc: f32, y_ndc: f32) -> Point {
+        let forward = self.forward.normalize();
+        if let Projection::Orthographic { .. } = self.projection {
+            return -forward;
+        }
+        let right = forward.cross(self.up).normalize();
+        let up = right.cross(forward);
+        let fov_rad = (self.fov.to_radians() / 2.0).tan();
 
-        Matrix4x4 {
-            data: [
-                [right.x, up.x, -forward.x, 0.0],
-                [right.y, up.y, -forward.y, 0.0],
-                [right.z, up.z, -forward.z, 0.0],
-                [tx, ty, tz, 1.0],
-            ],
+        // `forward` zeigt von der Blickrichtung weg, siehe `view_matrix`
+        (-forward + right * (x_ndc * fov_rad * self.aspect_ratio) + up * (y_ndc * fov_rad))
+            .normalize()
+    }
+
+    ///Richtung von `point` zur Kamera, bei der Parallelprojektion überall gleich
+    pub fn to_camera(&self, point: Point) -> Point {
+        match self.projection {
+            Projection::Perspective => self.position - point,
+            Projection::Orthographic { .. } => self.forward.normalize(),
         }
     }
 
-    pub fn projection_matrix(&self) -> Matrix4x4 {
-        let fov_rad = (self.fov.to_radians() / 2.0).tan();
-        Matrix4x4 {
-            data: [
-                [1.0 / (self.aspect_ratio * fov_rad), 0.0, 0.0, 0.0],
-                [0.0, 1.0 / fov_rad, 0.0, 0.0],
-                [0.0, 0.0, self.far / (self.far - self.near), 1.0],
-                [
-                    0.0,
-                    0.0,
-                    (-self.far * self.near) / (self.far - self.near),
-                    0.0,
-                ],
-            ],
+    pub fn projection_matrix(&self) -> Mat4 {
+        match self.projection {
+            Projection::Perspective => Mat4::perspective(
+                self.fov.to_radians(),
+                self.aspect_ratio,
+                self.near,
+                self.far,
+            ),
+            Projection::Orthographic { height } => {
+                let (half_width, half_height) = (height * self.aspect_ratio / 2.0, height / 2.0);
+                Mat4::orthographic(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    self.near,
+                    self.far,
+                )
+            }
         }
     }
+
+    ///Sichtbare Bildhöhe der Perspektive in der Entfernung `distance`
+    pub fn view_height_at(&self, distance: f32) -> f32 {
+        2.0 * distance * (self.fov.to_radians() / 2.0).tan()
+    }
+
+    ///Wechselt zwischen Perspektive und Parallelprojektion. In der Entfernung
+    ///`focus_distance` sind Objekte danach gleich groß wie vorher
+    pub fn toggle_projection(&mut self, focus_distance: f32) {
+        self.projection = match self.projection {
+            Projection::Perspective => Projection::Orthographic {
+                height: self.view_height_at(focus_distance),
+            },
+            Projection::Orthographic { .. } => Projection::Perspective,
+        };
+    }
+
+    ///Dreht die Kamera auf der Stelle in eine Standardansicht
+    pub fn set_standard_view(&mut self, view: StandardView) {
+        (self.yaw, self.pitch) = view.angles();
+        self.update_forward();
+    }
+
     pub fn move_forward(&mut self, distance: f32) {
-        self.position = self.position - self.forward.normalize() * distance;
+        self.position -= self.forward.normalize() * distance;
     }
 
     pub fn move_backward(&mut self, distance: f32) {
-        self.position = self.position + self.forward.normalize() * distance;
+        self.position += self.forward.normalize() * distance;
     }
 
     pub fn strafe_right(&mut self, distance: f32) {
         let right = self.forward.cross(self.up).normalize();
-        self.position = self.position + right * distance;
+        self.position += right * distance;
     }
 
     pub fn strafe_left(&mut self, distance: f32) {
         let right = self.forward.cross(self.up).normalize();
-        self.position = self.position - right * distance;
+        self.position -= right * distance;
     }
 
     pub fn move_up(&mut self, distance: f32) {
-        self.position = self.position - self.up.normalize() * distance;
+        self.position -= self.up.normalize() * distance;
     }
 
     pub fn move_down(&mut self, distance: f32) {
-        self.position = self.position + self.up.normalize() * distance;
+        self.position += self.up.normalize() * distance;
     }
 
     pub fn look_left(&mut self) {
-        let rotation_matrix = Matrix4x4::rotation_around_axis(self.up, self.look_sensitivity);
-        self.forward = rotation_matrix.multiply_point(&self.forward).normalize();
+        let rotation = Quat::from_axis_angle(self.up, -self.look_sensitivity);
+        self.set_orientation(rotation * self.orientation);
     }
 
     pub fn look_right(&mut self) {
-        let rotation_matrix = Matrix4x4::rotation_around_axis(self.up, -self.look_sensitivity);
-        self.forward = rotation_matrix.multiply_point(&self.forward).normalize();
+        let rotation = Quat::from_axis_angle(self.up, self.look_sensitivity);
+        self.set_orientation(rotation * self.orientation);
     }
 
+    ///Berechnet Drehung, `forward` und `up` aus `yaw`, `pitch` und `roll`.
+    ///`forward` = (cos yaw · cos pitch, sin pitch, sin yaw · cos pitch), `roll` dreht um die Blickachse
     pub fn update_forward(&mut self) {
-        self.forward.x = self.yaw.cos() * self.pitch.cos();
-        self.forward.y = self.pitch.sin();
-        self.forward.z = self.yaw.sin() * self.pitch.cos();
+        self.orientation = euler_orientation(self.yaw, self.pitch, self.roll);
+        self.apply_orientation();
+    }
 
+    ///Setzt die Drehung direkt, z.B. für den Flugmodus, und hält die Eulerwinkel passend
+    pub fn set_orientation(&mut self, orientation: Quat) {
+        self.orientation = orientation.normalize();
+        let (yaw, pitch, roll) = self.orientation.to_euler();
+        (self.yaw, self.pitch, self.roll) = (-yaw - FRAC_PI_2, pitch, roll);
+        self.apply_orientation();
+    }
+
+    ///Dreht um die eigenen Achsen der Kamera (x rechts, y oben, z in Blickrichtung)
+    pub fn rotate_local(&mut self, rotation: Quat) {
+        self.set_orientation(self.orientation * rotation);
+    }
+
+    fn apply_orientation(&mut self) {
+        // `forward` zeigt von der Blickrichtung weg
+        self.forward = -self.orientation.rotate(Point::new(0.0, 0.0, 1.0));
         if self.forward.x.abs() < 1e-6 {
             self.forward.x = 0.0; // Snap small x to 0
         }
         if self.forward.z.abs() < 1e-6 {
             self.forward.z = 0.0; // Snap small z to 0
         }
-
         self.forward = self.forward.normalize();
-
-        let right = self.forward.cross(Point::new(0.0, 1.0, 0.0)).normalize();
-        self.up = right.cross(self.forward).normalize();
+        self.up = self
+            .orientation
+            .rotate(Point::new(0.0, 1.0, 0.0))
+            .normalize();
     }
 
     pub fn look_around(&mut self, delta_x: f32, delta_y: f32) {
@@ -156,35 +346,148 @@ impl Camera {
         self.update_forward();
     }
 
+    ///Konsolenvariablen für Bewegung und Blick mit den Standardwerten einer neuen Kamera.
+    ///Die Bereiche halten die Bewegung bei 60 Schritten pro Sekunde stabil
+    pub fn register_cvars(cvars: &mut Cvars) {
+        let movement = MovementSettings::default();
+        for (name, value, (min, max), description) in [
+            (
+                "sv_maxspeed",
+                movement.max_speed,
+                (0.0, 10000.0),
+                "Max speed on ground",
+            ),
+            (
+                "sv_airmaxspeed",
+                movement.air_max_speed,
+                (0.0, 10000.0),
+                "Max speed while air strafing",
+            ),
+            // Mit `cl_acceleration` höchstens 5000, darüber schießt die Geschwindigkeit über
+            (
+                "sv_accelerate",
+                movement.accelerate,
+                (0.0, 1000.0),
+                "Ground acceleration",
+            ),
+            (
+                "sv_airaccelerate",
+                movement.air_accelerate,
+                (0.0, 10000.0),
+                "Air strafing acceleration",
+            ),
+            // Wird jeden Schritt am Boden multipliziert
+            (
+                "sv_friction",
+                movement.friction,
+                (0.0, 1.0),
+                "Ground friction, speed kept per tick",
+            ),
+            (
+                "sv_jumpspeed",
+                movement.jump_strength,
+                (0.0, 1000.0),
+                "Vertical speed at jump",
+            ),
+            // Ab 60 (1 / Zeitschritt) würde die Luftbewegung pro Schritt umgekehrt
+            (
+                "sv_airdrag",
+                movement.air_drag,
+                (0.0, 60.0),
+                "Air drag per second",
+            ),
+            (
+                "sv_diagonalscale",
+                movement.diagonal_speed_scale,
+                (0.0, 2.0),
+                "Ground speed scale",
+            ),
+            (
+                "sv_strafeboost",
+                movement.strafe_boost_factor,
+                (0.0, 1.0),
+                "Ground strafing boost",
+            ),
+            (
+                "sv_gravity",
+                80.0,
+                (0.0, 1000.0),
+                "Downward acceleration while airborne",
+            ),
+            (
+                "cl_acceleration",
+                1.0,
+                (0.0, 5.0),
+                "Multiplier for ground and air acceleration",
+            ),
+            (
+                "m_sensitivity",
+                0.001,
+                (0.0, 0.1),
+                "Mouse look in radians per pixel",
+            ),
+            (
+                "fov",
+                60.0,
+                (1.0, 179.0),
+                "Vertical field of view in degrees",
+            ),
+        ] {
+            cvars.register(Cvar::new(name, value, description).with_range(min, max));
+        }
+    }
+
+    ///Übernimmt die Werte aus `register_cvars`, fehlende Variablen ändern nichts
+    pub fn apply_cvars(&mut self, cvars: &Cvars) {
+        let float = |name: &str, current: f32| cvars.get_float(name).unwrap_or(current);
+        let movement = self.movement;
+        self.movement = MovementSettings {
+            max_speed: float("sv_maxspeed", movement.max_speed),
+            air_max_speed: float("sv_airmaxspeed", movement.air_max_speed),
+            accelerate: float("sv_accelerate", movement.accelerate),
+            air_accelerate: float("sv_airaccelerate", movement.air_accelerate),
+            friction: float("sv_friction", movement.friction),
+            jump_strength: float("sv_jumpspeed", movement.jump_strength),
+            air_drag: float("sv_airdrag", movement.air_drag),
+            diagonal_speed_scale: float("sv_diagonalscale", movement.diagonal_speed_scale),
+            strafe_boost_factor: float("sv_strafeboost", movement.strafe_boost_factor),
+        };
+        self.gravity = float("sv_gravity", self.gravity);
+        self.acceleration = float("cl_acceleration", self.acceleration);
+        self.look_sensitivity = float("m_sensitivity", self.look_sensitivity);
+        self.fov = float("fov", self.fov);
+    }
+
     pub fn update_movement(
         &mut self,
         delta_time: f32,
-        keys: &[bool; 256],
+        input: &ActionState,
         mouse_delta: (f32, f32),
     ) {
-        // Constants for movement and physics
-        let sv_maxspeed = 320.0; // Max speed on ground
-        let sv_air_maxspeed = 950.0; // Max speed while air strafing
-        let sv_accelerate = 5.5; // Ground acceleration
-        let sv_air_accelerate = 12.0; // Air strafing acceleration
-        let sv_friction = 0.6; // Ground friction
-        let jump_strength = 27.0; // Jump power
-        let air_drag = 0.01; // Air drag to slow player slightly in air
-        let diagonal_speed_scale = 1.1; // Slightly faster diagonal movement
-        let strafe_boost_factor = 0.02; // Small boost for strafing on the ground
+        let MovementSettings {
+            max_speed: sv_maxspeed,
+            air_max_speed: sv_air_maxspeed,
+            accelerate: sv_accelerate,
+            air_accelerate: sv_air_accelerate,
+            friction: sv_friction,
+            jump_strength,
+            air_drag,
+            diagonal_speed_scale,
+            strafe_boost_factor,
+        } = self.movement;
 
         let mut move_dir = Point::new(0.0, 0.0, 0.0);
-        if keys['W' as usize] {
-            move_dir = move_dir - self.forward;
+        if input.is_down(Action::MoveForward) {
+            move_dir -= self.forward;
         }
-        if keys['S' as usize] {
-            move_dir = move_dir + self.forward;
+        if input.is_down(Action::MoveBack) {
+            move_dir += self.forward;
         }
-        if keys['A' as usize] {
-            move_dir = move_dir - self.forward.cross(self.up);
+        if input.is_down(Action::MoveLeft) {
+            move_dir -= self.forward.cross(self.up);
         }
-        if keys['D' as usize] {
-            move_dir = move_dir + self.forward.cross(self.up);
+        if input.is_down(Action::MoveRight) {
+            move_dir += self.forward.cross(self.up);
         }
 
         move_dir.y = 0.0;
@@ -194,33 +497,33 @@ impl Camera {
         if !self.is_jumping && self.is_on_ground() {
             if move_dir.magnitude() > 0.0 {
                 let wish_vel = move_dir * sv_maxspeed * diagonal_speed_scale;
-                let accel = sv_accelerate * delta_time;
+                let accel = sv_accelerate * self.acceleration * delta_time;
                 self.velocity = self.velocity + (wish_vel - self.velocity) * accel * delta_time;
 
-                if keys['A' as usize] || keys['D' as usize] {
-                    self.velocity = self.velocity * (1.0 + (strafe_boost_factor * delta_time));
+                if input.is_down(Action::MoveLeft) || input.is_down(Action::MoveRight) {
+                    self.velocity *= 1.0 + (strafe_boost_factor * delta_time);
                 }
             }
 
-            if !keys['V' as usize] {
-                self.velocity = self.velocity * sv_friction;
+            if !input.is_down(Action::Jump) {
+                self.velocity *= sv_friction;
             } else {
                 self.is_jumping = true;
             }
 
-            if keys['V' as usize] {
+            if input.is_down(Action::Jump) {
                 self.vertical_velocity = jump_strength;
             }
         } else {
             // Air strafing logic
             if move_dir.magnitude() > 0.0 {
-                let air_accel = sv_air_accelerate * delta_time;
+                let air_accel = sv_air_accelerate * self.acceleration * delta_time;
                 let wish_vel = move_dir * sv_air_maxspeed;
                 self.velocity = self.velocity + (wish_vel - self.velocity).clamp_length(air_accel);
             }
 
             // Apply slight air drag for balance
-            self.velocity = self.velocity * (1.0 - (air_drag * delta_time));
+            self.velocity *= 1.0 - (air_drag * delta_time);
 
             if self.velocity.magnitude() > sv_air_maxspeed {
                 self.velocity = self.velocity.normalize() * sv_air_maxspeed;
@@ -228,25 +531,75 @@ impl Camera {
 
             self.vertical_velocity -= self.gravity * delta_time;
 
-            // Update position based on velocity
-            self.position.y += self.vertical_velocity * delta_time;
+            if self.collision.is_none() {
+                // Update position based on velocity
+                self.position.y += self.vertical_velocity * delta_time;
 
-            // Check for landing
-            if self.position.y < 0.0 {
-                self.position.y = 0.0;
-                self.vertical_velocity = 0.0;
-                self.is_jumping = false;
+                // Check for landing
+                if self.position.y < 0.0 {
+                    self.position.y = 0.0;
+                    self.vertical_velocity = 0.0;
+                    self.is_jumping = false;
+                }
             }
         }
 
-        // Update position based on horizontal velocity
-        self.position.x += self.velocity.x * delta_time;
-        self.position.z += self.velocity.z * delta_time;
+        if let Some(collision) = self.collision.clone() {
+            self.move_with_collision(&collision, delta_time);
+        } else {
+            // Update position based on horizontal velocity
+            self.position.x += self.velocity.x * delta_time;
+            self.position.z += self.velocity.z * delta_time;
+        }
 
         self.look_around(mouse_delta.0, mouse_delta.1);
     }
 
+    ///Bewegt die Kapsel durch die Szene. An Wänden bleibt nur die Geschwindigkeit entlang der Wand
+    fn move_with_collision(&mut self, collision: &CollisionMesh, delta_time: f32) {
+        let motion =
+            Point::new(self.velocity.x, self.vertical_velocity, self.velocity.z) * delta_time;
+        let on_ground = self.on_ground && !self.is_jumping;
+        let movement = collision.move_capsule(self.position, motion, &self.capsule, on_ground);
+
+        self.position = movement.position;
+        if delta_time > 0.0 {
+            self.velocity.x = movement.horizontal.x / delta_time;
+            self.velocity.z = movement.horizontal.z / delta_time;
+        }
+        if movement.hit_ceiling && self.vertical_velocity > 0.0 {
+            self.vertical_velocity = 0.0;
+        }
+
+        // Beim Absprung zählt der Boden unter den Füßen noch nicht
+        self.on_ground = movement.ground.is_some() && self.vertical_velocity <= 0.0;
+        if self.on_ground {
+            self.vertical_velocity = 0.0;
+            self.is_jumping = false;
+        }
+    }
+
     fn is_on_ground(&self) -> bool {
-        self.position.y <= 0.0
+        match self.collision {
+            Some(_) => self.on_ground,
+            None => self.position.y <= 0.0,
+        }
+    }
+}
+
+///Drehung aus den Eulerwinkeln von `Camera::update_forward`
+pub(crate) fn euler_orientation(yaw: f32, pitch: f32, roll: f32) -> Quat {
+    // Bei yaw = -90° schaut die Kamera entlang +z, dort ist der Quaternion-Gierwinkel 0
+    Quat::from_euler(-yaw - FRAC_PI_2, pitch, roll)
+}
+
+///Drehung zu einer Kamera mit `forward` (von der Blickrichtung weg) und ungefährem `up`.
+///Schaut die Kamera entlang `up`, bleibt es bei keiner Drehung
+pub(crate) fn look_rotation(forward: Point, up: Point) -> Quat {
+    let view = -forward.normalize();
+    let right = forward.cross(up).normalize();
+    if !right.x.is_finite() || !view.x.is_finite() {
+        return Quat::IDENTITY;
     }
+    Quat::from_mat3(&Mat3::from_rows([right, view.cross(right), view]))
 }
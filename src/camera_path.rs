@@ -0,0 +1,422 @@
+use crate::camera::{euler_orientation, look_rotation};
+use crate::{Camera, Point, Quat};
+use std::f32::consts::TAU;
+use std::ops::{Add, Mul, Sub};
+use std::path::Path;
+
+/// Verlauf innerhalb eines Abschnitts zwischen zwei Schlüsseln
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,    // Langsam losfahren
+    EaseOut,   // Langsam ankommen
+    EaseInOut, // Beides, die Kamera hält an jedem Schlüssel kurz an
+}
+
+impl Easing {
+    ///Bildet den Anteil 0 bis 1 am Abschnitt auf den Anteil am Weg ab
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "linear" => Some(Easing::Linear),
+            "in" => Some(Easing::EaseIn),
+            "out" => Some(Easing::EaseOut),
+            "inout" => Some(Easing::EaseInOut),
+            _ => None,
+        }
+    }
+}
+
+/// Kurve durch die Positionen, Drehungen und Öffnungswinkel der Schlüssel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    Linear,
+    #[default]
+    CatmullRom, // Glatt durch alle Schlüssel, Tangenten aus den Nachbarn
+    Bezier, // Wie Catmull-Rom, aber mit den Griffen der Schlüssel, wo angegeben
+}
+
+/// Position, Drehung und Öffnungswinkel der Kamera zu einem Zeitpunkt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraPose {
+    pub position: Point,
+    pub orientation: Quat, // Wie `Camera::orientation`
+    pub fov: f32,          // In Grad
+}
+
+impl CameraPose {
+    ///Setzt die Kamera auf die Pose, eine laufende Bewegung wird angehalten
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.position = self.position;
+        camera.set_orientation(self.orientation);
+        camera.fov = self.fov;
+        camera.velocity = Point::new(0.0, 0.0, 0.0);
+        camera.vertical_velocity = 0.0;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    pub time: f32, // Sekunden ab Beginn
+    pub position: Point,
+    pub orientation: Quat,
+    pub fov: f32,
+    pub easing: Easing, // Für den Abschnitt bis zum nächsten Schlüssel
+    pub handles: Option<(Point, Point)>, // Bézier-Griffe davor und danach, relativ zu `position`
+}
+
+impl Keyframe {
+    pub fn new(time: f32, position: Point, orientation: Quat, fov: f32) -> Self {
+        Keyframe {
+            time,
+            position,
+            orientation,
+            fov,
+            easing: Easing::Linear,
+            handles: None,
+        }
+    }
+
+    ///Schaut von `position` auf `target`, oben ist +Y
+    pub fn look_at(time: f32, position: Point, target: Point, fov: f32) -> Self {
+        let orientation = look_rotation(position - target, Point::new(0.0, 1.0, 0.0));
+        Keyframe::new(time, position, orientation, fov)
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn with_handles(mut self, incoming: Point, outgoing: Point) -> Self {
+        self.handles = Some((incoming, outgoing));
+        self
+    }
+}
+
+/// Kamerafahrt aus Schlüsseln, z.B. für Drehteller-Ansichten und Benchmark-Flüge.
+/// Die Drehung folgt bei den Splines einer sphärischen Bézierkurve mit Catmull-Rom-Tangenten,
+/// linear wird zwischen zwei Schlüsseln sphärisch interpoliert
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CameraPath {
+    pub keyframes: Vec<Keyframe>, // Nach Zeit sortiert
+    pub interpolation: Interpolation,
+    pub looping: bool, // Beginnt am Ende von vorn, der letzte Schlüssel sollte dem ersten gleichen
+}
+
+impl CameraPath {
+    pub fn new(keyframes: Vec<Keyframe>) -> Self {
+        let mut path = CameraPath {
+            keyframes,
+            ..CameraPath::default()
+        };
+        path.sort();
+        path
+    }
+
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    ///Einmal um `target` herum in `duration` Sekunden, im Abstand `radius` und `height` darüber
+    pub fn turntable(target: Point, radius: f32, height: f32, duration: f32, fov: f32) -> Self {
+        const STEPS: usize = 16;
+        let keyframes = (0..=STEPS)
+            .map(|step| {
+                let angle = step as f32 / STEPS as f32 * TAU;
+                let offset = Point::new(angle.sin() * radius, height, -angle.cos() * radius);
+                let time = step as f32 / STEPS as f32 * duration;
+                Keyframe::look_at(time, target + offset, target, fov)
+            })
+            .collect();
+        CameraPath::new(keyframes).looping()
+    }
+
+    pub fn sort(&mut self) {
+        self.keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+    }
+
+    pub fn start(&self) -> f32 {
+        self.keyframes.first().map_or(0.0, |key| key.time)
+    }
+
+    pub fn end(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |key| key.time)
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.end() - self.start()
+    }
+
+    ///Anzahl der Bilder bei `fps` Bildern pro Sekunde, das erste zeigt `start`. Beim Schleifen
+    ///fehlt das letzte, es gleicht dem ersten
+    pub fn frame_count(&self, fps: f32) -> usize {
+        if self.keyframes.is_empty() {
+            return 0;
+        }
+        let frames = (self.duration() * fps - 1e-3).ceil().max(0.0) as usize;
+        if self.looping {
+            frames.max(1)
+        } else {
+            frames + 1
+        }
+    }
+
+    ///Pose zum Zeitpunkt `time`, davor und danach die erste bzw. letzte, beim Schleifen
+    ///wiederholt. Ohne Schlüssel `None`
+    pub fn sample(&self, time: f32) -> Option<CameraPose> {
+        let keys = &self.keyframes;
+        let last = keys.len().checked_sub(1)?;
+        let duration = self.duration();
+        let time = if self.looping && duration > 0.0 {
+            self.start() + (time - self.start()).rem_euclid(duration)
+        } else {
+            time.clamp(self.start(), self.end())
+        };
+
+        let index = keys[..last]
+            .iter()
+            .rposition(|key| key.time <= time)
+            .unwrap_or(0);
+        let (a, b) = (&keys[index], &keys[(index + 1).min(last)]);
+        let span = b.time - a.time;
+        let t = if span > 0.0 {
+            a.easing.apply((time - a.time) / span)
+        } else {
+            1.0
+        };
+
+        let (position, orientation, fov) = match self.interpolation {
+            Interpolation::Linear => (
+                a.position + (b.position - a.position) * t,
+                a.orientation.slerp(b.orientation, t),
+                a.fov + (b.fov - a.fov) * t,
+            ),
+            Interpolation::CatmullRom | Interpolation::Bezier => {
+                let position_tangents = (
+                    self.tangent(index, |key| key.position),
+                    self.tangent(index + 1, |key| key.position),
+                );
+                // Kontrollpunkte relativ zu `a` und `b`, bei Bézier die Griffe, wo vorhanden
+                let mut handles = (
+                    position_tangents.0 * (span / 3.0),
+                    position_tangents.1 * (-span / 3.0),
+                );
+                if self.interpolation == Interpolation::Bezier {
+                    if let Some((_, outgoing)) = a.handles {
+                        handles.0 = outgoing;
+                    }
+                    if let Some((incoming, _)) = b.handles {
+                        handles.1 = incoming;
+                    }
+                }
+                let fov_tangents = (
+                    self.tangent(index, |key| key.fov),
+                    self.tangent(index + 1, |key| key.fov),
+                );
+                // Kontrolldrehungen im Drittel des Abschnitts, wie die Punkte der Position
+                let turn_rates = (self.turn_rate(index), self.turn_rate(index + 1));
+                let controls = (
+                    a.orientation * Quat::from_rotation_vector(turn_rates.0 * (span / 3.0)),
+                    b.orientation * Quat::from_rotation_vector(turn_rates.1 * (-span / 3.0)),
+                );
+                (
+                    bezier(
+                        a.position,
+                        a.position + handles.0,
+                        b.position + handles.1,
+                        b.position,
+                        t,
+                    ),
+                    spherical_bezier(a.orientation, controls.0, controls.1, b.orientation, t),
+                    bezier(
+                        a.fov,
+                        a.fov + fov_tangents.0 * (span / 3.0),
+                        b.fov - fov_tangents.1 * (span / 3.0),
+                        b.fov,
+                        t,
+                    ),
+                )
+            }
+        };
+
+        Some(CameraPose {
+            position,
+            orientation,
+            fov,
+        })
+    }
+
+    ///Änderung pro Sekunde am Schlüssel `index` aus seinen Nachbarn, siehe `neighbours`
+    fn tangent<T>(&self, index: usize, value: impl Fn(&Keyframe) -> T) -> T
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T>,
+    {
+        let ((previous, previous_time), (next, next_time)) = self.neighbours(index);
+        let (previous, next) = (
+            value(&self.keyframes[previous]),
+            value(&self.keyframes[next]),
+        );
+        let span = next_time - previous_time;
+        if span > 0.0 {
+            (next - previous) * (1.0 / span)
+        } else {
+            (next - previous) * 0.0
+        }
+    }
+
+    ///Drehgeschwindigkeit am Schlüssel `index` als Rotationsvektor pro Sekunde im Raum der
+    ///Kamera, wie `tangent` aus den Drehungen zu den Nachbarn
+    fn turn_rate(&self, index: usize) -> Point {
+        let ((previous, previous_time), (next, next_time)) = self.neighbours(index);
+        let center = self.keyframes[index.min(self.keyframes.len() - 1)].orientation;
+        let towards = |other: usize| {
+            (center.conjugate() * self.keyframes[other].orientation).to_rotation_vector()
+        };
+        let span = next_time - previous_time;
+        if span > 0.0 {
+            (towards(next) - towards(previous)) * (1.0 / span)
+        } else {
+            Point::new(0.0, 0.0, 0.0)
+        }
+    }
+
+    ///Vorgänger und Nachfolger des Schlüssels `index` mit ihren Zeiten. An den Enden ist das
+    ///der Schlüssel selbst, beim Schleifen zählt der vorletzte Schlüssel als Vorgänger des ersten
+    fn neighbours(&self, index: usize) -> ((usize, f32), (usize, f32)) {
+        let keys = &self.keyframes;
+        let last = keys.len() - 1;
+        let duration = self.duration();
+        let wrap = self.looping && last >= 2 && duration > 0.0;
+        let previous = match index {
+            0 if wrap => (last - 1, keys[last - 1].time - duration),
+            0 => (0, keys[0].time),
+            _ => (index - 1, keys[index - 1].time),
+        };
+        let next = match index {
+            _ if index >= last && wrap => (1, keys[1].time + duration),
+            _ if index >= last => (last, keys[last].time),
+            _ => (index + 1, keys[index + 1].time),
+        };
+        (previous, next)
+    }
+
+    ///Textformat, eine Anweisung pro Zeile, `//` leitet Kommentare ein:
+    ///`interpolation linear|catmullrom|bezier`, `loop`,
+    ///`key zeit x y z look zx zy zz [fov grad] [ease linear|in|out|inout]` bzw. statt `look`
+    ///`angles gier nick roll` in Grad wie `Camera::yaw`, und `handles ix iy iz ax ay az`
+    ///für die Bézier-Griffe des vorigen Schlüssels. Ohne `fov` gilt der des vorigen Schlüssels
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut path = CameraPath::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split("//").next().unwrap_or("");
+            path.parse_line(line)
+                .map_err(|error| format!("line {}: {error}", number + 1))?;
+        }
+        path.sort();
+        Ok(path)
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<(), String> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        let number = |word: &str| {
+            word.parse::<f32>()
+                .map_err(|_| format!("\"{word}\" is not a number"))
+        };
+        let point = |words: &[&str]| -> Result<Point, String> {
+            match words {
+                [x, y, z, ..] => Ok(Point::new(number(x)?, number(y)?, number(z)?)),
+                _ => Err("Expected x y z".to_string()),
+            }
+        };
+        match words.as_slice() {
+            [] => {}
+            ["interpolation", kind] => {
+                self.interpolation = match *kind {
+                    "linear" => Interpolation::Linear,
+                    "catmullrom" => Interpolation::CatmullRom,
+                    "bezier" => Interpolation::Bezier,
+                    _ => return Err(format!("Unknown interpolation: {kind}")),
+                }
+            }
+            ["loop"] => self.looping = true,
+            ["handles", rest @ ..] if rest.len() == 6 => {
+                let key = self
+                    .keyframes
+                    .last_mut()
+                    .ok_or("handles without a previous key")?;
+                key.handles = Some((point(&rest[..3])?, point(&rest[3..])?));
+            }
+            ["key", time, rest @ ..] if rest.len() >= 7 => {
+                let time = number(time)?;
+                let position = point(rest)?;
+                let orientation = match rest[3] {
+                    "look" => {
+                        look_rotation(position - point(&rest[4..])?, Point::new(0.0, 1.0, 0.0))
+                    }
+                    "angles" => {
+                        let angles = point(&rest[4..])?;
+                        euler_orientation(
+                            angles.x.to_radians(),
+                            angles.y.to_radians(),
+                            angles.z.to_radians(),
+                        )
+                    }
+                    other => return Err(format!("Expected look or angles, got \"{other}\"")),
+                };
+                let fov = self.keyframes.last().map_or(60.0, |key| key.fov);
+                let mut key = Keyframe::new(time, position, orientation, fov);
+                for option in rest[7..].chunks(2) {
+                    match option {
+                        ["fov", value] => key.fov = number(value)?,
+                        ["ease", value] => {
+                            key.easing = Easing::from_name(value)
+                                .ok_or_else(|| format!("Unknown easing: {value}"))?
+                        }
+                        _ => return Err(format!("Unknown key option: {}", option.join(" "))),
+                    }
+                }
+                self.keyframes.push(key);
+            }
+            _ => return Err(format!("Unknown path command: {}", line.trim())),
+        }
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("Failed to read camera path: {}", e))?;
+        Self::parse(&text)
+    }
+}
+
+///De Casteljau mit `slerp` statt Strecken, die Bézierkurve auf der Kugel der Drehungen
+fn spherical_bezier(q0: Quat, q1: Quat, q2: Quat, q3: Quat, t: f32) -> Quat {
+    let (a, b, c) = (q0.slerp(q1, t), q1.slerp(q2, t), q2.slerp(q3, t));
+    a.slerp(b, t).slerp(b.slerp(c, t), t)
+}
+
+///Kubische Bézierkurve durch `p0` und `p3` mit den Kontrollpunkten `p1` und `p2`
+fn bezier<T>(p0: T, p1: T, p2: T, p3: T, t: f32) -> T
+where
+    T: Copy + Add<Output = T> + Mul<f32, Output = T>,
+{
+    let s = 1.0 - t;
+    p0 * (s * s * s) + p1 * (3.0 * s * s * t) + p2 * (3.0 * s * t * t) + p3 * (t * t * t)
+}
@@ -0,0 +1,490 @@
+use crate::{Point, Polygon};
+use std::collections::HashMap;
+use std::f32::consts::FRAC_PI_4;
+
+/// Dreiecke, die mehr Zellen überdecken, landen in einer Liste, die immer geprüft wird
+const MAX_CELLS_PER_TRIANGLE: usize = 64;
+/// Größere Suchbereiche prüfen einfach alle Dreiecke
+const MAX_QUERY_CELLS: usize = 4096;
+/// Wie oft eine Bewegung an Flächen abgelenkt wird, bevor der Rest verfällt
+const MAX_SLIDES: usize = 4;
+/// Abstand zur Oberfläche nach einem Treffer, relativ zum Radius
+const SKIN: f32 = 0.01;
+const EPSILON: f32 = 1e-6;
+
+/// Form des Spielers: eine senkrechte Kapsel, der Mittelpunkt der oberen Kugel liegt im Auge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Capsule {
+    pub radius: f32,
+    pub height: f32,      // Vom Auge bis zu den Füßen
+    pub step_height: f32, // Höchste Stufe, die ohne Springen überwunden wird
+    pub max_slope: f32,   // Steilster begehbarer Hang, in Radiant
+}
+
+impl Default for Capsule {
+    fn default() -> Self {
+        Self {
+            radius: 0.3,
+            height: 1.7,
+            step_height: 0.4,
+            max_slope: FRAC_PI_4,
+        }
+    }
+}
+
+impl Capsule {
+    ///Ob eine Fläche mit dieser Normale als Boden gilt
+    pub fn is_walkable(&self, normal: Point) -> bool {
+        normal.y >= self.max_slope.cos() - EPSILON
+    }
+
+    ///Die Kapsel wird als Kette sich überlappender Kugeln getestet, das sind ihre Höhen relativ zum Auge
+    fn sphere_offsets(&self) -> Vec<f32> {
+        let length = (self.height - self.radius).max(0.0);
+        let count = (length / self.radius).ceil() as usize + 1;
+        (0..count)
+            .map(|i| -length * i as f32 / (count - 1).max(1) as f32)
+            .collect()
+    }
+
+    fn skin(&self) -> f32 {
+        self.radius * SKIN
+    }
+}
+
+/// Berührung einer bewegten Kugel oder Kapsel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+    pub time: f32,      // Anteil der Bewegung bis zur Berührung, von 0 bis 1
+    pub point: Point,   // Berührpunkt auf der Oberfläche
+    pub normal: Point,  // Von der Oberfläche zur Kugel, normalisiert
+    pub surface: Point, // Flächennormale des getroffenen Dreiecks, zur Kugel hin. Entscheidet über begehbar
+}
+
+/// Ergebnis von `CollisionMesh::move_capsule`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Movement {
+    pub position: Point,
+    pub horizontal: Point, // Tatsächlich zurückgelegter waagrechter Weg, nach dem Gleiten an Wänden
+    pub ground: Option<Point>, // Normale des begehbaren Bodens unter den Füßen
+    pub hit_ceiling: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Triangle {
+    a: Point,
+    b: Point,
+    c: Point,
+    normal: Point,
+}
+
+impl Triangle {
+    fn new(a: Point, b: Point, c: Point) -> Option<Self> {
+        let normal = (b - a).cross(c - a);
+        (normal.magnitude() > EPSILON).then(|| Self {
+            a,
+            b,
+            c,
+            normal: normal.normalize(),
+        })
+    }
+
+    fn bounds(&self) -> (Point, Point) {
+        let min = Point::new(
+            self.a.x.min(self.b.x).min(self.c.x),
+            self.a.y.min(self.b.y).min(self.c.y),
+            self.a.z.min(self.b.z).min(self.c.z),
+        );
+        let max = Point::new(
+            self.a.x.max(self.b.x).max(self.c.x),
+            self.a.y.max(self.b.y).max(self.c.y),
+            self.a.z.max(self.b.z).max(self.c.z),
+        );
+        (min, max)
+    }
+
+    ///Punkt in der Ebene des Dreiecks liegt innerhalb der drei Kanten
+    fn contains(&self, point: Point) -> bool {
+        [(self.a, self.b), (self.b, self.c), (self.c, self.a)]
+            .iter()
+            .all(|&(from, to)| (to - from).cross(point - from).dot(self.normal) >= -EPSILON)
+    }
+
+    ///Swept-Sphere-Test: Fläche, dann Ecken und Kanten
+    fn sweep(&self, center: Point, radius: f32, motion: Point) -> Option<Hit> {
+        // Beidseitig: Normale zeigt immer zur Seite der Kugel
+        let mut normal = self.normal;
+        let mut distance = normal.dot(center - self.a);
+        if distance < 0.0 {
+            normal = -normal;
+            distance = -distance;
+        }
+        let approach = normal.dot(motion);
+        if distance >= radius && distance + approach >= radius {
+            return None;
+        }
+
+        if distance >= radius {
+            let time = (distance - radius) / -approach;
+            let point = center + motion * time - normal * radius;
+            if self.contains(point) {
+                return Some(Hit {
+                    time,
+                    point,
+                    normal,
+                    surface: normal,
+                });
+            }
+        } else {
+            // Startet schon in Berührung, nur die Bewegung in die Fläche hinein wird gestoppt
+            let point = center - normal * distance;
+            if self.contains(point) {
+                return (approach < -EPSILON).then_some(Hit {
+                    time: 0.0,
+                    point,
+                    normal,
+                    surface: normal,
+                });
+            }
+        }
+
+        let motion_sq = motion.dot(motion);
+        if motion_sq < EPSILON * EPSILON {
+            return None;
+        }
+        let mut nearest: Option<(f32, Point)> = None;
+
+        for vertex in [self.a, self.b, self.c] {
+            let offset = center - vertex;
+            let limit = nearest.map_or(1.0, |(time, _)| time);
+            if let Some(time) = lowest_root(
+                motion_sq,
+                2.0 * motion.dot(offset),
+                offset.dot(offset) - radius * radius,
+                limit,
+            ) {
+                nearest = Some((time, vertex));
+            }
+        }
+
+        for (from, to) in [(self.a, self.b), (self.b, self.c), (self.c, self.a)] {
+            let edge = to - from;
+            let base = from - center;
+            let edge_sq = edge.dot(edge);
+            let edge_motion = edge.dot(motion);
+            let edge_base = edge.dot(base);
+            // Abstand zur unendlichen Geraden durch die Kante gleich dem Radius
+            let limit = nearest.map_or(1.0, |(time, _)| time);
+            let root = lowest_root(
+                edge_sq * motion_sq - edge_motion * edge_motion,
+                2.0 * (edge_motion * edge_base - edge_sq * motion.dot(base)),
+                edge_sq * (base.dot(base) - radius * radius) - edge_base * edge_base,
+                limit,
+            );
+            if let Some(time) = root {
+                let along = (edge_motion * time - edge_base) / edge_sq;
+                if (0.0..=1.0).contains(&along) {
+                    nearest = Some((time, from + edge * along));
+                }
+            }
+        }
+
+        nearest.map(|(time, point)| Hit {
+            time,
+            point,
+            normal: (center + motion * time - point).normalize(),
+            surface: normal,
+        })
+    }
+}
+
+///Kleinste Nullstelle von a·t² + b·t + c im Bereich 0 bis `limit`. Ist c < 0, überlappen die
+///Körper schon, dann zählt das nur, wenn sie sich weiter annähern
+fn lowest_root(a: f32, b: f32, c: f32, limit: f32) -> Option<f32> {
+    if c < 0.0 {
+        return (b < -EPSILON).then_some(0.0);
+    }
+    if a < EPSILON * EPSILON {
+        return None;
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let time = (-b - discriminant.sqrt()) / (2.0 * a);
+    (0.0..=limit).contains(&time).then_some(time)
+}
+
+/// Dreiecke der Szene in einem gleichmäßigen Gitter, für Kollisionen der Kamera mit der Welt
+#[derive(Debug, Default)]
+pub struct CollisionMesh {
+    triangles: Vec<Triangle>,
+    cell_size: f32,
+    cells: HashMap<(i32, i32, i32), Vec<usize>>,
+    large: Vec<usize>,             // Zu groß für das Gitter, wird immer geprüft
+    pub ground_plane: Option<f32>, // Unendliche waagrechte Ebene in dieser Höhe, z.B. unter einem einzelnen Modell
+}
+
+impl CollisionMesh {
+    ///Zellengröße aus der mittleren Dreiecksgröße
+    pub fn new(polygons: &[Polygon]) -> Self {
+        let triangles = triangulate(polygons);
+        let extent: f32 = triangles
+            .iter()
+            .map(|triangle| {
+                let (min, max) = triangle.bounds();
+                (max - min).magnitude()
+            })
+            .sum();
+        let cell_size = if triangles.is_empty() {
+            1.0
+        } else {
+            (extent / triangles.len() as f32).max(0.01)
+        };
+        Self::build(triangles, cell_size)
+    }
+
+    pub fn with_cell_size(polygons: &[Polygon], cell_size: f32) -> Self {
+        Self::build(triangulate(polygons), cell_size)
+    }
+
+    pub fn with_ground_plane(mut self, height: f32) -> Self {
+        self.ground_plane = Some(height);
+        self
+    }
+
+    fn build(triangles: Vec<Triangle>, cell_size: f32) -> Self {
+        let mut mesh = Self {
+            triangles,
+            cell_size,
+            ..Default::default()
+        };
+        for (index, triangle) in mesh.triangles.iter().enumerate() {
+            let (min, max) = triangle.bounds();
+            let (min, max) = (mesh.cell(min), mesh.cell(max));
+            let count = (max.0 - min.0 + 1) as usize
+                * (max.1 - min.1 + 1) as usize
+                * (max.2 - min.2 + 1) as usize;
+            if count > MAX_CELLS_PER_TRIANGLE {
+                mesh.large.push(index);
+                continue;
+            }
+            for x in min.0..=max.0 {
+                for y in min.1..=max.1 {
+                    for z in min.2..=max.2 {
+                        mesh.cells.entry((x, y, z)).or_default().push(index);
+                    }
+                }
+            }
+        }
+        mesh
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    fn cell(&self, point: Point) -> (i32, i32, i32) {
+        (
+            (point.x / self.cell_size).floor() as i32,
+            (point.y / self.cell_size).floor() as i32,
+            (point.z / self.cell_size).floor() as i32,
+        )
+    }
+
+    ///Alle Dreiecke, deren Zellen den Quader berühren, jedes nur einmal
+    fn candidates(&self, min: Point, max: Point) -> Vec<usize> {
+        let (min, max) = (self.cell(min), self.cell(max));
+        let count = (max.0 - min.0 + 1) as usize
+            * (max.1 - min.1 + 1) as usize
+            * (max.2 - min.2 + 1) as usize;
+        if count > MAX_QUERY_CELLS {
+            return (0..self.triangles.len()).collect();
+        }
+        let mut indices = self.large.clone();
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                for z in min.2..=max.2 {
+                    if let Some(cell) = self.cells.get(&(x, y, z)) {
+                        indices.extend_from_slice(cell);
+                    }
+                }
+            }
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+
+    fn sweep_spheres(&self, centers: &[Point], radius: f32, motion: Point) -> Option<Hit> {
+        let (mut min, mut max) = (centers[0], centers[0]);
+        for &center in centers {
+            for p in [center, center + motion] {
+                min = Point::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+                max = Point::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+            }
+        }
+        let margin = Point::new(radius, radius, radius);
+        let candidates = self.candidates(min - margin, max + margin);
+
+        let mut nearest: Option<Hit> = None;
+        for &center in centers {
+            let hits = candidates
+                .iter()
+                .filter_map(|&index| self.triangles[index].sweep(center, radius, motion))
+                .chain(self.sweep_ground_plane(center, radius, motion));
+            for hit in hits {
+                // Auf einer gemeinsamen Kante zählt die flachere Fläche, damit man auf Stufen stehen kann
+                let closer = |nearest: Hit| {
+                    hit.time < nearest.time - EPSILON
+                        || (hit.time <= nearest.time + EPSILON && hit.surface.y > nearest.surface.y)
+                };
+                if nearest.is_none_or(closer) {
+                    nearest = Some(hit);
+                }
+            }
+        }
+        nearest
+    }
+
+    fn sweep_ground_plane(&self, center: Point, radius: f32, motion: Point) -> Option<Hit> {
+        let height = self.ground_plane?;
+        let distance = center.y - height;
+        if motion.y >= 0.0 || distance + motion.y >= radius {
+            return None;
+        }
+        let time = ((distance - radius) / -motion.y).max(0.0);
+        let mut point = center + motion * time;
+        point.y = height;
+        Some(Hit {
+            time,
+            point,
+            normal: Point::new(0.0, 1.0, 0.0),
+            surface: Point::new(0.0, 1.0, 0.0),
+        })
+    }
+
+    ///Erste Berührung einer Kugel, die sich um `motion` bewegt
+    pub fn sweep_sphere(&self, center: Point, radius: f32, motion: Point) -> Option<Hit> {
+        self.sweep_spheres(&[center], radius, motion)
+    }
+
+    ///Erste Berührung der Kapsel mit dem Auge in `eye`, die sich um `motion` bewegt
+    pub fn sweep_capsule(&self, eye: Point, capsule: &Capsule, motion: Point) -> Option<Hit> {
+        let centers: Vec<_> = capsule
+            .sphere_offsets()
+            .into_iter()
+            .map(|offset| eye + Point::new(0.0, offset, 0.0))
+            .collect();
+        self.sweep_spheres(&centers, capsule.radius, motion)
+    }
+
+    ///Bewegt die Kapsel bis zur ersten Berührung und lenkt den Rest entlang der getroffenen Fläche ab
+    fn slide(
+        &self,
+        mut eye: Point,
+        mut motion: Point,
+        capsule: &Capsule,
+        hits: &mut Vec<Hit>,
+    ) -> Point {
+        for _ in 0..MAX_SLIDES {
+            let length = motion.magnitude();
+            if length < EPSILON {
+                break;
+            }
+            let Some(hit) = self.sweep_capsule(eye, capsule, motion) else {
+                eye += motion;
+                break;
+            };
+            let travel = (hit.time * length - capsule.skin()).max(0.0);
+            eye += motion * (travel / length);
+            let remaining = motion * (1.0 - hit.time);
+            motion = remaining - hit.normal * remaining.dot(hit.normal);
+            hits.push(hit);
+        }
+        eye
+    }
+
+    ///Bewegt die Kapsel um `motion`: waagrecht mit Gleiten an Wänden und Treppensteigen, dann senkrecht.
+    ///`on_ground` hält die Kapsel beim Gehen bergab und über Stufen nach unten am Boden
+    pub fn move_capsule(
+        &self,
+        eye: Point,
+        motion: Point,
+        capsule: &Capsule,
+        on_ground: bool,
+    ) -> Movement {
+        let skin = capsule.skin();
+        let horizontal = Point::new(motion.x, 0.0, motion.z);
+        let mut hits = Vec::new();
+        let mut position = self.slide(eye, horizontal, capsule, &mut hits);
+
+        // Stufe: anheben, waagrecht bewegen und wieder auf den Boden absenken. Blockiert ist, was
+        // die Kapsel steil berührt, auch die Kante einer begehbaren Stufe
+        let blocked = hits.iter().any(|hit| !capsule.is_walkable(hit.normal));
+        if on_ground && blocked && capsule.step_height > 0.0 {
+            let mut step_hits = Vec::new();
+            let lift = Point::new(0.0, capsule.step_height, 0.0);
+            let raised = self.slide(eye, lift, capsule, &mut step_hits);
+            let across = self.slide(raised, horizontal, capsule, &mut step_hits);
+            let drop = Point::new(0.0, eye.y - raised.y, 0.0);
+            let landing = self
+                .sweep_capsule(across, capsule, drop)
+                .filter(|hit| capsule.is_walkable(hit.surface));
+            if let Some(hit) = landing {
+                let stepped = across + drop * hit.time + Point::new(0.0, skin, 0.0);
+                let progress = |p: Point| Point::new(p.x - eye.x, 0.0, p.z - eye.z).magnitude();
+                if progress(stepped) > progress(position) + skin {
+                    position = stepped;
+                }
+            }
+        }
+        let moved = Point::new(position.x - eye.x, 0.0, position.z - eye.z);
+
+        // Senkrecht: Sprung oder Fall. Begehbarer Boden und Decken stoppen, steile Hänge lenken ab
+        let mut hit_ceiling = false;
+        let vertical = Point::new(0.0, motion.y, 0.0);
+        if motion.y != 0.0 {
+            match self.sweep_capsule(position, capsule, vertical) {
+                None => position += vertical,
+                Some(hit) if capsule.is_walkable(hit.surface) || hit.normal.y < 0.0 => {
+                    hit_ceiling = hit.normal.y < 0.0;
+                    position.y += (motion.y.abs() * hit.time - skin).max(0.0) * motion.y.signum();
+                }
+                Some(_) => position = self.slide(position, vertical, capsule, &mut Vec::new()),
+            }
+        }
+
+        // Bodenkontakt. Wer schon steht, wird bis zur Stufenhöhe nach unten gezogen
+        let probe = if on_ground && motion.y <= 0.0 {
+            capsule.step_height.max(2.0 * skin)
+        } else {
+            2.0 * skin
+        };
+        let ground = self
+            .sweep_capsule(position, capsule, Point::new(0.0, -probe, 0.0))
+            .filter(|hit| capsule.is_walkable(hit.surface));
+        if let (Some(hit), true) = (ground, on_ground) {
+            position.y -= (hit.time * probe - skin).max(0.0);
+        }
+
+        Movement {
+            position,
+            horizontal: moved,
+            ground: ground.map(|hit| hit.surface),
+            hit_ceiling,
+        }
+    }
+}
+
+///Polygone als Fächer in Dreiecke zerlegen
+fn triangulate(polygons: &[Polygon]) -> Vec<Triangle> {
+    polygons
+        .iter()
+        .flat_map(|polygon| {
+            let vertices = &polygon.vertices;
+            (2..vertices.len())
+                .filter_map(move |i| Triangle::new(vertices[0], vertices[i - 1], vertices[i]))
+        })
+        .collect()
+}
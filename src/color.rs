@@ -0,0 +1,295 @@
+use lazy_static::lazy_static;
+use std::ops::{Add, Mul, Sub};
+
+/// Auflösung der Tabelle für linear -> sRGB, fein genug damit 8 Bit exakt zurückkommen
+const LINEAR_TO_SRGB_STEPS: usize = 4096;
+
+lazy_static! {
+    /// sRGB-Wert (0 - 255) -> linearer Wert (0.0 - 1.0)
+    static ref SRGB_TO_LINEAR: [f32; 256] =
+        std::array::from_fn(|i| srgb_to_linear(i as f32 / 255.0));
+
+    /// Linearer Wert in `LINEAR_TO_SRGB_STEPS` Stufen -> sRGB-Wert (0 - 255)
+    static ref LINEAR_TO_SRGB: Vec<u8> = (0..=LINEAR_TO_SRGB_STEPS)
+        .map(|i| {
+            let srgb = linear_to_srgb(i as f32 / LINEAR_TO_SRGB_STEPS as f32);
+            (srgb * 255.0).round() as u8
+        })
+        .collect();
+}
+
+/// In welchem Farbraum die Werte einer Textur gespeichert sind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorSpace {
+    #[default]
+    Srgb, // Farbtexturen und Bilddateien, werden beim Lesen linearisiert
+    Linear, // Daten wie Normalen oder Rauheit, werden unverändert gelesen
+}
+
+/// 8 Bit pro Kanal, so wie Texturen und PNGs gespeichert sind
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgba8 {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+/// Gleitkommafarbe mit 0.0 - 1.0 für LDR, Werte über 1.0 sind erlaubt (HDR)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rgba32F {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+/// Pixelformat des Framebuffers. Gerechnet wird immer linear mit `Rgba32F`,
+/// gespeichert wird im jeweiligen Format (`Rgba8` sRGB-kodiert, `Rgba32F` linear)
+pub trait Color: Copy + Default + PartialEq + Send + Sync + 'static {
+    /// Deckendes Schwarz, die Hintergrundfarbe beim Leeren
+    const BLACK: Self;
+
+    fn from_linear(color: Rgba32F) -> Self;
+    fn to_linear(self) -> Rgba32F;
+
+    ///Für die Ausgabe, Werte außerhalb von 0.0 - 1.0 werden abgeschnitten
+    fn to_srgb8(self) -> Rgba8 {
+        self.to_linear().to_srgb8()
+    }
+}
+
+impl Rgba8 {
+    pub const BLACK: Rgba8 = Rgba8::new(0, 0, 0, 255);
+    pub const WHITE: Rgba8 = Rgba8::new(255, 255, 255, 255);
+
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Rgba8 { r, g, b, a }
+    }
+
+    ///Aus gepacktem 0xAARRGGBB, wie es u.a. das Windows-Bitmap erwartet
+    pub const fn from_argb(argb: u32) -> Self {
+        Rgba8 {
+            r: (argb >> 16) as u8,
+            g: (argb >> 8) as u8,
+            b: argb as u8,
+            a: (argb >> 24) as u8,
+        }
+    }
+
+    ///Packt die Farbe als 0xAARRGGBB
+    pub const fn to_argb(self) -> u32 {
+        ((self.a as u32) << 24) | ((self.r as u32) << 16) | ((self.g as u32) << 8) | self.b as u32
+    }
+
+    ///Dekodiert sRGB nach linear über die Tabelle, Alpha ist immer linear
+    #[inline(always)]
+    pub fn to_linear(self) -> Rgba32F {
+        let table = &*SRGB_TO_LINEAR;
+        Rgba32F {
+            r: table[self.r as usize],
+            g: table[self.g as usize],
+            b: table[self.b as usize],
+            a: self.a as f32 / 255.0,
+        }
+    }
+}
+
+impl From<u32> for Rgba8 {
+    ///Farbwerte als Zahl sind immer 0xAARRGGBB
+    fn from(argb: u32) -> Self {
+        Rgba8::from_argb(argb)
+    }
+}
+
+impl From<[u8; 4]> for Rgba8 {
+    fn from([r, g, b, a]: [u8; 4]) -> Self {
+        Rgba8 { r, g, b, a }
+    }
+}
+
+impl From<Rgba8> for [u8; 4] {
+    fn from(color: Rgba8) -> Self {
+        [color.r, color.g, color.b, color.a]
+    }
+}
+
+impl Color for Rgba8 {
+    const BLACK: Self = Rgba8::BLACK;
+
+    #[inline(always)]
+    fn from_linear(color: Rgba32F) -> Self {
+        color.to_srgb8()
+    }
+
+    #[inline(always)]
+    fn to_linear(self) -> Rgba32F {
+        Rgba8::to_linear(self)
+    }
+
+    #[inline(always)]
+    fn to_srgb8(self) -> Rgba8 {
+        self
+    }
+}
+
+impl Rgba32F {
+    pub const BLACK: Rgba32F = Rgba32F::new(0.0, 0.0, 0.0, 1.0);
+    pub const WHITE: Rgba32F = Rgba32F::new(1.0, 1.0, 1.0, 1.0);
+
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Rgba32F { r, g, b, a }
+    }
+
+    ///Gleiche Farbe mit anderem Alpha
+    #[inline(always)]
+    pub fn with_alpha(self, a: f32) -> Self {
+        Rgba32F { a, ..self }
+    }
+
+    ///Lineare Interpolation aller Kanäle, `t = 0` ergibt `self`
+    #[inline(always)]
+    pub fn lerp(self, other: Rgba32F, t: f32) -> Self {
+        self + (other - self) * t
+    }
+
+    ///Wahrgenommene Helligkeit (Rec. 601 Gewichte)
+    #[inline(always)]
+    pub fn luma(self) -> f32 {
+        0.299 * self.r + 0.587 * self.g + 0.114 * self.b
+    }
+
+    ///Kodiert nach sRGB mit 8 Bit pro Kanal über die Tabelle,
+    ///Werte außerhalb von 0.0 - 1.0 werden abgeschnitten
+    #[inline(always)]
+    pub fn to_srgb8(self) -> Rgba8 {
+        let table = &*LINEAR_TO_SRGB;
+        let channel = |value: f32| {
+            let step = (value * LINEAR_TO_SRGB_STEPS as f32).round();
+            table[step.clamp(0.0, LINEAR_TO_SRGB_STEPS as f32) as usize]
+        };
+        Rgba8 {
+            r: channel(self.r),
+            g: channel(self.g),
+            b: channel(self.b),
+            a: (self.a * 255.0).round().clamp(0.0, 255.0) as u8,
+        }
+    }
+
+    ///Rundet auf 8 Bit pro Kanal ohne Umrechnung des Farbraums,
+    ///Werte außerhalb von 0.0 - 1.0 werden abgeschnitten
+    #[inline(always)]
+    pub fn to_rgba8(self) -> Rgba8 {
+        let channel = |value: f32| (value * 255.0).round().clamp(0.0, 255.0) as u8;
+        Rgba8 {
+            r: channel(self.r),
+            g: channel(self.g),
+            b: channel(self.b),
+            a: channel(self.a),
+        }
+    }
+}
+
+/// Ohne Umrechnung des Farbraums, für sRGB-Farben `Rgba8::to_linear` verwenden
+impl From<Rgba8> for Rgba32F {
+    #[inline(always)]
+    fn from(color: Rgba8) -> Self {
+        Rgba32F {
+            r: color.r as f32 / 255.0,
+            g: color.g as f32 / 255.0,
+            b: color.b as f32 / 255.0,
+            a: color.a as f32 / 255.0,
+        }
+    }
+}
+
+impl Color for Rgba32F {
+    const BLACK: Self = Rgba32F::BLACK;
+
+    #[inline(always)]
+    fn from_linear(color: Rgba32F) -> Self {
+        color
+    }
+
+    #[inline(always)]
+    fn to_linear(self) -> Rgba32F {
+        self
+    }
+}
+
+impl Add for Rgba32F {
+    type Output = Rgba32F;
+
+    #[inline(always)]
+    fn add(self, other: Rgba32F) -> Rgba32F {
+        Rgba32F::new(
+            self.r + other.r,
+            self.g + other.g,
+            self.b + other.b,
+            self.a + other.a,
+        )
+    }
+}
+
+impl Sub for Rgba32F {
+    type Output = Rgba32F;
+
+    #[inline(always)]
+    fn sub(self, other: Rgba32F) -> Rgba32F {
+        Rgba32F::new(
+            self.r - other.r,
+            self.g - other.g,
+            self.b - other.b,
+            self.a - other.a,
+        )
+    }
+}
+
+impl Mul<f32> for Rgba32F {
+    type Output = Rgba32F;
+
+    #[inline(always)]
+    fn mul(self, factor: f32) -> Rgba32F {
+        Rgba32F::new(
+            self.r * factor,
+            self.g * factor,
+            self.b * factor,
+            self.a * factor,
+        )
+    }
+}
+
+/// Komponentenweise, z.B. für multiplikatives Blending
+impl Mul for Rgba32F {
+    type Output = Rgba32F;
+
+    #[inline(always)]
+    fn mul(self, other: Rgba32F) -> Rgba32F {
+        Rgba32F::new(
+            self.r * other.r,
+            self.g * other.g,
+            self.b * other.b,
+            self.a * other.a,
+        )
+    }
+}
+
+///Exakte sRGB-Übertragungsfunktion, Grundlage für die Tabellen
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+///Umkehrung von `srgb_to_linear`
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
@@ -0,0 +1,185 @@
+use crate::Framebuffer;
+use crate::color::{Color, Rgba32F};
+use crate::cvar::Cvars;
+use crate::input::Bindings;
+use crate::text::{CHAR_ADVANCE, LINE_ADVANCE};
+use std::path::PathBuf;
+
+/// Ältere Ausgabezeilen werden verworfen
+const MAX_LOG_LINES: usize = 256;
+
+/// Konsole im Stil von Quake: Eingabezeile, Verlauf und Ausgabe, halbtransparent über dem Bild.
+/// Befehle gehen an `Cvars::execute`, `bind` und Verwandte an `Bindings::execute`,
+/// dazu kommen `clear`, `exec` und `writeconfig`
+#[derive(Debug)]
+pub struct Console {
+    pub open: bool,
+    pub input: String,
+    pub log: Vec<String>,
+    pub config_path: Option<PathBuf>, // Standarddatei für `exec` und `writeconfig`
+    pub scale: usize,                 // Vergrößerung der Schrift
+    pub background: Rgba32F,
+    pub text_color: Rgba32F,
+    history: Vec<String>,
+    history_index: usize, // Gleich `history.len()`, solange nicht im Verlauf geblättert wird
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self {
+            open: false,
+            input: String::new(),
+            log: Vec::new(),
+            config_path: None,
+            scale: 2,
+            background: Rgba32F::new(0.0, 0.0, 0.0, 0.75),
+            text_color: Rgba32F::new(0.9, 0.9, 0.9, 1.0),
+            history: Vec::new(),
+            history_index: 0,
+        }
+    }
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config_path = Some(path.into());
+        self
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    ///Hängt Text an die Ausgabe an, mehrzeiliger Text wird aufgeteilt
+    pub fn print(&mut self, text: &str) {
+        self.log.extend(text.lines().map(str::to_string));
+        let excess = self.log.len().saturating_sub(MAX_LOG_LINES);
+        self.log.drain(..excess);
+    }
+
+    ///Getipptes Zeichen, Steuerzeichen werden ignoriert
+    pub fn type_char(&mut self, c: char) {
+        if !c.is_control() {
+            self.input.push(c);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        self.input.pop();
+    }
+
+    pub fn history_previous(&mut self) {
+        if self.history_index > 0 {
+            self.history_index -= 1;
+            self.input = self.history[self.history_index].clone();
+        }
+    }
+
+    pub fn history_next(&mut self) {
+        if self.history_index < self.history.len() {
+            self.history_index += 1;
+            self.input = self
+                .history
+                .get(self.history_index)
+                .cloned()
+                .unwrap_or_default();
+        }
+    }
+
+    ///Vervollständigt den Namen am Anfang der Eingabe. Bei mehreren Treffern wird bis zum
+    ///gemeinsamen Anfang ergänzt und die Auswahl ausgegeben
+    pub fn complete(&mut self, cvars: &Cvars) {
+        let prefix = self.input.trim_start();
+        if prefix.contains(' ') {
+            return;
+        }
+        match cvars.complete(prefix).as_slice() {
+            [] => {}
+            [name] => self.input = format!("{name} "),
+            names => {
+                let common = names.iter().fold(names[0], |common, name| {
+                    // Länge in Bytes, Namen dürfen auch Umlaute enthalten
+                    let length = common
+                        .char_indices()
+                        .zip(name.chars())
+                        .take_while(|((_, a), b)| a == b)
+                        .last()
+                        .map_or(0, |((index, c), _)| index + c.len_utf8());
+                    &common[..length]
+                });
+                let list = names.join("  ");
+                self.input = common.to_string();
+                self.print(&list);
+            }
+        }
+    }
+
+    ///Führt die Eingabezeile aus und schreibt Befehl und Ergebnis in die Ausgabe
+    pub fn submit(&mut self, cvars: &mut Cvars, bindings: &mut Bindings) {
+        let line = std::mem::take(&mut self.input);
+        self.print(&format!("] {line}"));
+        if !line.trim().is_empty() && self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+        }
+        self.history_index = self.history.len();
+
+        let mut words = line.split_whitespace();
+        let result = match (words.next(), words.next()) {
+            (Some("clear"), None) => {
+                self.log.clear();
+                Ok(String::new())
+            }
+            (Some("writeconfig"), path) => self.config_file(path).and_then(|path| {
+                cvars.save(&path)?;
+                Ok(format!("Wrote {}", path.display()))
+            }),
+            (Some("exec"), path) => self.config_file(path).and_then(|path| {
+                let errors = cvars.load(&path)?;
+                Ok(errors.join("\n"))
+            }),
+            (Some("bind" | "unbind" | "unbindall" | "bindlist"), _) => bindings.execute(&line),
+            _ => cvars.execute(&line),
+        };
+        match result {
+            Ok(output) => self.print(&output),
+            Err(error) => self.print(&error),
+        }
+    }
+
+    fn config_file(&self, path: Option<&str>) -> Result<PathBuf, String> {
+        path.map(PathBuf::from)
+            .or_else(|| self.config_path.clone())
+            .ok_or_else(|| "No config file given".to_string())
+    }
+
+    ///Zeichnet die Konsole über die obere Bildhälfte, die neuesten Zeilen direkt über der Eingabe
+    pub fn draw<C: Color>(&self, framebuffer: &mut Framebuffer<C>) {
+        if !self.open {
+            return;
+        }
+        let line_height = LINE_ADVANCE * self.scale;
+        let height = framebuffer.height / 2;
+        framebuffer.blend_rect(0, 0, framebuffer.width, height, self.background);
+
+        let margin = self.scale as i32 * 2;
+        let mut y = height as i32 - line_height as i32;
+        let prompt = format!("] {}_", self.input);
+        // Lange Eingaben bleiben am Ende sichtbar
+        let columns = (framebuffer.width / (CHAR_ADVANCE * self.scale)).max(1);
+        let skip = prompt.chars().count().saturating_sub(columns);
+        let prompt: String = prompt.chars().skip(skip).collect();
+        framebuffer.draw_text(margin, y, &prompt, self.text_color, self.scale);
+
+        for line in self.log.iter().rev() {
+            y -= line_height as i32;
+            if y < 0 {
+                break;
+            }
+            framebuffer.draw_text(margin, y, line, self.text_color, self.scale);
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use crate::camera::{Projection, StandardView};
+use crate::input::{Action, ActionState};
+use crate::{Camera, Point, Polygon, Quat};
+use std::f32::consts::FRAC_PI_2;
+
+/// Steuert die Kamera aus Tastatur und Maus. Verschiedene Controller lassen sich zur Laufzeit
+/// austauschen, `activate` übernimmt dabei die aktuelle Kamera, damit das Bild nicht springt
+pub trait CameraController: Send {
+    fn name(&self) -> &'static str;
+
+    ///Wird beim Umschalten auf diesen Controller aufgerufen
+    fn activate(&mut self, _camera: &Camera) {}
+
+    ///Mausbewegung in Pixeln, einmal pro Bild
+    fn look(&mut self, camera: &mut Camera, mouse_delta: (f32, f32));
+
+    ///Gehaltene Aktionen, einmal pro Simulationsschritt
+    fn update(&mut self, camera: &mut Camera, delta_time: f32, input: &ActionState);
+
+    ///Mausrad in Rasten, positiv = vom Benutzer weg gedreht
+    fn zoom(&mut self, _camera: &mut Camera, _steps: f32) {}
+
+    ///Richtet die Kamera so aus, dass die Polygone das Bild füllen
+    fn frame(&mut self, _camera: &mut Camera, _polygons: &[Polygon]) {}
+
+    ///Schaut aus einer Standardansicht, ohne Ziel dreht sich die Kamera auf der Stelle
+    fn standard_view(&mut self, camera: &mut Camera, view: StandardView) {
+        camera.set_standard_view(view);
+    }
+
+    ///Eigener Zustand neben der Kamera, z.B. für Aufnahmen. Leer, wenn alles in der Kamera steckt
+    fn state(&self) -> Vec<f32> {
+        Vec::new()
+    }
+
+    ///Stellt einen Zustand aus `state` wieder her, unpassende Werte werden ignoriert
+    fn restore_state(&mut self, _state: &[f32]) {}
+}
+
+/// Die bisherige Ego-Steuerung mit Air-Strafing und Boden bei y = 0
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FpsController;
+
+impl CameraController for FpsController {
+    fn name(&self) -> &'static str {
+        "FPS"
+    }
+
+    fn look(&mut self, camera: &mut Camera, mouse_delta: (f32, f32)) {
+        camera.look_around(mouse_delta.0, mouse_delta.1);
+    }
+
+    fn update(&mut self, camera: &mut Camera, delta_time: f32, input: &ActionState) {
+        // Die Ego-Steuerung kennt kein Rollen, z.B. nach dem Flugmodus
+        if camera.roll != 0.0 {
+            camera.roll = 0.0;
+            camera.update_forward();
+        }
+        camera.update_movement(delta_time, input, (0.0, 0.0));
+    }
+}
+
+/// Freier Flug ohne Schwerkraft. Die Maus dreht um die eigenen Achsen der Kamera, dadurch
+/// gibt es keine Begrenzung an den Polen. W/A/S/D bewegen, Leertaste/Shift steigen und sinken
+/// entlang der Kamera-Hochachse, Q/E rollen
+#[derive(Debug, Clone, Copy)]
+pub struct FlyController {
+    pub speed: f32,      // Welteinheiten pro Sekunde
+    pub roll_speed: f32, // Radiant pro Sekunde
+}
+
+impl Default for FlyController {
+    fn default() -> Self {
+        FlyController {
+            speed: 5.0,
+            roll_speed: 1.5,
+        }
+    }
+}
+
+impl CameraController for FlyController {
+    fn name(&self) -> &'static str {
+        "Fly"
+    }
+
+    fn look(&mut self, camera: &mut Camera, mouse_delta: (f32, f32)) {
+        if mouse_delta == (0.0, 0.0) {
+            return;
+        }
+        // Gleiche Vorzeichen wie `Camera::look_around`, aber um die lokalen Achsen
+        let sensitivity = camera.look_sensitivity;
+        camera.rotate_local(
+            Quat::from_axis_angle(Point::new(0.0, 1.0, 0.0), mouse_delta.0 * sensitivity)
+                * Quat::from_axis_angle(Point::new(1.0, 0.0, 0.0), mouse_delta.1 * sensitivity),
+        );
+    }
+
+    fn update(&mut self, camera: &mut Camera, delta_time: f32, input: &ActionState) {
+        let axis = |positive, negative| input.axis(positive, negative) * delta_time;
+
+        let roll = axis(Action::RollLeft, Action::RollRight) * self.roll_speed;
+        if roll != 0.0 {
+            camera.rotate_local(Quat::from_axis_angle(Point::new(0.0, 0.0, 1.0), roll));
+        }
+
+        let distance = self.speed;
+        camera.move_forward(axis(Action::MoveForward, Action::MoveBack) * distance);
+        camera.strafe_right(axis(Action::MoveRight, Action::MoveLeft) * distance);
+        camera.position +=
+            camera.up.normalize() * (axis(Action::MoveUp, Action::MoveDown) * distance);
+        camera.velocity = Point::new(0.0, 0.0, 0.0);
+        camera.vertical_velocity = 0.0;
+    }
+}
+
+/// Dreht die Kamera um einen Zielpunkt, zum Begutachten eines einzelnen Modells.
+/// Maus dreht, W/S oder Mausrad zoomen, Pfeiltasten verschieben das Ziel
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitController {
+    pub target: Point,
+    pub distance: f32,
+    pub yaw: f32, // Gleiche Winkel wie `Camera::yaw` und `Camera::pitch`
+    pub pitch: f32,
+
+    pub rotate_sensitivity: f32, // Radiant pro Pixel Mausbewegung
+    pub zoom_speed: f32,         // Anteil der Entfernung pro Mausrast bzw. Sekunde
+    pub pan_speed: f32,          // Anteil der Entfernung pro Sekunde
+    pub min_distance: f32,
+}
+
+impl OrbitController {
+    pub fn new(target: Point, distance: f32) -> Self {
+        OrbitController {
+            target,
+            distance,
+            yaw: 0.0,
+            pitch: 0.0,
+
+            rotate_sensitivity: 0.005,
+            zoom_speed: 0.1,
+            pan_speed: 0.5,
+            min_distance: 0.01,
+        }
+    }
+
+    ///Dreht um das Ziel, an den Polen wird angehalten
+    pub fn rotate(&mut self, delta_yaw: f32, delta_pitch: f32) {
+        self.yaw += delta_yaw;
+        self.pitch = (self.pitch + delta_pitch).clamp(-FRAC_PI_2, FRAC_PI_2);
+    }
+
+    ///Fährt auf das Ziel zu (positiv) oder davon weg, proportional zur Entfernung
+    pub fn dolly(&mut self, amount: f32) {
+        self.distance = (self.distance * (1.0 - amount)).max(self.min_distance);
+    }
+
+    ///Verschiebt das Ziel in der Bildebene, `right` und `up` in Anteilen der Entfernung
+    pub fn pan(&mut self, camera: &Camera, right: f32, up: f32) {
+        let forward = camera.forward.normalize();
+        let camera_right = forward.cross(camera.up).normalize();
+        let camera_up = camera_right.cross(forward);
+        self.target =
+            self.target + camera_right * (right * self.distance) + camera_up * (up * self.distance);
+    }
+
+    ///Setzt Position und Blickrichtung der Kamera aus Ziel, Entfernung und Winkeln.
+    ///Bei der Parallelprojektion zoomt die Entfernung über die Bildhöhe mit
+    pub fn apply(&self, camera: &mut Camera) {
+        if let Projection::Orthographic { .. } = camera.projection {
+            camera.projection = Projection::Orthographic {
+                height: camera.view_height_at(self.distance),
+            };
+        }
+        camera.yaw = self.yaw;
+        camera.pitch = self.pitch;
+        camera.roll = 0.0;
+        camera.update_forward();
+        // `forward` zeigt von der Blickrichtung weg, also vom Ziel zur Kamera
+        camera.position = self.target + camera.forward * self.distance;
+        camera.velocity = Point::new(0.0, 0.0, 0.0);
+        camera.vertical_velocity = 0.0;
+    }
+}
+
+impl CameraController for OrbitController {
+    fn name(&self) -> &'static str {
+        "Orbit"
+    }
+
+    ///Behält das Ziel und übernimmt Entfernung und Winkel aus der Kameraposition
+    fn activate(&mut self, camera: &Camera) {
+        let offset = camera.position - self.target;
+        let distance = offset.magnitude();
+        if distance > self.min_distance {
+            let direction = offset * (1.0 / distance);
+            self.distance = distance;
+            self.yaw = direction.z.atan2(direction.x);
+            self.pitch = direction.y.clamp(-1.0, 1.0).asin();
+            self.rotate(0.0, 0.0);
+        }
+    }
+
+    fn look(&mut self, camera: &mut Camera, mouse_delta: (f32, f32)) {
+        if mouse_delta == (0.0, 0.0) {
+            return;
+        }
+        // Gleiche Vorzeichen wie `Camera::look_around`
+        self.rotate(
+            -mouse_delta.0 * self.rotate_sensitivity,
+            mouse_delta.1 * self.rotate_sensitivity,
+        );
+        self.apply(camera);
+    }
+
+    fn update(&mut self, camera: &mut Camera, delta_time: f32, input: &ActionState) {
+        let axis = |positive, negative| input.axis(positive, negative) * delta_time;
+
+        self.dolly(axis(Action::MoveForward, Action::MoveBack) * self.zoom_speed * 10.0);
+        self.pan(
+            camera,
+            axis(Action::PanRight, Action::PanLeft) * self.pan_speed,
+            axis(Action::PanUp, Action::PanDown) * self.pan_speed,
+        );
+        self.apply(camera);
+    }
+
+    fn zoom(&mut self, camera: &mut Camera, steps: f32) {
+        self.dolly(steps * self.zoom_speed);
+        self.apply(camera);
+    }
+
+    ///Ziel in die Mitte der Bounding Box, Entfernung so dass ihre Umkugel in das Sichtfeld passt
+    fn frame(&mut self, camera: &mut Camera, polygons: &[Polygon]) {
+        let Some((min, max)) = bounding_box(polygons) else {
+            return;
+        };
+        let center = (min + max) * 0.5;
+        let radius = (max - min).magnitude() * 0.5;
+
+        // Das engere der beiden Sichtfelder bestimmt die Entfernung
+        let half_fov = (camera.fov.to_radians() / 2.0).tan();
+        let narrow = half_fov.min(half_fov * camera.aspect_ratio).atan();
+        self.target = center;
+        self.distance = (radius / narrow.sin()).max(self.min_distance);
+        self.apply(camera);
+    }
+
+    fn standard_view(&mut self, camera: &mut Camera, view: StandardView) {
+        (self.yaw, self.pitch) = view.angles();
+        self.apply(camera);
+    }
+
+    ///Ziel, Entfernung und Winkel
+    fn state(&self) -> Vec<f32> {
+        let Point { x, y, z } = self.target;
+        vec![x, y, z, self.distance, self.yaw, self.pitch]
+    }
+
+    fn restore_state(&mut self, state: &[f32]) {
+        if let &[x, y, z, distance, yaw, pitch] = state {
+            self.target = Point::new(x, y, z);
+            (self.distance, self.yaw, self.pitch) = (distance, yaw, pitch);
+        }
+    }
+}
+
+///Achsenparallele Bounding Box aller Ecken, `None` ohne Ecken
+pub fn bounding_box(polygons: &[Polygon]) -> Option<(Point, Point)> {
+    let mut vertices = polygons.iter().flat_map(|polygon| polygon.vertices.iter());
+    let first = *vertices.next()?;
+    Some(vertices.fold((first, first), |(min, max), v| {
+        (
+            Point::new(min.x.min(v.x), min.y.min(v.y), min.z.min(v.z)),
+            Point::new(max.x.max(v.x), max.y.max(v.y), max.z.max(v.z)),
+        )
+    }))
+}
@@ -0,0 +1,391 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// Wert einer Konsolenvariable, der Typ steht mit der Registrierung fest
+#[derive(Debug, Clone, PartialEq)]
+pub enum CvarValue {
+    Bool(bool),
+    Int(i32),
+    Float(f32),
+    Text(String),
+}
+
+impl CvarValue {
+    ///Liest `text` als Wert vom selben Typ wie `self`
+    fn parse_like(&self, text: &str) -> Result<Self, String> {
+        match self {
+            CvarValue::Bool(_) => match text.to_ascii_lowercase().as_str() {
+                "1" | "true" | "on" | "yes" => Ok(CvarValue::Bool(true)),
+                "0" | "false" | "off" | "no" => Ok(CvarValue::Bool(false)),
+                _ => Err(format!("\"{text}\" is not a boolean (0 or 1)")),
+            },
+            CvarValue::Int(_) => text
+                .parse()
+                .map(CvarValue::Int)
+                .map_err(|_| format!("\"{text}\" is not an integer")),
+            CvarValue::Float(_) => text
+                .parse::<f32>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .map(CvarValue::Float)
+                .ok_or_else(|| format!("\"{text}\" is not a number")),
+            CvarValue::Text(_) => Ok(CvarValue::Text(text.to_string())),
+        }
+    }
+
+    fn same_type(&self, other: &CvarValue) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl fmt::Display for CvarValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CvarValue::Bool(value) => write!(f, "{}", *value as i32),
+            CvarValue::Int(value) => write!(f, "{value}"),
+            CvarValue::Float(value) => write!(f, "{value}"),
+            CvarValue::Text(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl From<bool> for CvarValue {
+    fn from(value: bool) -> Self {
+        CvarValue::Bool(value)
+    }
+}
+
+impl From<i32> for CvarValue {
+    fn from(value: i32) -> Self {
+        CvarValue::Int(value)
+    }
+}
+
+impl From<f32> for CvarValue {
+    fn from(value: f32) -> Self {
+        CvarValue::Float(value)
+    }
+}
+
+impl From<&str> for CvarValue {
+    fn from(value: &str) -> Self {
+        CvarValue::Text(value.to_string())
+    }
+}
+
+/// Benannte, zur Laufzeit änderbare Einstellung wie in Quake
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cvar {
+    pub name: String,
+    pub value: CvarValue,
+    pub default: CvarValue,
+    pub description: String,
+    pub archive: bool, // Wird von `Cvars::save` in die Konfigurationsdatei geschrieben
+    pub range: Option<(f32, f32)>, // Zahlen werden beim Setzen auf diesen Bereich begrenzt
+}
+
+impl Cvar {
+    pub fn new(name: &str, default: impl Into<CvarValue>, description: &str) -> Self {
+        let default = default.into();
+        Self {
+            name: name.to_ascii_lowercase(),
+            value: default.clone(),
+            default,
+            description: description.to_string(),
+            archive: true,
+            range: None,
+        }
+    }
+
+    ///Bereich für Zahlen, bei ganzen Zahlen muss mindestens eine darin liegen
+    pub fn with_range(mut self, min: f32, max: f32) -> Self {
+        assert!(min <= max, "{}: range {min}..{max} is empty", self.name);
+        if let CvarValue::Int(_) = self.default {
+            assert!(
+                min.ceil() <= max.floor(),
+                "{}: range {min}..{max} contains no integer",
+                self.name
+            );
+        }
+        self.range = Some((min, max));
+        self
+    }
+
+    ///Nur für die laufende Sitzung, wird nicht gespeichert
+    pub fn temporary(mut self) -> Self {
+        self.archive = false;
+        self
+    }
+
+    fn clamp(&self, value: CvarValue) -> CvarValue {
+        match (value, self.range) {
+            (CvarValue::Int(value), Some((min, max))) => {
+                CvarValue::Int(value.clamp(min.ceil() as i32, max.floor() as i32))
+            }
+            (CvarValue::Float(value), Some((min, max))) => CvarValue::Float(value.clamp(min, max)),
+            (value, _) => value,
+        }
+    }
+}
+
+/// Sammlung aller Konsolenvariablen. Namen sind unabhängig von Groß- und Kleinschreibung
+#[derive(Debug, Default, Clone)]
+pub struct Cvars {
+    vars: BTreeMap<String, Cvar>,
+}
+
+impl Cvars {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Legt die Variable an. Gibt es sie schon mit gleichem Typ, bleibt ihr Wert erhalten,
+    ///erneutes Registrieren setzt also nichts zurück
+    pub fn register(&mut self, cvar: Cvar) {
+        let value = match self.vars.get(&cvar.name) {
+            Some(existing) if existing.value.same_type(&cvar.default) => {
+                cvar.clamp(existing.value.clone())
+            }
+            _ => cvar.value.clone(),
+        };
+        self.vars.insert(cvar.name.clone(), Cvar { value, ..cvar });
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Cvar> {
+        self.vars.get(&name.to_ascii_lowercase())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Cvar> {
+        self.vars.values()
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        match self.get(name)?.value {
+            CvarValue::Bool(value) => Some(value),
+            CvarValue::Int(value) => Some(value != 0),
+            _ => None,
+        }
+    }
+
+    pub fn get_int(&self, name: &str) -> Option<i32> {
+        match self.get(name)?.value {
+            CvarValue::Int(value) => Some(value),
+            CvarValue::Bool(value) => Some(value as i32),
+            _ => None,
+        }
+    }
+
+    ///Ganze Zahlen werden ebenfalls als Kommazahl geliefert
+    pub fn get_float(&self, name: &str) -> Option<f32> {
+        match self.get(name)?.value {
+            CvarValue::Float(value) => Some(value),
+            CvarValue::Int(value) => Some(value as f32),
+            _ => None,
+        }
+    }
+
+    pub fn get_text(&self, name: &str) -> Option<&str> {
+        match &self.get(name)?.value {
+            CvarValue::Text(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    ///Setzt den Wert aus Text, wie er in der Konsole oder der Konfigurationsdatei steht
+    pub fn set(&mut self, name: &str, text: &str) -> Result<(), String> {
+        let cvar = self.get_mut(name)?;
+        let value = cvar.value.parse_like(text)?;
+        cvar.value = cvar.clamp(value);
+        Ok(())
+    }
+
+    ///Setzt einen typisierten Wert. Ganze Zahlen passen auch in Kommazahl-Variablen
+    pub fn set_value(&mut self, name: &str, value: impl Into<CvarValue>) -> Result<(), String> {
+        let cvar = self.get_mut(name)?;
+        let value = match (value.into(), &cvar.value) {
+            (CvarValue::Int(value), CvarValue::Float(_)) => CvarValue::Float(value as f32),
+            (CvarValue::Float(value), _) if !value.is_finite() => {
+                return Err(format!("{} cannot be set to \"{value}\"", cvar.name));
+            }
+            (value, current) if value.same_type(current) => value,
+            (value, _) => return Err(format!("{} cannot be set to \"{value}\"", cvar.name)),
+        };
+        cvar.value = cvar.clamp(value);
+        Ok(())
+    }
+
+    pub fn reset(&mut self, name: &str) -> Result<(), String> {
+        let cvar = self.get_mut(name)?;
+        cvar.value = cvar.default.clone();
+        Ok(())
+    }
+
+    fn get_mut(&mut self, name: &str) -> Result<&mut Cvar, String> {
+        self.vars
+            .get_mut(&name.to_ascii_lowercase())
+            .ok_or_else(|| format!("Unknown cvar: {name}"))
+    }
+
+    ///Namen, die mit `prefix` beginnen, für die Vervollständigung in der Konsole
+    pub fn complete(&self, prefix: &str) -> Vec<&str> {
+        let prefix = prefix.to_ascii_lowercase();
+        self.vars
+            .keys()
+            .filter(|name| name.starts_with(&prefix))
+            .map(String::as_str)
+            .collect()
+    }
+
+    ///Führt eine Zeile aus und liefert die Ausgabe für die Konsole:
+    ///`name` zeigt den Wert, `name wert` oder `set name wert` setzt ihn,
+    ///außerdem `reset name`, `toggle name` und `cvarlist [anfang]`
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let tokens = tokenize(line);
+        let Some((command, args)) = tokens.split_first() else {
+            return Ok(String::new());
+        };
+        match (command.to_ascii_lowercase().as_str(), args) {
+            ("set", [name, value @ ..]) if !value.is_empty() => {
+                self.set(name, &value.join(" "))?;
+                Ok(String::new())
+            }
+            ("reset", [name]) => {
+                self.reset(name)?;
+                Ok(String::new())
+            }
+            ("toggle", [name]) => {
+                let value = self
+                    .get_bool(name)
+                    .ok_or_else(|| format!("{name} is not a boolean"))?;
+                self.set_value(name, !value)?;
+                self.describe(name)
+            }
+            ("cvarlist", prefix) => {
+                let prefix = prefix.first().map_or("", String::as_str);
+                let lines: Vec<_> = self
+                    .complete(prefix)
+                    .iter()
+                    .map(|name| {
+                        let cvar = &self.vars[*name];
+                        format!("{} \"{}\"", cvar.name, cvar.value)
+                    })
+                    .collect();
+                Ok(format!("{}\n{} cvars", lines.join("\n"), lines.len()))
+            }
+            (_, []) => self.describe(command),
+            (_, value) => {
+                self.set(command, &value.join(" "))?;
+                Ok(String::new())
+            }
+        }
+    }
+
+    fn describe(&self, name: &str) -> Result<String, String> {
+        let cvar = self
+            .get(name)
+            .ok_or_else(|| format!("Unknown command or cvar: {name}"))?;
+        Ok(format!(
+            "{} is \"{}\", default \"{}\" - {}",
+            cvar.name, cvar.value, cvar.default, cvar.description
+        ))
+    }
+
+    ///Führt eine Konfiguration Zeile für Zeile aus, siehe `exec_lines`
+    pub fn exec_config(&mut self, text: &str) -> Vec<String> {
+        exec_lines(text, |line| self.execute(line))
+    }
+
+    ///Alle zu speichernden Variablen als `set name "wert"`, von `exec_config` wieder lesbar.
+    ///Anführungszeichen und Backslashes im Wert werden mit `\` geschützt
+    pub fn to_config(&self) -> String {
+        self.iter()
+            .filter(|cvar| cvar.archive)
+            .map(|cvar| {
+                let value = cvar.value.to_string();
+                let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+                format!("set {} \"{value}\"\n", cvar.name)
+            })
+            .collect()
+    }
+
+    ///Lädt eine Konfigurationsdatei. Fehler einzelner Zeilen werden zurückgegeben, nicht abgebrochen
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<Vec<String>, String> {
+        let text = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("Failed to read config: {}", e))?;
+        Ok(self.exec_config(&text))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        std::fs::write(path.as_ref(), self.to_config())
+            .map_err(|e| format!("Failed to write config: {}", e))
+    }
+}
+
+///Führt `text` Zeile für Zeile mit `execute` aus, `//` außerhalb von Anführungszeichen leitet
+///Kommentare ein. Fehlerhafte Zeilen werden übersprungen und mit Zeilennummer zurückgegeben.
+///Gemeinsam für Konsolenvariablen und Tastenbelegung
+pub(crate) fn exec_lines(
+    text: &str,
+    mut execute: impl FnMut(&str) -> Result<String, String>,
+) -> Vec<String> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(number, line)| {
+            execute(strip_comment(line))
+                .err()
+                .map(|error| format!("line {}: {error}", number + 1))
+        })
+        .collect()
+}
+
+///Schneidet den Kommentar ab, `//` in Anführungszeichen gehört zum Text
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let mut chars = line.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' if quoted => {
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            '/' if !quoted && line[index..].starts_with("//") => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+///Zerlegt eine Zeile an Leerzeichen, Anführungszeichen fassen Text mit Leerzeichen zusammen.
+///Darin stehen `\"` und `\\` für ein Anführungszeichen bzw. einen Backslash
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.trim().chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut token = String::new();
+            while let Some(c) = chars.next() {
+                match (c, chars.peek()) {
+                    ('"', _) => break,
+                    ('\\', Some('"' | '\\')) => token.extend(chars.next()),
+                    _ => token.push(c),
+                }
+            }
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '"' {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+    tokens
+}
@@ -0,0 +1,458 @@
+use crate::camera::Projection;
+use crate::controller::CameraController;
+use crate::cvar::Cvars;
+use crate::input::{Action, ActionState};
+use crate::{Camera, Point, Polygon, Quat};
+use std::path::Path;
+
+/// Kennung am Anfang jeder Aufnahme, danach die Formatversion
+const MAGIC: &[u8; 4] = b"RKDM";
+const VERSION: u8 = 3;
+
+/// Bits in `DemoTick`, fehlende Werte sind null bzw. leer
+const TICK_LOOK: u8 = 1;
+const TICK_ZOOM: u8 = 2;
+const TICK_COMMANDS: u8 = 4;
+const TICK_PAUSED: u8 = 8;
+
+/// Alles an der Kamera, was die Simulation beeinflusst. Abgeleitete Werte wie `forward`
+/// werden mitgespeichert, damit die Wiedergabe bitgenau beim selben Zustand beginnt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraState {
+    pub position: Point,
+    pub forward: Point,
+    pub up: Point,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32,
+    pub orientation: Quat,
+    pub velocity: Point,
+    pub vertical_velocity: f32,
+    pub is_jumping: bool,
+    pub on_ground: bool,
+    pub projection: Projection,
+}
+
+impl CameraState {
+    pub fn capture(camera: &Camera) -> Self {
+        CameraState {
+            position: camera.position,
+            forward: camera.forward,
+            up: camera.up,
+            yaw: camera.yaw,
+            pitch: camera.pitch,
+            roll: camera.roll,
+            orientation: camera.orientation,
+            velocity: camera.velocity,
+            vertical_velocity: camera.vertical_velocity,
+            is_jumping: camera.is_jumping,
+            on_ground: camera.on_ground,
+            projection: camera.projection,
+        }
+    }
+
+    pub fn restore(&self, camera: &mut Camera) {
+        camera.position = self.position;
+        camera.forward = self.forward;
+        camera.up = self.up;
+        camera.yaw = self.yaw;
+        camera.pitch = self.pitch;
+        camera.roll = self.roll;
+        camera.orientation = self.orientation;
+        camera.velocity = self.velocity;
+        camera.vertical_velocity = self.vertical_velocity;
+        camera.is_jumping = self.is_jumping;
+        camera.on_ground = self.on_ground;
+        camera.projection = self.projection;
+    }
+}
+
+/// Eingaben eines Simulationsschritts, wie sie `run_tick` verarbeitet
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DemoTick {
+    pub input: ActionState,
+    pub look: (f32, f32),      // Mausbewegung in Pixeln seit dem letzten Schritt
+    pub zoom: f32,             // Mausrasten seit dem letzten Schritt
+    pub commands: Vec<Action>, // Einmalige Aktionen vor dem Schritt, z.B. Standardansichten
+    pub paused: bool,          // Mausblick aus, die Kamera bleibt stehen
+    pub position: Point,       // Kameraposition nach dem Schritt, zum Erkennen von Abweichungen
+}
+
+///Ein Simulationsschritt: erst Blick und Zoom, dann die gehaltenen Aktionen. Live und bei der
+///Wiedergabe gleich aufgerufen, damit beide exakt dasselbe rechnen. `commands` führt der
+///Aufrufer vorher mit `run_command` aus, `position` wird hier nicht gesetzt
+pub fn run_tick(
+    camera: &mut Camera,
+    controller: &mut dyn CameraController,
+    tick: &DemoTick,
+    delta_time: f32,
+) {
+    if tick.paused {
+        return;
+    }
+    controller.look(camera, tick.look);
+    if tick.zoom != 0.0 {
+        controller.zoom(camera, tick.zoom);
+    }
+    controller.update(camera, delta_time, &tick.input);
+}
+
+///Einmalige Befehle vor einem Schritt, live und bei der Wiedergabe gleich ausgeführt.
+///`active` ist die Steuerung in `controllers`, `polygons` braucht `FrameScene`
+pub fn run_command(
+    action: Action,
+    camera: &mut Camera,
+    controllers: &mut [Box<dyn CameraController>],
+    active: &mut usize,
+    polygons: &[Polygon],
+) {
+    match action {
+        Action::CycleController => {
+            // Kamerasteuerung wechseln, die neue übernimmt die aktuelle Ansicht
+            *active = (*active + 1) % controllers.len();
+            controllers[*active].activate(camera);
+        }
+        Action::FrameScene => controllers[*active].frame(camera, polygons),
+        Action::View(view) => controllers[*active].standard_view(camera, view),
+        Action::ToggleProjection => {
+            // Perspektive/Parallelprojektion, gleich groß im Abstand zum Ursprung.
+            // Die Orbit-Kamera passt die Bildhöhe danach an ihre Entfernung an
+            let focus_distance = camera.position.magnitude();
+            camera.toggle_projection(focus_distance);
+        }
+        _ => {}
+    }
+}
+
+/// Aufnahme einer Sitzung mit festem Zeitschritt: Anfangszustand, Einstellungen und die
+/// Eingaben jedes Schritts. Änderungen an Konsolenvariablen während der Aufnahme fehlen
+#[derive(Debug, Clone, PartialEq)]
+pub struct Demo {
+    pub timestep: f32,
+    pub controller: String,         // `CameraController::name` zu Beginn
+    pub controller_state: Vec<f32>, // `CameraController::state` zu Beginn, z.B. das Orbit-Ziel
+    pub cvars: String,              // Wie von `Cvars::to_config`, vor der Wiedergabe auszuführen
+    pub start: CameraState,
+    pub ticks: Vec<DemoTick>,
+}
+
+impl Demo {
+    ///Beginnt eine leere Aufnahme beim aktuellen Zustand
+    pub fn new(
+        timestep: f32,
+        camera: &Camera,
+        controller: &dyn CameraController,
+        cvars: &Cvars,
+    ) -> Self {
+        Demo {
+            timestep,
+            controller: controller.name().to_string(),
+            controller_state: controller.state(),
+            cvars: cvars.to_config(),
+            start: CameraState::capture(camera),
+            ticks: Vec::new(),
+        }
+    }
+
+    ///Hängt einen ausgeführten Schritt an, mit der Kameraposition danach
+    pub fn record(&mut self, mut tick: DemoTick, camera: &Camera) {
+        tick.position = camera.position;
+        self.ticks.push(tick);
+    }
+
+    ///Länge in Sekunden
+    pub fn duration(&self) -> f32 {
+        self.ticks.len() as f32 * self.timestep
+    }
+
+    ///Index der aufgenommenen Steuerung in `controllers`
+    pub fn find_controller(
+        &self,
+        controllers: &[Box<dyn CameraController>],
+    ) -> Result<usize, String> {
+        controllers
+            .iter()
+            .position(|controller| controller.name() == self.controller)
+            .ok_or_else(|| format!("Demo needs the {} camera controller", self.controller))
+    }
+
+    ///Spielt die Aufnahme ohne Fenster ab, z.B. für Benchmarks und Golden Images.
+    ///Übernimmt vorher `cvars` mit `apply_cvars`, die aufgenommene Steuerung aus
+    ///`controllers` und ihren Zustand. Befehle laufen wie live über `run_command`, `polygons`
+    ///ist die Szene für `FrameScene`. `on_tick` bekommt nach jedem Schritt dessen Nummer und
+    ///die Kamera. Liefert den ersten Schritt, an dem die Position von der Aufnahme abweicht
+    pub fn play(
+        &self,
+        camera: &mut Camera,
+        controllers: &mut [Box<dyn CameraController>],
+        polygons: &[Polygon],
+        mut on_tick: impl FnMut(usize, &Camera),
+    ) -> Result<Option<usize>, String> {
+        let mut active = self.find_controller(controllers)?;
+        self.apply_cvars(camera);
+        self.start.restore(camera);
+        controllers[active].activate(camera);
+        controllers[active].restore_state(&self.controller_state);
+        let mut desync = None;
+        for (index, tick) in self.ticks.iter().enumerate() {
+            for command in &tick.commands {
+                run_command(*command, camera, controllers, &mut active, polygons);
+            }
+            run_tick(camera, controllers[active].as_mut(), tick, self.timestep);
+            if desync.is_none() && camera.position != tick.position {
+                desync = Some(index);
+            }
+            on_tick(index, camera);
+        }
+        Ok(desync)
+    }
+
+    ///Übergibt der Kamera die aufgenommenen Variablen, fehlende mit ihrem Standardwert. Ohne
+    ///aufgenommene Variablen bleibt die Kamera unverändert, andere als die der Kamera werden
+    ///ignoriert
+    pub fn apply_cvars(&self, camera: &mut Camera) {
+        if self.cvars.is_empty() {
+            return;
+        }
+        let mut cvars = Cvars::new();
+        Camera::register_cvars(&mut cvars);
+        cvars.exec_config(&self.cvars);
+        camera.apply_cvars(&cvars);
+    }
+
+    ///Kompaktes Binärformat, Zahlen als Little Endian
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer(Vec::new());
+        writer.0.extend_from_slice(MAGIC);
+        writer.u8(VERSION);
+        writer.f32(self.timestep);
+        writer.text(&self.controller);
+        writer.u32(self.controller_state.len() as u32);
+        self.controller_state
+            .iter()
+            .for_each(|value| writer.f32(*value));
+        writer.text(&self.cvars);
+        writer.camera(&self.start);
+        writer.u32(self.ticks.len() as u32);
+        for tick in &self.ticks {
+            let flags = [
+                (tick.look != (0.0, 0.0), TICK_LOOK),
+                (tick.zoom != 0.0, TICK_ZOOM),
+                (!tick.commands.is_empty(), TICK_COMMANDS),
+                (tick.paused, TICK_PAUSED),
+            ]
+            .iter()
+            .filter(|(set, _)| *set)
+            .fold(0, |flags, (_, bit)| flags | bit);
+            writer.u8(flags);
+            writer.u64(tick.input.to_bits());
+            if flags & TICK_LOOK != 0 {
+                writer.f32(tick.look.0);
+                writer.f32(tick.look.1);
+            }
+            if flags & TICK_ZOOM != 0 {
+                writer.f32(tick.zoom);
+            }
+            if flags & TICK_COMMANDS != 0 {
+                writer.u32(tick.commands.len() as u32);
+                for command in &tick.commands {
+                    writer.u8(command.index() as u8);
+                }
+            }
+            writer.point(tick.position);
+        }
+        writer.0
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader(bytes);
+        if reader.take(4)? != MAGIC {
+            return Err("Not a demo file".to_string());
+        }
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(format!("Unsupported demo version {version}"));
+        }
+        let timestep = reader.f32()?;
+        let controller = reader.text()?;
+        let mut controller_state = Vec::new();
+        for _ in 0..reader.u32()? {
+            controller_state.push(reader.f32()?);
+        }
+        let cvars = reader.text()?;
+        let start = reader.camera()?;
+        let count = reader.u32()? as usize;
+        let mut ticks = Vec::with_capacity(count.min(bytes.len()));
+        for _ in 0..count {
+            let flags = reader.u8()?;
+            let mut tick = DemoTick {
+                input: ActionState::from_bits(reader.u64()?),
+                paused: flags & TICK_PAUSED != 0,
+                ..DemoTick::default()
+            };
+            if flags & TICK_LOOK != 0 {
+                tick.look = (reader.f32()?, reader.f32()?);
+            }
+            if flags & TICK_ZOOM != 0 {
+                tick.zoom = reader.f32()?;
+            }
+            if flags & TICK_COMMANDS != 0 {
+                for _ in 0..reader.u32()? {
+                    let index = reader.u8()?;
+                    let command = Action::from_index(index as usize)
+                        .ok_or_else(|| format!("Unknown action {index} in demo"))?;
+                    tick.commands.push(command);
+                }
+            }
+            tick.position = reader.point()?;
+            ticks.push(tick);
+        }
+        Ok(Demo {
+            timestep,
+            controller,
+            controller_state,
+            cvars,
+            start,
+            ticks,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let bytes =
+            std::fs::read(path.as_ref()).map_err(|e| format!("Failed to read demo: {}", e))?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        std::fs::write(path.as_ref(), self.to_bytes())
+            .map_err(|e| format!("Failed to write demo: {}", e))
+    }
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn point(&mut self, point: Point) {
+        self.f32(point.x);
+        self.f32(point.y);
+        self.f32(point.z);
+    }
+
+    fn text(&mut self, text: &str) {
+        self.u32(text.len() as u32);
+        self.0.extend_from_slice(text.as_bytes());
+    }
+
+    fn camera(&mut self, state: &CameraState) {
+        self.point(state.position);
+        self.point(state.forward);
+        self.point(state.up);
+        self.f32(state.yaw);
+        self.f32(state.pitch);
+        self.f32(state.roll);
+        let Quat { x, y, z, w } = state.orientation;
+        [x, y, z, w].into_iter().for_each(|value| self.f32(value));
+        self.point(state.velocity);
+        self.f32(state.vertical_velocity);
+        self.u8(state.is_jumping as u8 | (state.on_ground as u8) << 1);
+        match state.projection {
+            Projection::Perspective => self.u8(0),
+            Projection::Orthographic { height } => {
+                self.u8(1);
+                self.f32(height);
+            }
+        }
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], String> {
+        if self.0.len() < count {
+            return Err("Demo file is truncated".to_string());
+        }
+        let (bytes, rest) = self.0.split_at(count);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32, String> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn point(&mut self) -> Result<Point, String> {
+        Ok(Point::new(self.f32()?, self.f32()?, self.f32()?))
+    }
+
+    fn text(&mut self) -> Result<String, String> {
+        let length = self.u32()? as usize;
+        String::from_utf8(self.take(length)?.to_vec())
+            .map_err(|_| "Invalid text in demo file".to_string())
+    }
+
+    fn camera(&mut self) -> Result<CameraState, String> {
+        let position = self.point()?;
+        let forward = self.point()?;
+        let up = self.point()?;
+        let (yaw, pitch, roll) = (self.f32()?, self.f32()?, self.f32()?);
+        let orientation = Quat {
+            x: self.f32()?,
+            y: self.f32()?,
+            z: self.f32()?,
+            w: self.f32()?,
+        };
+        let velocity = self.point()?;
+        let vertical_velocity = self.f32()?;
+        let flags = self.u8()?;
+        let projection = match self.u8()? {
+            0 => Projection::Perspective,
+            1 => Projection::Orthographic {
+                height: self.f32()?,
+            },
+            tag => return Err(format!("Unknown projection {tag} in demo")),
+        };
+        Ok(CameraState {
+            position,
+            forward,
+            up,
+            yaw,
+            pitch,
+            roll,
+            orientation,
+            velocity,
+            vertical_velocity,
+            is_jumping: flags & 1 != 0,
+            on_ground: flags & 2 != 0,
+            projection,
+        })
+    }
+}
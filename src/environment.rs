@@ -0,0 +1,95 @@
+use crate::background::{Background, Cubemap, cubemap_face, equirectangular_uv};
+use crate::color::Rgba32F;
+use crate::point::Point;
+use crate::texture::HdrTexture;
+
+/// Vorgefilterte Umgebung für Spiegelungen. Jede Mip-Stufe hat die halbe Auflösung der vorigen,
+/// raue Oberflächen lesen aus den verschwommenen Stufen
+#[derive(Debug, Clone)]
+pub enum Environment {
+    Cubemap(Vec<Cubemap>),            // Stufe 0 ist die Originalauflösung
+    Equirectangular(Vec<HdrTexture>), // Stufe 0 ist die Originalauflösung
+}
+
+impl Environment {
+    pub fn from_cubemap(cubemap: &Cubemap) -> Self {
+        let mut levels = vec![cubemap.clone()];
+        loop {
+            let last = &levels[levels.len() - 1];
+            if last
+                .faces
+                .iter()
+                .all(|face| face.width <= 1 && face.height <= 1)
+            {
+                break;
+            }
+            let next = Cubemap::new(std::array::from_fn(|i| last.faces[i].downsample()));
+            levels.push(next);
+        }
+        Environment::Cubemap(levels)
+    }
+
+    pub fn from_equirectangular(texture: &HdrTexture) -> Self {
+        let mut levels = vec![texture.clone()];
+        loop {
+            let last = &levels[levels.len() - 1];
+            if last.width <= 1 && last.height <= 1 {
+                break;
+            }
+            let next = last.downsample();
+            levels.push(next);
+        }
+        Environment::Equirectangular(levels)
+    }
+
+    ///Umgebung aus einer Skybox, damit Spiegelungen zum Hintergrund passen.
+    ///Einfarbige Hintergründe und Verläufe haben keine Richtung und ergeben `None`
+    pub fn from_background(background: &Background) -> Option<Self> {
+        match background {
+            Background::Cubemap(cubemap) => Some(Environment::from_cubemap(cubemap)),
+            Background::Equirectangular(texture) => {
+                Some(Environment::from_equirectangular(texture))
+            }
+            Background::Solid(_) | Background::Gradient { .. } => None,
+        }
+    }
+
+    pub fn mip_levels(&self) -> usize {
+        match self {
+            Environment::Cubemap(levels) => levels.len(),
+            Environment::Equirectangular(levels) => levels.len(),
+        }
+    }
+
+    ///Rauheit 0.0 - 1.0 auf die Mip-Stufe, 1.0 liest die kleinste Stufe
+    #[inline(always)]
+    pub fn roughness_to_mip(&self, roughness: f32) -> f32 {
+        roughness.clamp(0.0, 1.0) * (self.mip_levels() - 1) as f32
+    }
+
+    ///Liest die Umgebung in Richtung `direction`, zwischen zwei Mip-Stufen interpoliert
+    #[inline(always)]
+    pub fn sample(&self, direction: Point, roughness: f32) -> Rgba32F {
+        let mip = self.roughness_to_mip(roughness);
+        let level = mip as usize;
+        let sharp = self.sample_level(direction, level);
+        if level + 1 >= self.mip_levels() {
+            return sharp;
+        }
+        sharp.lerp(self.sample_level(direction, level + 1), mip - level as f32)
+    }
+
+    #[inline(always)]
+    fn sample_level(&self, direction: Point, level: usize) -> Rgba32F {
+        match self {
+            Environment::Cubemap(levels) => {
+                let (face, u, v) = cubemap_face(direction);
+                levels[level].faces[face].sample_bilinear(u, v)
+            }
+            Environment::Equirectangular(levels) => {
+                let (u, v) = equirectangular_uv(direction);
+                levels[level].sample_bilinear(u, v)
+            }
+        }
+    }
+}
@@ -0,0 +1,450 @@
+use image::RgbaImage;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+/// Ziel für eine Folge gerenderter Bilder, z.B. Vorschauvideos im CI.
+/// Alle Bilder einer Folge haben die beim Anlegen angegebene Größe
+pub trait FrameSink {
+    fn write_frame(&mut self, frame: &RgbaImage) -> Result<(), String>;
+
+    ///Schließt die Ausgabe ab, danach werden keine Bilder mehr angenommen
+    fn finish(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Png, // Nummerierte Bilder in einem Ordner
+    Y4m,    // Unkomprimiertes YUV 4:4:4, versteht ffmpeg und die meisten Player
+    Avi,    // Unkomprimiertes 24-Bit-RGB, höchstens 4 GB
+    Ffmpeg, // Rohbilder an einen lokalen `ffmpeg`-Prozess, Format nach Dateiendung
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "png" => Some(ExportFormat::Png),
+            "y4m" => Some(ExportFormat::Y4m),
+            "avi" => Some(ExportFormat::Avi),
+            "ffmpeg" => Some(ExportFormat::Ffmpeg),
+            _ => None,
+        }
+    }
+
+    ///Format nach Dateiendung, ohne bekannte Endung ist `path` ein Ordner für PNG-Bilder
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match extension.as_str() {
+            "y4m" => ExportFormat::Y4m,
+            "avi" => ExportFormat::Avi,
+            "mp4" | "mkv" | "webm" | "mov" | "gif" => ExportFormat::Ffmpeg,
+            _ => ExportFormat::Png,
+        }
+    }
+
+    ///Öffnet die Ausgabe für Bilder der Größe `width` x `height` mit `fps` Bildern pro Sekunde
+    pub fn create(
+        self,
+        path: impl AsRef<Path>,
+        width: usize,
+        height: usize,
+        fps: f32,
+    ) -> Result<Box<dyn FrameSink>, String> {
+        let path = path.as_ref();
+        // Vor dem Anlegen der Datei prüfen
+        frame_rate(fps)?;
+        check_dimensions(width, height)?;
+        let create = || {
+            File::create(path)
+                .map(BufWriter::new)
+                .map_err(|e| format!("Failed to create {}: {}", path.display(), e))
+        };
+        Ok(match self {
+            ExportFormat::Png => Box::new(PngSequence::new(path)?),
+            ExportFormat::Y4m => Box::new(Y4mWriter::new(create()?, width, height, fps)?),
+            ExportFormat::Avi => Box::new(AviWriter::new(create()?, width, height, fps)?),
+            ExportFormat::Ffmpeg => Box::new(FfmpegPipe::new(path, width, height, fps)?),
+        })
+    }
+}
+
+///Bildrate als Bruch, z.B. 29.97 als 2997/100. Auf Tausendstel genau, der Zähler muss in
+///ein `u32` passen
+fn frame_rate(fps: f32) -> Result<(u32, u32), String> {
+    let thousandths = (fps * 1000.0).round();
+    if !(1.0..=u32::MAX as f32).contains(&thousandths) {
+        return Err(format!("Invalid frame rate: {fps}"));
+    }
+    let (mut numerator, mut denominator) = (thousandths as u32, 1000);
+    let (mut a, mut b) = (numerator, denominator);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    numerator /= a.max(1);
+    denominator /= a.max(1);
+    Ok((numerator.max(1), denominator))
+}
+
+///Breite und Höhe müssen mindestens ein Pixel sein
+fn check_dimensions(width: usize, height: usize) -> Result<(), String> {
+    if width == 0 || height == 0 {
+        return Err(format!("Invalid frame size: {width}x{height}"));
+    }
+    Ok(())
+}
+
+fn check_size(frame: &RgbaImage, width: usize, height: usize) -> Result<(), String> {
+    if (frame.width() as usize, frame.height() as usize) != (width, height) {
+        return Err(format!(
+            "Frame is {}x{}, expected {width}x{height}",
+            frame.width(),
+            frame.height()
+        ));
+    }
+    Ok(())
+}
+
+/// `frame_00000.png`, `frame_00001.png` usw. in einem Ordner, der bei Bedarf angelegt wird
+pub struct PngSequence {
+    pub directory: PathBuf,
+    pub frames: usize, // Bisher geschriebene Bilder
+}
+
+impl PngSequence {
+    pub fn new(directory: impl Into<PathBuf>) -> Result<Self, String> {
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory)
+            .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+        Ok(PngSequence {
+            directory,
+            frames: 0,
+        })
+    }
+
+    pub fn frame_path(&self, frame: usize) -> PathBuf {
+        self.directory.join(format!("frame_{frame:05}.png"))
+    }
+}
+
+impl FrameSink for PngSequence {
+    fn write_frame(&mut self, frame: &RgbaImage) -> Result<(), String> {
+        let file = self.frame_path(self.frames);
+        frame
+            .save(&file)
+            .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+        self.frames += 1;
+        Ok(())
+    }
+}
+
+/// YUV4MPEG2-Strom, Farben nach BT.601 mit eingeschränktem Wertebereich (16..235)
+pub struct Y4mWriter<W: Write> {
+    output: W,
+    width: usize,
+    height: usize,
+    planes: Vec<u8>, // Y, U und V hintereinander, wiederverwendet
+}
+
+impl<W: Write> Y4mWriter<W> {
+    pub fn new(mut output: W, width: usize, height: usize, fps: f32) -> Result<Self, String> {
+        check_dimensions(width, height)?;
+        let (numerator, denominator) = frame_rate(fps)?;
+        writeln!(
+            output,
+            "YUV4MPEG2 W{width} H{height} F{numerator}:{denominator} Ip A1:1 C444"
+        )
+        .map_err(|e| format!("Failed to write Y4M header: {}", e))?;
+        Ok(Y4mWriter {
+            output,
+            width,
+            height,
+            planes: Vec::new(),
+        })
+    }
+
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+}
+
+impl<W: Write> FrameSink for Y4mWriter<W> {
+    fn write_frame(&mut self, frame: &RgbaImage) -> Result<(), String> {
+        check_size(frame, self.width, self.height)?;
+        let count = self.width * self.height;
+        self.planes.resize(count * 3, 0);
+        let (y, uv) = self.planes.split_at_mut(count);
+        let (u, v) = uv.split_at_mut(count);
+        for (index, pixel) in frame.pixels().enumerate() {
+            let [r, g, b] = [pixel[0], pixel[1], pixel[2]].map(f32::from);
+            y[index] = (16.0 + 0.2568 * r + 0.5041 * g + 0.0979 * b).round() as u8;
+            u[index] = (128.0 - 0.1482 * r - 0.2910 * g + 0.4392 * b).round() as u8;
+            v[index] = (128.0 + 0.4392 * r - 0.3678 * g - 0.0714 * b).round() as u8;
+        }
+        self.output
+            .write_all(b"FRAME\n")
+            .and_then(|_| self.output.write_all(&self.planes))
+            .map_err(|e| format!("Failed to write Y4M frame: {}", e))
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        self.output
+            .flush()
+            .map_err(|e| format!("Failed to write Y4M frame: {}", e))
+    }
+}
+
+/// Größe des Kopfs vor der `movi`-Liste, siehe `AviWriter::header`
+const AVI_HEADER_SIZE: usize = 224;
+
+/// AVI-Datei mit unkomprimierten 24-Bit-Bildern (BI_RGB, von unten nach oben) und Index.
+/// Ohne OpenDML-Erweiterung, die Datei darf daher nicht größer als 4 GB werden. Kopf und
+/// Längen werden in `finish` geschrieben
+pub struct AviWriter<W: Write + Seek> {
+    output: W,
+    width: usize,
+    height: usize,
+    rate: (u32, u32),
+    index: Vec<u32>, // Abstand jedes Bildes vom Beginn der `movi`-Liste
+    movi_size: usize,
+    row: Vec<u8>,
+}
+
+impl<W: Write + Seek> AviWriter<W> {
+    pub fn new(output: W, width: usize, height: usize, fps: f32) -> Result<Self, String> {
+        check_dimensions(width, height)?;
+        let mut writer = AviWriter {
+            output,
+            width,
+            height,
+            rate: frame_rate(fps)?,
+            index: Vec::new(),
+            movi_size: 4,
+            row: Vec::new(),
+        };
+        // Vorläufiger Kopf, Bildzahl und Längen folgen in `finish`
+        let header = writer.header();
+        writer
+            .output
+            .write_all(&header)
+            .map_err(|e| format!("Failed to write AVI header: {}", e))?;
+        Ok(writer)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+
+    ///Zeilen auf 4 Byte aufgefüllt, wie es BITMAPINFOHEADER verlangt
+    fn stride(&self) -> usize {
+        (self.width * 3).div_ceil(4) * 4
+    }
+
+    ///RIFF-Kopf mit `avih`, `strh`, `strf` und dem Beginn der `movi`-Liste
+    fn header(&self) -> Vec<u8> {
+        let frame_size = (self.stride() * self.height) as u32;
+        let frames = self.index.len() as u32;
+        let (rate, scale) = self.rate;
+        let riff_size = AVI_HEADER_SIZE - 12 + self.movi_size + 8 + self.index.len() * 16;
+
+        let mut header = Vec::with_capacity(AVI_HEADER_SIZE);
+        let fourcc = |header: &mut Vec<u8>, code: &[u8; 4]| header.extend_from_slice(code);
+        let u32 = |header: &mut Vec<u8>, value: u32| header.extend_from_slice(&value.to_le_bytes());
+        let u16 = |header: &mut Vec<u8>, value: u16| header.extend_from_slice(&value.to_le_bytes());
+
+        fourcc(&mut header, b"RIFF");
+        u32(&mut header, riff_size as u32);
+        fourcc(&mut header, b"AVI ");
+        fourcc(&mut header, b"LIST");
+        u32(&mut header, 192);
+        fourcc(&mut header, b"hdrl");
+
+        fourcc(&mut header, b"avih");
+        u32(&mut header, 56);
+        u32(
+            &mut header,
+            (1_000_000.0 * scale as f64 / rate as f64).round() as u32,
+        );
+        u32(
+            &mut header,
+            (frame_size as f64 * rate as f64 / scale as f64) as u32,
+        );
+        u32(&mut header, 0); // Padding
+        u32(&mut header, 0x10); // AVIF_HASINDEX
+        u32(&mut header, frames);
+        u32(&mut header, 0); // Initial frames
+        u32(&mut header, 1); // Streams
+        u32(&mut header, frame_size);
+        u32(&mut header, self.width as u32);
+        u32(&mut header, self.height as u32);
+        header.extend_from_slice(&[0; 16]);
+
+        fourcc(&mut header, b"LIST");
+        u32(&mut header, 116);
+        fourcc(&mut header, b"strl");
+        fourcc(&mut header, b"strh");
+        u32(&mut header, 56);
+        fourcc(&mut header, b"vids");
+        fourcc(&mut header, b"DIB ");
+        u32(&mut header, 0); // Flags
+        u16(&mut header, 0); // Priority
+        u16(&mut header, 0); // Language
+        u32(&mut header, 0); // Initial frames
+        u32(&mut header, scale);
+        u32(&mut header, rate);
+        u32(&mut header, 0); // Start
+        u32(&mut header, frames);
+        u32(&mut header, frame_size);
+        u32(&mut header, u32::MAX); // Quality
+        u32(&mut header, frame_size);
+        u16(&mut header, 0);
+        u16(&mut header, 0);
+        u16(&mut header, self.width as u16);
+        u16(&mut header, self.height as u16);
+
+        fourcc(&mut header, b"strf");
+        u32(&mut header, 40);
+        u32(&mut header, 40); // BITMAPINFOHEADER
+        u32(&mut header, self.width as u32);
+        u32(&mut header, self.height as u32); // Positiv: unterste Zeile zuerst
+        u16(&mut header, 1); // Planes
+        u16(&mut header, 24); // Bits pro Pixel
+        u32(&mut header, 0); // BI_RGB
+        u32(&mut header, frame_size);
+        header.extend_from_slice(&[0; 16]);
+
+        fourcc(&mut header, b"LIST");
+        u32(&mut header, self.movi_size as u32);
+        fourcc(&mut header, b"movi");
+        debug_assert_eq!(header.len(), AVI_HEADER_SIZE);
+        header
+    }
+}
+
+impl<W: Write + Seek> FrameSink for AviWriter<W> {
+    fn write_frame(&mut self, frame: &RgbaImage) -> Result<(), String> {
+        check_size(frame, self.width, self.height)?;
+        let stride = self.stride();
+        let frame_size = stride * self.height;
+        if AVI_HEADER_SIZE + self.movi_size + 8 + frame_size + (self.index.len() + 1) * 16
+            > u32::MAX as usize
+        {
+            return Err("AVI file would exceed 4 GB".to_string());
+        }
+
+        let error = |e: std::io::Error| format!("Failed to write AVI frame: {}", e);
+        self.output.write_all(b"00db").map_err(error)?;
+        self.output
+            .write_all(&(frame_size as u32).to_le_bytes())
+            .map_err(error)?;
+        self.row.resize(stride, 0);
+        for y in (0..self.height as u32).rev() {
+            for x in 0..self.width as u32 {
+                let pixel = frame.get_pixel(x, y);
+                let offset = x as usize * 3;
+                self.row[offset..offset + 3].copy_from_slice(&[pixel[2], pixel[1], pixel[0]]);
+            }
+            self.output.write_all(&self.row).map_err(error)?;
+        }
+        self.index.push(self.movi_size as u32);
+        self.movi_size += 8 + frame_size;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        let error = |e: std::io::Error| format!("Failed to write AVI index: {}", e);
+        let mut index = Vec::with_capacity(8 + self.index.len() * 16);
+        index.extend_from_slice(b"idx1");
+        index.extend_from_slice(&(self.index.len() as u32 * 16).to_le_bytes());
+        let frame_size = (self.stride() * self.height) as u32;
+        for &offset in &self.index {
+            index.extend_from_slice(b"00db");
+            index.extend_from_slice(&0x10u32.to_le_bytes()); // AVIIF_KEYFRAME
+            index.extend_from_slice(&offset.to_le_bytes());
+            index.extend_from_slice(&frame_size.to_le_bytes());
+        }
+        self.output.write_all(&index).map_err(error)?;
+        let header = self.header();
+        self.output.seek(SeekFrom::Start(0)).map_err(error)?;
+        self.output.write_all(&header).map_err(error)?;
+        self.output.seek(SeekFrom::End(0)).map_err(error)?;
+        self.output.flush().map_err(error)
+    }
+}
+
+/// Schickt die Bilder roh an `ffmpeg`, das sie nach `output` kodiert (H.264 bei `.mp4` usw.).
+/// `ffmpeg` muss im Suchpfad liegen
+pub struct FfmpegPipe {
+    process: Child,
+    input: Option<ChildStdin>,
+    width: usize,
+    height: usize,
+}
+
+impl FfmpegPipe {
+    pub fn new(
+        output: impl AsRef<Path>,
+        width: usize,
+        height: usize,
+        fps: f32,
+    ) -> Result<Self, String> {
+        check_dimensions(width, height)?;
+        let (numerator, denominator) = frame_rate(fps)?;
+        let mut process = Command::new("ffmpeg")
+            .args([
+                "-y",
+                "-loglevel",
+                "error",
+                "-f",
+                "rawvideo",
+                "-pix_fmt",
+                "rgba",
+            ])
+            .args(["-s", &format!("{width}x{height}")])
+            .args(["-r", &format!("{numerator}/{denominator}")])
+            .args(["-i", "-", "-pix_fmt", "yuv420p"])
+            .arg(output.as_ref())
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start ffmpeg: {}", e))?;
+        let input = process.stdin.take();
+        Ok(FfmpegPipe {
+            process,
+            input,
+            width,
+            height,
+        })
+    }
+}
+
+impl FrameSink for FfmpegPipe {
+    fn write_frame(&mut self, frame: &RgbaImage) -> Result<(), String> {
+        check_size(frame, self.width, self.height)?;
+        self.input
+            .as_mut()
+            .ok_or("ffmpeg input already closed")?
+            .write_all(frame.as_raw())
+            .map_err(|e| format!("Failed to write to ffmpeg: {}", e))
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        // Ohne Eingabe beendet sich ffmpeg nach dem letzten Bild
+        drop(self.input.take());
+        let status = self
+            .process
+            .wait()
+            .map_err(|e| format!("Failed to wait for ffmpeg: {}", e))?;
+        if !status.success() {
+            return Err(format!("ffmpeg failed: {status}"));
+        }
+        Ok(())
+    }
+}
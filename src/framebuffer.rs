@@ -1,71 +1,240 @@
+use crate::antialiasing::Msaa;
+use crate::background::Background;
+use crate::color::{Color, Rgba8, Rgba32F};
+use crate::environment::Environment;
+use crate::material::Material;
+use crate::oit::{OitBuffers, TransparencyMode};
+use crate::pbr::{DirectionalLight, PbrMaterial};
+use crate::post::PostEffect;
+use crate::shading::{SurfaceModel, SurfaceTriangle};
 use crate::texture::Texture;
+use crate::tonemap::ToneMapping;
 use crate::{Point2D, Polygon2D, point};
+use image::{Rgba, Rgba32FImage, RgbaImage};
 use std::ptr;
+use std::sync::Arc;
+
+/// Wie die Polygone einer Szene dargestellt werden
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderMode {
+    #[default]
+    Shaded, // Gefüllte Polygone (Standard)
+    Wireframe,           // Nur Kanten, auch verdeckte und Rückseiten
+    WireframeOverShaded, // Sichtbare Kanten über den gefüllten Polygonen
+    HiddenLine,          // Nur sichtbare Kanten, Flächen in Hintergrundfarbe
+}
 
+impl RenderMode {
+    /// Nächster Modus, zum Durchschalten per Taste
+    pub fn next(self) -> Self {
+        match self {
+            RenderMode::Shaded => RenderMode::Wireframe,
+            RenderMode::Wireframe => RenderMode::WireframeOverShaded,
+            RenderMode::WireframeOverShaded => RenderMode::HiddenLine,
+            RenderMode::HiddenLine => RenderMode::Shaded,
+        }
+    }
+}
+
+/// Zielbild der Rasterisierung. Das Pixelformat ist standardmäßig `Rgba8`,
+/// mit `Rgba32F` bleiben Werte über 1.0 für HDR erhalten
 #[derive(Clone)]
-pub struct Framebuffer {
+pub struct Framebuffer<C: Color = Rgba8> {
     pub width: usize,
     pub height: usize,
-    pub pixels: Vec<u32>,   // Farbwerte in 0xRRGGBBAA
+    pub pixels: Vec<C>, // Farbwerte im Pixelformat, Alpha wird bei der Ausgabe ignoriert
     pub z_buffer: Vec<f32>, // Tiefenwerte für jeden pixel, index gleich mit pixels
+
+    pub render_mode: RenderMode,
+    pub line_color: Rgba8,       // Farbe der Kanten in den Wireframe-Modi
+    pub antialiased_lines: bool, // Kanten mit Xiaolin Wu statt Bresenham zeichnen
+
+    pub msaa: Msaa, // Samples pro Pixel, wird in `resolve_msaa` nach `pixels` gemittelt
+    pub fxaa: bool, // FXAA als günstigere Kantenglättung nach dem Rendern
+    pub(crate) sample_colors: Vec<C>, // width * height * Samples, nur bei aktivem MSAA
+    pub(crate) sample_depths: Vec<f32>,
+
+    pub transparency: TransparencyMode, // Sortierte Polygone oder Order-Independent Transparency
+    pub(crate) oit: OitBuffers,
+
+    pub exposure: f32,             // Belichtung in Blendenstufen, 0.0 = unverändert
+    pub tone_mapping: ToneMapping, // Abbildung der HDR-Werte in `resolve_tone_mapping`
+
+    pub post_effects: Vec<Arc<dyn PostEffect>>, // Post-Processing-Kette, in dieser Reihenfolge
+
+    pub background: Background, // Farbe, Verlauf oder Skybox hinter der Geometrie
+    pub environment: Option<Arc<Environment>>, // Was spiegelnde Materialien reflektieren
+    pub lights: Vec<DirectionalLight>, // Direktes Licht für PBR-Materialien
 }
 
 impl Framebuffer {
-    pub(crate) fn new(width: usize, height: usize) -> Self {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_format(width, height)
+    }
+}
+
+impl<C: Color> Framebuffer<C> {
+    ///Framebuffer mit anderem Pixelformat, z.B. `Framebuffer::<Rgba32F>::with_format`
+    pub fn with_format(width: usize, height: usize) -> Self {
         Self {
             width,
             height,
-            pixels: vec![0; width * height], // Schwarzes Bild
+            pixels: vec![C::BLACK; width * height], // Schwarzes Bild
             z_buffer: vec![f32::INFINITY; width * height], // Z-Buffer initial auf unendlich um andere werte drüber zu Zeichnen
+
+            render_mode: RenderMode::Shaded,
+            line_color: Rgba8::WHITE,
+            antialiased_lines: false,
+
+            msaa: Msaa::Off,
+            fxaa: false,
+            sample_colors: Vec::new(),
+            sample_depths: Vec::new(),
+
+            transparency: TransparencyMode::Sorted,
+            oit: OitBuffers::default(),
+
+            exposure: 0.0,
+            tone_mapping: ToneMapping::None,
+
+            post_effects: Vec::new(),
+
+            background: Background::default(),
+            environment: None,
+            lights: Vec::new(),
         }
     }
 
-    pub(crate) fn resize(&mut self, width: usize, height: usize) {
+    pub fn resize(&mut self, width: usize, height: usize) {
         self.width = width;
         self.height = height;
-        self.pixels.resize(self.width * self.height, 0);
+        self.pixels.resize(self.width * self.height, C::BLACK);
         self.z_buffer
             .resize(self.width * self.height, f32::INFINITY);
     }
 
-    ///Füllt den Framebuffer mit Schwarz und Z Werte von Unendlich
-    pub(crate) fn clear(&mut self) {
+    ///Füllt den Framebuffer mit der Hintergrundfarbe und Z Werte von Unendlich
+    pub fn clear(&mut self) {
+        let clear_color = self.clear_color();
         unsafe {
             let pixel_ptr = self.pixels.as_mut_ptr();
             for i in 0..self.width * self.height {
-                ptr::write(pixel_ptr.offset(i as isize), 0xFF000000);
+                ptr::write(pixel_ptr.add(i), clear_color);
             }
 
             let buffer_ptr = self.z_buffer.as_mut_ptr();
             for i in 0..self.width * self.height {
-                ptr::write(buffer_ptr.offset(i as isize), f32::INFINITY);
+                ptr::write(buffer_ptr.add(i), f32::INFINITY);
             }
         }
+
+        self.prepare_samples();
+        self.sample_colors.fill(clear_color);
+        self.sample_depths.fill(f32::INFINITY);
+        self.prepare_transparency();
+    }
+
+    ///Kopiert den Framebuffer in ein RGBA-Bild, z.B. zum Speichern als PNG.
+    ///Alpha wird wie bei der Fensterausgabe ignoriert und auf 255 gesetzt,
+    ///HDR-Werte über 1.0 werden abgeschnitten.
+    pub fn to_image(&self) -> RgbaImage {
+        RgbaImage::from_fn(self.width as u32, self.height as u32, |x, y| {
+            let pixel = self.pixels[y as usize * self.width + x as usize].to_srgb8();
+            Rgba([pixel.r, pixel.g, pixel.b, 0xFF])
+        })
+    }
+
+    ///Kopiert den Framebuffer ohne Begrenzung der Werte, z.B. zum Speichern als OpenEXR
+    pub fn to_hdr_image(&self) -> Rgba32FImage {
+        Rgba32FImage::from_fn(self.width as u32, self.height as u32, |x, y| {
+            let pixel = self.pixels[y as usize * self.width + x as usize].to_linear();
+            Rgba([pixel.r, pixel.g, pixel.b, 1.0])
+        })
+    }
+
+    ///Schreibt die Pixel als 0xAARRGGBB in `target`, so wie ein Windows-DIB sie erwartet
+    pub fn write_argb(&self, target: &mut [u32]) {
+        for (target, pixel) in target.iter_mut().zip(&self.pixels) {
+            *target = pixel.to_srgb8().to_argb() | 0xFF000000;
+        }
     }
 
     ///Erstellt Dreiecke aus dem gegebenen Polygon und rasterisiert diese in den Frambuffer, mit oder ohne Textur
-    pub(crate) fn draw_polygon(
+    pub fn draw_polygon(&mut self, polygon: &Polygon2D, texture: Option<&Texture>, color: Rgba32F) {
+        self.draw_polygon_with_material(polygon, texture, color, &Material::default());
+    }
+
+    ///Wie `draw_polygon`, aber mit Alpha-Test und Blending aus dem Material
+    pub fn draw_polygon_with_material(
+        &mut self,
+        polygon: &Polygon2D,
+        texture: Option<&Texture>,
+        color: Rgba32F,
+        material: &Material,
+    ) {
+        self.draw_polygon_shaded(polygon, texture, color, material, None);
+    }
+
+    ///Wie `draw_polygon_with_material`, mit PBR-Beleuchtung aus `lights` und `environment`.
+    ///Spiegelung und PBR brauchen Normalen im `Polygon2D`
+    pub fn draw_polygon_shaded(
         &mut self,
         polygon: &Polygon2D,
         texture: Option<&Texture>,
-        color: u32,
+        color: Rgba32F,
+        material: &Material,
+        pbr: Option<&PbrMaterial>,
     ) {
-        if let Some(texture) = texture {
-            // Texturiertes Rendering
-            let triangles = triangulate_ear_clipping(polygon);
-            for (v0, v1, v2) in triangles {
-                self.rasterize_triangle_with_texture(v0.0, v1.0, v2.0, v0.1, v1.1, v2.1, texture);
+        let triangles = triangulate_ear_clipping(polygon);
+        let environment = self.environment.clone();
+        let lights = self.lights.clone();
+        let model = SurfaceModel::new(material, pbr, environment.as_deref(), &lights);
+        for indices in triangles {
+            let [v0, v1, v2] = indices.map(|i| polygon.vertices[i]);
+            let [uv0, uv1, uv2] = indices.map(|i| polygon.uv_coords[i]);
+            let surface = model.and_then(|model| SurfaceTriangle::new(model, polygon, indices));
+            let surface = surface.as_ref();
+
+            if self.msaa != Msaa::Off {
+                // Multisampling, Samples werden in `resolve_msaa` zusammengeführt
+                self.rasterize_triangle_msaa(
+                    v0, v1, v2, uv0, uv1, uv2, texture, color, material, surface,
+                );
+            } else if let Some(texture) = texture {
+                // Texturiertes Rendering
+                self.rasterize_triangle_with_texture(
+                    v0, v1, v2, uv0, uv1, uv2, texture, material, surface,
+                );
+            } else {
+                // Einfarbiges Rendering
+                self.rasterize_triangle(v0, v1, v2, color, material, surface);
             }
-        } else {
-            // Einfarbiges Rendering
-            let triangles = triangulate_ear_clipping(polygon);
-            for (v0, v1, v2) in triangles {
-                self.rasterize_triangle(v0.0, v1.0, v2.0, color);
+        }
+    }
+
+    ///Zeichnet die Umrisskanten des Polygons (ohne die Diagonalen der Triangulierung)
+    pub fn draw_polygon_edges(&mut self, polygon: &Polygon2D, color: Rgba32F, depth_test: bool) {
+        let vertices = &polygon.vertices;
+        for i in 0..vertices.len() {
+            let p0 = vertices[i];
+            let p1 = vertices[(i + 1) % vertices.len()];
+            if self.antialiased_lines {
+                self.draw_line_aa(p0, p1, color, depth_test);
+            } else {
+                self.draw_line(p0, p1, color, depth_test);
             }
         }
     }
 
-    fn rasterize_triangle(&mut self, v0: Point2D, v1: Point2D, v2: Point2D, color: u32) {
+    fn rasterize_triangle(
+        &mut self,
+        v0: Point2D,
+        v1: Point2D,
+        v2: Point2D,
+        color: Rgba32F,
+        material: &Material,
+        surface: Option<&SurfaceTriangle>,
+    ) {
         let v0 = point::snap_to_pixel(v0);
         let v1 = point::snap_to_pixel(v1);
         let v2 = point::snap_to_pixel(v2);
@@ -103,10 +272,11 @@ impl Framebuffer {
 
                     let pixel_index = y as usize * self.width + x as usize;
 
-                    if z_interpolated < self.z_buffer[pixel_index] {
-                        self.z_buffer[pixel_index] = z_interpolated;
-                        self.pixels[pixel_index] = color;
-                    }
+                    let color = match surface {
+                        Some(surface) => surface.shade(color, w0, w1, w2),
+                        None => color,
+                    };
+                    self.write_fragment(pixel_index, z_interpolated, color, material);
                 }
             }
         }
@@ -125,16 +295,15 @@ impl Framebuffer {
         uv1: (f32, f32),
         uv2: (f32, f32),
         texture: &Texture,
+        material: &Material,
+        surface: Option<&SurfaceTriangle>,
     ) {
-        let tex_width = texture.width as f32;
-        let tex_height = texture.height as f32;
-
         // Berechnung der Bounding Box des Dreiecks
         let min_x = v0.x.min(v1.x).min(v2.x).max(0.0) as usize;
         let max_x = v0.x.max(v1.x).max(v2.x).min(self.width as f32) as usize;
         let min_y = v0.y.min(v1.y).min(v2.y).max(0.0) as usize;
         let max_y = v0.y.max(v1.y).max(v2.y).min(self.height as f32) as usize;
-        let det = (v1.x - v0.x) * (v2.y - v0.y) - (v2.x - v0.x) * (v1.y - v0.y);        // Vorberechnung der Determinante für Dreieckskalierung
+        let det = (v1.x - v0.x) * (v2.y - v0.y) - (v2.x - v0.x) * (v1.y - v0.y); // Vorberechnung der Determinante für Dreieckskalierung
 
         if det.abs() < f32::EPSILON {
             return; // Degeneriertes Dreieck
@@ -151,94 +320,61 @@ impl Framebuffer {
                 let w1 = (v2.x - px) * (v0.y - py) - (v0.x - px) * (v2.y - py);
                 let w2 = (v0.x - px) * (v1.y - py) - (v1.x - px) * (v0.y - py);
 
-                // Prüfe ob der Pixel innerhalb des Dreiecks liegt
-                if w0 <= 0.0 && w1 <= 0.0 && w2 <= 0.0 {
-                    // Normiere die baryzentrischen Koordinaten
-                    let denom = 1.0 / det;
-                    let b0 = w0 * denom;
-                    let b1 = w1 * denom;
-                    let b2 = w2 * denom;
+                // Normiere die baryzentrischen Koordinaten
+                let denom = 1.0 / det;
+                let b0 = w0 * denom;
+                let b1 = w1 * denom;
+                let b2 = w2 * denom;
 
+                // Prüfe ob der Pixel innerhalb des Dreiecks liegt (unabhängig von der Umlaufrichtung)
+                if b0 >= 0.0 && b1 >= 0.0 && b2 >= 0.0 {
                     // Interpoliere die UV-Koordinaten
                     let u = b0 * uv0.0 + b1 * uv1.0 + b2 * uv2.0;
                     let v = b0 * uv0.1 + b1 * uv1.1 + b2 * uv2.1;
 
-                    // Skalierung auf Texturgröße
-                    let tex_x = (u * tex_width).clamp(0.0, tex_width - 1.0) as usize;
-                    let tex_y = (v * tex_height).clamp(0.0, tex_height - 1.0) as usize;
-
-                    // Berechne den Textur-Index
-                    let tex_index = (tex_y * texture.width + tex_x) * 4;
-                    let color = &texture.data[tex_index..tex_index + 4];
-
-                    let color_u32 = rgba_to_u32([color[0], color[1], color[2], color[3]]);
+                    let mut color = texture.sample(u, v);
+                    if let Some(surface) = surface {
+                        color = surface.shade(color, b0, b1, b2);
+                    }
 
                     let z = b0 * v0.z + b1 * v1.z + b2 * v2.z;
                     let buffer_index = y * self.width + x;
 
                     // nur Pixel schreiben, die näher sind
-                    if z < self.z_buffer[buffer_index] {
-                        self.z_buffer[buffer_index] = z;
-                        self.pixels[buffer_index] = color_u32;
-                    }
+                    self.write_fragment(buffer_index, z, color, material);
                 }
             }
         }
     }
 }
 
-#[inline(always)]
-fn rgba_to_u32(rgba: [u8; 4]) -> u32 {
-    ((rgba[3] as u32) << 24) | // Alpha
-        ((rgba[0] as u32) << 16) | // Rot
-        ((rgba[1] as u32) << 8)  | // Grün
-        (rgba[2] as u32) // Blau
-}
-
-fn triangulate_ear_clipping(
-    polygon: &Polygon2D,
-) -> Vec<(
-    (Point2D, (f32, f32)),
-    (Point2D, (f32, f32)),
-    (Point2D, (f32, f32)),
-)> {
+///Zerlegt ein projiziertes Polygon per Ear Clipping in Dreiecke, als Indizes der Ecken
+pub fn triangulate_ear_clipping(polygon: &Polygon2D) -> Vec<[usize; 3]> {
     let mut vertices = polygon.vertices.clone();
-    let mut uv_coords = polygon.uv_coords.clone();
+    let mut indices: Vec<usize> = (0..vertices.len()).collect();
     let mut triangles = Vec::new();
     // Quadrat Sonderfall –> einfache Zwei-Dreiecks-Zerlegung
     if vertices.len() == 4 {
-        return vec![
-            (
-                (vertices[0], uv_coords[0]),
-                (vertices[1], uv_coords[1]),
-                (vertices[2], uv_coords[2]),
-            ),
-            (
-                (vertices[2], uv_coords[2]),
-                (vertices[3], uv_coords[3]),
-                (vertices[0], uv_coords[0]),
-            ),
-        ];
+        return vec![[0, 1, 2], [2, 3, 0]];
     }
 
-    ensure_ccw(&mut vertices);
+    ensure_ccw(&mut vertices, &mut indices);
 
     while vertices.len() > 3 {
         let mut ear_found = false;
 
         for i in 0..vertices.len() {
-            let prev = vertices[(i + vertices.len() - 1) % vertices.len()];
-            let prev_uv = uv_coords[(i + uv_coords.len() - 1) % uv_coords.len()];
+            let prev_i = (i + vertices.len() - 1) % vertices.len();
+            let next_i = (i + 1) % vertices.len();
+            let prev = vertices[prev_i];
             let curr = vertices[i];
-            let curr_uv = uv_coords[i];
-            let next = vertices[(i + 1) % vertices.len()];
-            let next_uv = uv_coords[(i + 1) % uv_coords.len()];
+            let next = vertices[next_i];
 
             if is_ear(prev, curr, next, &vertices) {
-                triangles.push(((prev, prev_uv), (curr, curr_uv), (next, next_uv)));
+                triangles.push([indices[prev_i], indices[i], indices[next_i]]);
 
                 vertices.remove(i);
-                uv_coords.remove(i);
+                indices.remove(i);
 
                 ear_found = true;
                 break;
@@ -252,11 +388,7 @@ fn triangulate_ear_clipping(
     }
 
     if vertices.len() == 3 {
-        triangles.push((
-            (vertices[0], uv_coords[0]),
-            (vertices[1], uv_coords[1]),
-            (vertices[2], uv_coords[2]),
-        ));
+        triangles.push([indices[0], indices[1], indices[2]]);
     }
 
     triangles
@@ -281,11 +413,7 @@ fn is_ear(prev: Point2D, curr: Point2D, next: Point2D, vertices: &[Point2D]) ->
 fn is_ccw(p1: Point2D, p2: Point2D, p3: Point2D) -> bool {
     let cross_product = (p2.x - p1.x) * (p3.y - p1.y) - (p2.y - p1.y) * (p3.x - p1.x);
 
-    if cross_product > 0.0 {
-        true
-    } else {
-        false
-    }
+    cross_product > 0.0
 }
 
 #[inline(always)]
@@ -296,17 +424,15 @@ fn is_polygon_ccw(vertices: &[Point2D]) -> bool {
         let next = vertices[(i + 1) % vertices.len()];
         sum += (next.x - current.x) * (next.y + current.y);
     }
-    if sum > 0.0 {
-        true
-    } else {
-        false
-    }
+    // Die Summe ist die negative doppelte Fläche, gleiche Orientierung wie in `is_ccw`
+    sum < 0.0
 }
 
 #[inline(always)]
-fn ensure_ccw(vertices: &mut Vec<Point2D>) {
+fn ensure_ccw(vertices: &mut [Point2D], indices: &mut [usize]) {
     if !is_polygon_ccw(vertices) {
         vertices.reverse();
+        indices.reverse(); // Indizes müssen zu ihren Ecken passen
     }
 }
 
@@ -0,0 +1,77 @@
+//! Rendern ohne Fenster, z.B. eine Kamerafahrt als Bildfolge oder Video für Drehteller,
+//! Vorschauvideos im CI und Benchmarks.
+//! Läuft auf allen Plattformen
+
+use rake::render::render_scene;
+use rake::{Camera, CameraPath, ExportFormat, Framebuffer, Point, Rgba32F, Texture, object};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+///`--render-path datei [--out ziel] [--format png|y4m|avi|ffmpeg] [--fps 30] [--size 800x600]
+///[--obj datei] [--texture datei]` schreibt jedes Bild der Kamerafahrt nach `ziel`. Ohne
+///`--format` entscheidet die Endung: `.y4m`, `.avi`, über ffmpeg `.mp4` usw., sonst ein Ordner
+///mit `frame_00000.png` usw.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let option = |name: &str| {
+        let index = args.iter().position(|arg| arg == name)?;
+        args.get(index + 1).map(String::as_str)
+    };
+    let path_file = option("--render-path").ok_or("--render-path needs a file")?;
+    let out = Path::new(option("--out").unwrap_or("frames"));
+    let fps: f32 = option("--fps")
+        .unwrap_or("30")
+        .parse()
+        .map_err(|_| "--fps needs a number")?;
+    let (width, height) = option("--size")
+        .unwrap_or("800x600")
+        .split_once('x')
+        .and_then(|(w, h)| Some((w.parse::<usize>().ok()?, h.parse::<usize>().ok()?)))
+        .filter(|&(width, height)| width > 0 && height > 0)
+        .ok_or("--size needs WIDTHxHEIGHT")?;
+    let format = match option("--format") {
+        Some(name) => ExportFormat::from_name(name).ok_or(format!("Unknown format: {name}"))?,
+        None => ExportFormat::from_path(out),
+    };
+
+    let camera_path = CameraPath::load(path_file)?;
+    let mut polygons = object::load_obj(option("--obj").unwrap_or("capsule.obj"))?;
+    let texture = option("--texture").or(option("--obj").is_none().then_some("capsule0.jpg"));
+    if let Some(texture) = texture {
+        let texture = Arc::new(Texture::from_file(texture));
+        for polygon in polygons.iter_mut() {
+            polygon.set_texture(texture.clone());
+        }
+    }
+
+    let mut camera = Camera::new(
+        Point::new(0.0, 0.0, -5.0),
+        Point::new(0.0, 0.0, -1.0),
+        Point::new(0.0, 1.0, 0.0),
+        60.0,
+        width as f32 / height as f32,
+        0.1,
+        100.0,
+    );
+    let mut framebuffer = Framebuffer::<Rgba32F>::with_format(width, height);
+    let mut sink = format.create(out, width, height, fps)?;
+
+    let frames = camera_path.frame_count(fps);
+    let started = Instant::now();
+    for frame in 0..frames {
+        let time = camera_path.start() + frame as f32 / fps;
+        if let Some(pose) = camera_path.sample(time) {
+            pose.apply(&mut camera);
+        }
+        render_scene(&polygons, &camera, &mut framebuffer);
+        sink.write_frame(&framebuffer.to_image())?;
+    }
+    sink.finish()?;
+    let seconds = started.elapsed().as_secs_f32();
+    println!(
+        "{frames} Bilder nach {} in {seconds:.1} s ({:.1} Bilder/s)",
+        out.display(),
+        frames as f32 / seconds.max(1e-6)
+    );
+    Ok(())
+}
@@ -0,0 +1,395 @@
+use crate::StandardView;
+use crate::cvar::exec_lines;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Taste, Maustaste oder Mausrad, unabhängig vom Betriebssystem. Die Namen folgen Quake
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Key {
+    Char(char), // 'A' bis 'Z' und '0' bis '9', immer Großbuchstaben
+    F(u8),      // Funktionstasten 1 bis 12
+    Left,
+    Up,
+    Right,
+    Down,
+    Space,
+    Shift,
+    Control,
+    Alt,
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    KeypadPlus,
+    KeypadMinus,
+    Grave,     // Links neben der 1, ` auf amerikanischer Tastatur
+    Backslash, // ^ auf deutscher Tastatur
+    MouseLeft,
+    MouseRight,
+    MouseMiddle,
+    Mouse4,
+    Mouse5,
+    WheelUp,
+    WheelDown,
+}
+
+const KEY_NAMES: [(Key, &str); 23] = [
+    (Key::Left, "leftarrow"),
+    (Key::Up, "uparrow"),
+    (Key::Right, "rightarrow"),
+    (Key::Down, "downarrow"),
+    (Key::Space, "space"),
+    (Key::Shift, "shift"),
+    (Key::Control, "ctrl"),
+    (Key::Alt, "alt"),
+    (Key::Enter, "enter"),
+    (Key::Escape, "escape"),
+    (Key::Tab, "tab"),
+    (Key::Backspace, "backspace"),
+    (Key::KeypadPlus, "kp_plus"),
+    (Key::KeypadMinus, "kp_minus"),
+    (Key::Grave, "grave"),
+    (Key::Backslash, "backslash"),
+    (Key::MouseLeft, "mouse1"),
+    (Key::MouseRight, "mouse2"),
+    (Key::MouseMiddle, "mouse3"),
+    (Key::Mouse4, "mouse4"),
+    (Key::Mouse5, "mouse5"),
+    (Key::WheelUp, "mwheelup"),
+    (Key::WheelDown, "mwheeldown"),
+];
+
+impl Key {
+    ///Name wie in der Konfigurationsdatei, z.B. "w", "space", "mouse1", "f5"
+    pub fn from_name(name: &str) -> Option<Key> {
+        let name = name.to_ascii_lowercase();
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return c
+                .is_ascii_alphanumeric()
+                .then(|| Key::Char(c.to_ascii_uppercase()));
+        }
+        if let Some(number) = name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+            return (1..=12).contains(&number).then_some(Key::F(number));
+        }
+        KEY_NAMES
+            .iter()
+            .find(|(_, key_name)| *key_name == name)
+            .map(|(key, _)| *key)
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Key::Char(c) => c.to_ascii_lowercase().to_string(),
+            Key::F(number) => format!("f{number}"),
+            key => KEY_NAMES
+                .iter()
+                .find(|(other, _)| *other == key)
+                .map(|(_, name)| name.to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Was eine Taste auslöst. Gehaltene Aktionen wie die Bewegung stehen in `ActionState`,
+/// die übrigen werden beim Drücken einmal ausgeführt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Action {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    MoveUp,   // Flugmodus
+    MoveDown, // Flugmodus
+    RollLeft,
+    RollRight,
+    PanLeft, // Orbit-Kamera: Ziel verschieben
+    PanRight,
+    PanUp,
+    PanDown,
+    ZoomIn,
+    ZoomOut,
+    ToggleMouseLook,
+    ToggleConsole,
+    CycleController,
+    FrameScene,
+    ToggleProjection,
+    CycleRenderMode,
+    CycleToneMapping,
+    ToggleSsao,
+    ExposureUp,
+    ExposureDown,
+    View(StandardView),
+    PlayPath, // Kamerafahrt starten oder anhalten
+}
+
+const ACTION_NAMES: [(Action, &str); 33] = [
+    (Action::MoveForward, "forward"),
+    (Action::MoveBack, "back"),
+    (Action::MoveLeft, "moveleft"),
+    (Action::MoveRight, "moveright"),
+    (Action::Jump, "jump"),
+    (Action::MoveUp, "moveup"),
+    (Action::MoveDown, "movedown"),
+    (Action::RollLeft, "rollleft"),
+    (Action::RollRight, "rollright"),
+    (Action::PanLeft, "panleft"),
+    (Action::PanRight, "panright"),
+    (Action::PanUp, "panup"),
+    (Action::PanDown, "pandown"),
+    (Action::ZoomIn, "zoomin"),
+    (Action::ZoomOut, "zoomout"),
+    (Action::ToggleMouseLook, "mouselook"),
+    (Action::ToggleConsole, "console"),
+    (Action::CycleController, "controller"),
+    (Action::FrameScene, "frame"),
+    (Action::ToggleProjection, "projection"),
+    (Action::CycleRenderMode, "rendermode"),
+    (Action::CycleToneMapping, "tonemap"),
+    (Action::ToggleSsao, "ssao"),
+    (Action::ExposureUp, "exposureup"),
+    (Action::ExposureDown, "exposuredown"),
+    (Action::View(StandardView::Front), "view_front"),
+    (Action::View(StandardView::Back), "view_back"),
+    (Action::View(StandardView::Left), "view_left"),
+    (Action::View(StandardView::Right), "view_right"),
+    (Action::View(StandardView::Top), "view_top"),
+    (Action::View(StandardView::Bottom), "view_bottom"),
+    (Action::View(StandardView::Isometric), "view_iso"),
+    (Action::PlayPath, "playpath"),
+];
+
+impl Action {
+    pub fn from_name(name: &str) -> Option<Action> {
+        let name = name.to_ascii_lowercase();
+        ACTION_NAMES
+            .iter()
+            .find(|(_, action_name)| *action_name == name)
+            .map(|(action, _)| *action)
+    }
+
+    pub fn name(self) -> &'static str {
+        ACTION_NAMES
+            .iter()
+            .find(|(action, _)| *action == self)
+            .map(|(_, name)| *name)
+            .unwrap_or_default()
+    }
+
+    ///Feste Nummer für Aufnahmen, neue Aktionen daher nur hinten in `ACTION_NAMES` anfügen
+    pub(crate) fn index(self) -> usize {
+        ACTION_NAMES
+            .iter()
+            .position(|(action, _)| *action == self)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn from_index(index: usize) -> Option<Action> {
+        ACTION_NAMES.get(index).map(|(action, _)| *action)
+    }
+}
+
+/// Zuordnung von Tasten zu Aktionen. Jede Taste löst höchstens eine Aktion aus,
+/// eine Aktion kann auf mehreren Tasten liegen
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bindings {
+    keys: BTreeMap<Key, Action>,
+}
+
+impl Default for Bindings {
+    ///Die bisherige feste Belegung des Viewers
+    fn default() -> Self {
+        let mut bindings = Bindings::new();
+        for (key, action) in [
+            ("w", "forward"),
+            ("s", "back"),
+            ("a", "moveleft"),
+            ("d", "moveright"),
+            ("v", "jump"),
+            ("space", "moveup"),
+            ("shift", "movedown"),
+            ("q", "rollleft"),
+            ("e", "rollright"),
+            ("leftarrow", "panleft"),
+            ("rightarrow", "panright"),
+            ("uparrow", "panup"),
+            ("downarrow", "pandown"),
+            ("mwheelup", "zoomin"),
+            ("mwheeldown", "zoomout"),
+            ("l", "mouselook"),
+            ("grave", "console"),
+            ("backslash", "console"),
+            ("c", "controller"),
+            ("f", "frame"),
+            ("p", "projection"),
+            ("m", "rendermode"),
+            ("t", "tonemap"),
+            ("o", "ssao"),
+            ("kp_plus", "exposureup"),
+            ("kp_minus", "exposuredown"),
+            ("1", "view_front"),
+            ("2", "view_back"),
+            ("3", "view_left"),
+            ("4", "view_right"),
+            ("5", "view_top"),
+            ("6", "view_bottom"),
+            ("7", "view_iso"),
+            ("k", "playpath"),
+        ] {
+            bindings.bind(
+                Key::from_name(key).unwrap(),
+                Action::from_name(action).unwrap(),
+            );
+        }
+        bindings
+    }
+}
+
+impl Bindings {
+    ///Ohne jede Belegung, siehe `Default` für die Standardbelegung
+    pub fn new() -> Self {
+        Bindings {
+            keys: BTreeMap::new(),
+        }
+    }
+
+    pub fn bind(&mut self, key: Key, action: Action) {
+        self.keys.insert(key, action);
+    }
+
+    pub fn unbind(&mut self, key: Key) {
+        self.keys.remove(&key);
+    }
+
+    pub fn action(&self, key: Key) -> Option<Action> {
+        self.keys.get(&key).copied()
+    }
+
+    pub fn keys_for(&self, action: Action) -> Vec<Key> {
+        self.iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Key, Action)> + '_ {
+        self.keys.iter().map(|(key, action)| (*key, *action))
+    }
+
+    ///`bind taste aktion`, `bind taste` zeigt die Belegung, `unbind taste`, `unbindall` und `bindlist`
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        let key = |name: &str| Key::from_name(name).ok_or_else(|| format!("Unknown key: {name}"));
+        match words.as_slice() {
+            [] => Ok(String::new()),
+            ["bind", name] => {
+                let key = key(name)?;
+                Ok(match self.action(key) {
+                    Some(action) => format!("{} is bound to {}", key.name(), action.name()),
+                    None => format!("{} is not bound", key.name()),
+                })
+            }
+            ["bind", name, action] => {
+                let key = key(name)?;
+                let action =
+                    Action::from_name(action).ok_or_else(|| format!("Unknown action: {action}"))?;
+                self.bind(key, action);
+                Ok(String::new())
+            }
+            ["unbind", name] => {
+                self.unbind(key(name)?);
+                Ok(String::new())
+            }
+            ["unbindall"] => {
+                self.keys.clear();
+                Ok(String::new())
+            }
+            ["bindlist"] => Ok(self.to_config().trim_end().to_string()),
+            _ => Err(format!("Unknown command: {line}")),
+        }
+    }
+
+    ///Führt eine Konfiguration Zeile für Zeile aus, siehe `cvar::exec_lines`
+    pub fn exec_config(&mut self, text: &str) -> Vec<String> {
+        exec_lines(text, |line| self.execute(line))
+    }
+
+    ///Beginnt mit `unbindall`, damit das Laden genau diese Belegung ergibt
+    pub fn to_config(&self) -> String {
+        let mut config = String::from("unbindall\n");
+        for (key, action) in self.iter() {
+            config += &format!("bind {} {}\n", key.name(), action.name());
+        }
+        config
+    }
+
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<Vec<String>, String> {
+        let text = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("Failed to read bindings: {}", e))?;
+        Ok(self.exec_config(&text))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        std::fs::write(path.as_ref(), self.to_config())
+            .map_err(|e| format!("Failed to write bindings: {}", e))
+    }
+}
+
+/// Gehaltene Aktionen eines Simulationsschritts, das ersetzt das rohe Tastenfeld
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActionState {
+    down: BTreeSet<Action>,
+}
+
+impl ActionState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Aktionen aller gedrückten Tasten
+    pub fn from_keys<'a>(bindings: &Bindings, keys: impl IntoIterator<Item = &'a Key>) -> Self {
+        ActionState {
+            down: keys
+                .into_iter()
+                .filter_map(|key| bindings.action(*key))
+                .collect(),
+        }
+    }
+
+    pub fn press(&mut self, action: Action) {
+        self.down.insert(action);
+    }
+
+    pub fn release(&mut self, action: Action) {
+        self.down.remove(&action);
+    }
+
+    pub fn is_down(&self, action: Action) -> bool {
+        self.down.contains(&action)
+    }
+
+    ///1.0, -1.0 oder 0.0, wenn beide oder keine gehalten werden
+    pub fn axis(&self, positive: Action, negative: Action) -> f32 {
+        (self.is_down(positive) as i32 - self.is_down(negative) as i32) as f32
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Action> + '_ {
+        self.down.iter().copied()
+    }
+
+    ///Ein Bit pro Aktion, siehe `Action::index`
+    pub(crate) fn to_bits(&self) -> u64 {
+        self.iter()
+            .fold(0, |bits, action| bits | 1 << action.index())
+    }
+
+    pub(crate) fn from_bits(bits: u64) -> Self {
+        ActionState {
+            down: (0..64)
+                .filter(|index| bits & 1 << index != 0)
+                .filter_map(Action::from_index)
+                .collect(),
+        }
+    }
+}
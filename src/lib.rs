@@ -0,0 +1,66 @@
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+pub mod point;
+pub use point::Point;
+pub use point::Point2D;
+
+pub mod math;
+pub use math::{Mat3, Mat4, Quat, Transform, Vec2, Vec3, Vec4};
+
+pub mod polygon;
+pub use polygon::Polygon;
+pub use polygon::Polygon2D;
+pub use polygon::Tangent;
+
+pub mod camera;
+pub use camera::Camera;
+pub use camera::{MovementSettings, Projection, StandardView};
+
+pub mod camera_path;
+pub use camera_path::{CameraPath, CameraPose, Easing, Interpolation, Keyframe};
+
+pub mod antialiasing;
+pub mod background;
+pub mod collision;
+pub mod color;
+pub mod console;
+pub mod controller;
+pub mod cvar;
+pub mod demo;
+pub mod environment;
+pub mod export;
+pub mod framebuffer;
+pub mod input;
+pub mod line;
+pub mod material;
+pub mod object;
+pub mod oit;
+pub mod pbr;
+pub mod post;
+pub mod render;
+pub mod ssao;
+pub mod text;
+pub mod texture;
+pub mod tonemap;
+
+mod shading;
+
+pub use antialiasing::Msaa;
+pub use background::{Background, Cubemap};
+pub use collision::{Capsule, CollisionMesh};
+pub use color::{Color, ColorSpace, Rgba8, Rgba32F};
+pub use console::Console;
+pub use controller::{CameraController, FlyController, FpsController, OrbitController};
+pub use cvar::{Cvar, CvarValue, Cvars};
+pub use demo::{CameraState, Demo, DemoTick};
+pub use environment::Environment;
+pub use export::{ExportFormat, FrameSink};
+pub use framebuffer::Framebuffer;
+pub use framebuffer::RenderMode;
+pub use input::{Action, ActionState, Bindings, Key};
+pub use material::{BlendMode, Material};
+pub use oit::TransparencyMode;
+pub use pbr::{DirectionalLight, PbrMaterial};
+pub use post::{PostEffect, PostStage};
+pub use texture::{HdrTexture, Texture};
+pub use tonemap::ToneMapping;
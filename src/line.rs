@@ -0,0 +1,167 @@
+use crate::color::{Color, Rgba32F};
+use crate::{Framebuffer, Point2D};
+
+/// Kleiner Tiefen-Offset, damit Kanten nicht vom eigenen Polygon verdeckt werden
+const LINE_DEPTH_BIAS: f32 = 2e-4;
+
+impl<C: Color> Framebuffer<C> {
+    ///Zeichnet eine Linie mit Bresenham. Mit `depth_test` werden nur Pixel geschrieben,
+    ///die nicht hinter dem Z-Buffer liegen (der Z-Buffer selbst bleibt unverändert)
+    pub fn draw_line(&mut self, p0: Point2D, p1: Point2D, color: Rgba32F, depth_test: bool) {
+        let Some((p0, p1)) = self.clip_line(p0, p1) else {
+            return;
+        };
+
+        let (mut x0, mut y0) = (p0.x.round() as i32, p0.y.round() as i32);
+        let (x1, y1) = (p1.x.round() as i32, p1.y.round() as i32);
+
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let steps = dx.max(-dy).max(1) as f32;
+        let mut err = dx + dy;
+        let mut step = 0.0;
+
+        loop {
+            let z = p0.z + (p1.z - p0.z) * (step / steps);
+            self.plot_line_pixel(x0, y0, z, color, 1.0, depth_test);
+
+            if x0 == x1 && y0 == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x0 += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y0 += sy;
+            }
+            step += 1.0;
+        }
+    }
+
+    ///Zeichnet eine geglättete Linie nach Xiaolin Wu. Die Abdeckung jedes Pixels wird
+    ///mit der vorhandenen Farbe gemischt
+    pub fn draw_line_aa(&mut self, p0: Point2D, p1: Point2D, color: Rgba32F, depth_test: bool) {
+        let Some((mut p0, mut p1)) = self.clip_line(p0, p1) else {
+            return;
+        };
+
+        let steep = (p1.y - p0.y).abs() > (p1.x - p0.x).abs();
+        if steep {
+            (p0.x, p0.y) = (p0.y, p0.x);
+            (p1.x, p1.y) = (p1.y, p1.x);
+        }
+        if p0.x > p1.x {
+            (p0, p1) = (p1, p0);
+        }
+
+        let dx = p1.x - p0.x;
+        let gradient = if dx.abs() < f32::EPSILON {
+            1.0
+        } else {
+            (p1.y - p0.y) / dx
+        };
+
+        // Entlang der Hauptachse laufen und die beiden benachbarten Pixel anteilig setzen
+        let x_start = p0.x.round() as i32;
+        let x_end = p1.x.round() as i32;
+        let mut y = p0.y + gradient * (x_start as f32 - p0.x);
+
+        for x in x_start..=x_end {
+            let t = if dx.abs() < f32::EPSILON {
+                0.0
+            } else {
+                ((x as f32 - p0.x) / dx).clamp(0.0, 1.0)
+            };
+            let z = p0.z + (p1.z - p0.z) * t;
+
+            let y_floor = y.floor();
+            let coverage = y - y_floor;
+            let (px, py) = (x, y_floor as i32);
+
+            if steep {
+                self.plot_line_pixel(py, px, z, color, 1.0 - coverage, depth_test);
+                self.plot_line_pixel(py + 1, px, z, color, coverage, depth_test);
+            } else {
+                self.plot_line_pixel(px, py, z, color, 1.0 - coverage, depth_test);
+                self.plot_line_pixel(px, py + 1, z, color, coverage, depth_test);
+            }
+            y += gradient;
+        }
+    }
+
+    fn plot_line_pixel(
+        &mut self,
+        x: i32,
+        y: i32,
+        z: f32,
+        color: Rgba32F,
+        coverage: f32,
+        depth_test: bool,
+    ) {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+        let index = y as usize * self.width + x as usize;
+
+        if depth_test && z > self.z_buffer[index] + LINE_DEPTH_BIAS {
+            return;
+        }
+
+        self.pixels[index] = if coverage >= 1.0 {
+            C::from_linear(color)
+        } else {
+            let pixel = self.pixels[index].to_linear();
+            C::from_linear(pixel.lerp(color, coverage.clamp(0.0, 1.0)))
+        };
+    }
+
+    ///Schneidet die Linie auf den Bildschirm zu (Liang-Barsky), damit lange Linien
+    ///außerhalb des Bildes nicht Pixel für Pixel abgelaufen werden
+    fn clip_line(&self, p0: Point2D, p1: Point2D) -> Option<(Point2D, Point2D)> {
+        if !(p0.x.is_finite() && p0.y.is_finite() && p1.x.is_finite() && p1.y.is_finite()) {
+            return None;
+        }
+
+        let (min_x, max_x) = (0.0, self.width as f32 - 1.0);
+        let (min_y, max_y) = (0.0, self.height as f32 - 1.0);
+        let dx = p1.x - p0.x;
+        let dy = p1.y - p0.y;
+
+        let mut t0: f32 = 0.0;
+        let mut t1: f32 = 1.0;
+        for (p, q) in [
+            (-dx, p0.x - min_x),
+            (dx, max_x - p0.x),
+            (-dy, p0.y - min_y),
+            (dy, max_y - p0.y),
+        ] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None; // parallel und außerhalb
+                }
+            } else {
+                let r = q / p;
+                if p < 0.0 {
+                    t0 = t0.max(r);
+                } else {
+                    t1 = t1.min(r);
+                }
+            }
+        }
+        if t0 > t1 {
+            return None;
+        }
+
+        let lerp = |t: f32| Point2D {
+            x: p0.x + dx * t,
+            y: p0.y + dy * t,
+            z: p0.z + (p1.z - p0.z) * t,
+        };
+        Some((lerp(t0), lerp(t1)))
+    }
+}
@@ -0,0 +1,97 @@
+use crate::color::{Color, Rgba32F};
+
+/// Wie ein Fragment mit dem vorhandenen Pixel verrechnet wird
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    #[default]
+    Opaque, // Überschreibt den Pixel und schreibt den Z-Buffer
+    Alpha,         // src * a + dst * (1 - a)
+    Additive,      // dst + src * a
+    Multiply,      // src * dst
+    Premultiplied, // src + dst * (1 - a), Farbe ist bereits mit Alpha multipliziert
+}
+
+/// Oberflächeneigenschaften eines Polygons, die nicht von der Textur kommen
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Material {
+    pub alpha_cutoff: Option<f32>, // Alpha-Test: Fragmente mit Alpha darunter werden verworfen (0.0 - 1.0)
+    pub blend_mode: BlendMode,
+    pub reflectivity: f32, // Anteil der gespiegelten Umgebung bei senkrechtem Blick (0.0 - 1.0)
+    pub roughness: f32,    // 0.0 = Spiegel, 1.0 = ganz verschwommene Spiegelung
+}
+
+impl Material {
+    pub fn new(blend_mode: BlendMode) -> Self {
+        Material {
+            alpha_cutoff: None,
+            blend_mode,
+            reflectivity: 0.0,
+            roughness: 0.0,
+        }
+    }
+
+    pub fn with_alpha_cutoff(mut self, cutoff: f32) -> Self {
+        self.alpha_cutoff = Some(cutoff);
+        self
+    }
+
+    ///Spiegelt die Umgebung des Framebuffers (`Framebuffer::environment`)
+    pub fn with_reflection(mut self, reflectivity: f32, roughness: f32) -> Self {
+        self.reflectivity = reflectivity;
+        self.roughness = roughness;
+        self
+    }
+
+    pub fn is_reflective(&self) -> bool {
+        self.reflectivity > 0.0
+    }
+
+    /// Transparente Materialien werden nach den opaken und von hinten nach vorne gezeichnet
+    pub fn is_transparent(&self) -> bool {
+        self.blend_mode != BlendMode::Opaque
+    }
+
+    #[inline(always)]
+    pub fn passes_alpha_test(&self, color: Rgba32F) -> bool {
+        match self.alpha_cutoff {
+            Some(cutoff) => color.a >= cutoff,
+            None => true,
+        }
+    }
+
+    ///Alpha-Test, Tiefentest und Blending für ein Fragment.
+    ///Geblendete Fragmente schreiben keine Tiefe, damit dahinterliegende Transparenz sichtbar bleibt
+    #[inline(always)]
+    pub(crate) fn write_fragment<C: Color>(
+        &self,
+        pixel: &mut C,
+        depth: &mut f32,
+        z: f32,
+        color: Rgba32F,
+    ) {
+        if !self.passes_alpha_test(color) || z >= *depth {
+            return;
+        }
+
+        if self.blend_mode == BlendMode::Opaque {
+            *depth = z;
+            *pixel = C::from_linear(color);
+        } else {
+            *pixel = C::from_linear(blend_pixel(pixel.to_linear(), color, self.blend_mode));
+        }
+    }
+}
+
+///Verrechnet `src` mit `dst` nach dem Blend-Modus, das Ergebnis ist immer deckend.
+///Additive Werte werden nicht begrenzt, das übernimmt erst ein 8-Bit-Pixelformat
+#[inline(always)]
+pub fn blend_pixel(dst: Rgba32F, src: Rgba32F, mode: BlendMode) -> Rgba32F {
+    let blended = match mode {
+        BlendMode::Opaque => src,
+        BlendMode::Alpha => dst.lerp(src, src.a),
+        BlendMode::Additive => dst + src * src.a,
+        BlendMode::Multiply => dst * src,
+        BlendMode::Premultiplied => src + dst * (1.0 - src.a),
+    };
+    blended.with_alpha(1.0)
+}
@@ -0,0 +1,849 @@
+//! Vektoren und Matrizen.
+//!
+//! Konvention:
+//! - Zeilenvektoren, ein Punkt wird von links multipliziert: `p' = p · M`.
+//!   Die Translation steht deshalb in Zeile 3, `a * b` wendet erst `a` und dann `b` an.
+//! - Matrizen werden zeilenweise gespeichert, `data[zeile][spalte]`.
+//! - Linkshändiges Koordinatensystem: im View-Space zeigt x nach rechts, y nach oben und
+//!   z in den Bildschirm hinein. Winkel drehen von der ersten zur zweiten Achse der Ebene
+//!   (`rotate_z` dreht x nach y).
+
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Komponentenweise Operatoren und die gemeinsamen Methoden aller Vektoren
+macro_rules! vector {
+    ($name:ident { $($field:ident),+ }) => {
+        impl $name {
+            pub const ZERO: $name = $name { $($field: 0.0),+ };
+
+            pub fn new($($field: f32),+) -> Self {
+                $name { $($field),+ }
+            }
+
+            pub fn dot(self, other: $name) -> f32 {
+                0.0 $(+ self.$field * other.$field)+
+            }
+
+            pub fn magnitude(&self) -> f32 {
+                self.dot(*self).sqrt()
+            }
+
+            ///Ein Nullvektor ergibt NaN
+            pub fn normalize(self) -> $name {
+                let magnitude = self.magnitude();
+                $name { $($field: self.$field / magnitude),+ }
+            }
+
+            pub fn clamp_length(&self, max: f32) -> $name {
+                let length = self.magnitude();
+                if length > max {
+                    return self * (max / length);
+                }
+                *self
+            }
+
+            pub fn lerp(self, other: $name, t: f32) -> $name {
+                self + (other - self) * t
+            }
+        }
+
+        impl Add for $name {
+            type Output = $name;
+            fn add(self, other: $name) -> $name {
+                $name { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+            fn sub(self, other: $name) -> $name {
+                $name { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl Neg for $name {
+            type Output = $name;
+            fn neg(self) -> $name {
+                $name { $($field: -self.$field),+ }
+            }
+        }
+
+        impl Mul<f32> for $name {
+            type Output = $name;
+            fn mul(self, scalar: f32) -> $name {
+                $name { $($field: self.$field * scalar),+ }
+            }
+        }
+
+        impl Mul<f32> for &$name {
+            type Output = $name;
+            fn mul(self, scalar: f32) -> $name {
+                *self * scalar
+            }
+        }
+
+        impl Mul<$name> for f32 {
+            type Output = $name;
+            fn mul(self, vector: $name) -> $name {
+                vector * self
+            }
+        }
+
+        ///Komponentenweise
+        impl Mul for $name {
+            type Output = $name;
+            fn mul(self, other: $name) -> $name {
+                $name { $($field: self.$field * other.$field),+ }
+            }
+        }
+
+        impl Div<f32> for $name {
+            type Output = $name;
+            fn div(self, scalar: f32) -> $name {
+                $name { $($field: self.$field / scalar),+ }
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: $name) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: $name) {
+                *self = *self - other;
+            }
+        }
+
+        impl MulAssign<f32> for $name {
+            fn mul_assign(&mut self, scalar: f32) {
+                *self = *self * scalar;
+            }
+        }
+    };
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Vec4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+vector!(Vec2 { x, y });
+vector!(Vec3 { x, y, z });
+vector!(Vec4 { x, y, z, w });
+
+impl Vec2 {
+    ///z-Komponente des Kreuzprodukts, positiv wenn `other` links von `self` liegt
+    pub fn perp_dot(self, other: Vec2) -> f32 {
+        self.x * other.y - self.y * other.x
+    }
+}
+
+impl Vec3 {
+    pub fn cross(self, other: Vec3) -> Vec3 {
+        Vec3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    pub fn extend(self, w: f32) -> Vec4 {
+        Vec4::new(self.x, self.y, self.z, w)
+    }
+}
+
+impl Vec4 {
+    pub fn truncate(self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+
+    ///Perspektivische Division, bei w = 0 (Richtungen) bleibt der Vektor unverändert
+    pub fn project(self) -> Vec3 {
+        if self.w != 0.0 {
+            self.truncate() / self.w
+        } else {
+            self.truncate()
+        }
+    }
+}
+
+/// 3x3-Matrix für Rotation und Skalierung ohne Translation, z.B. für Normalen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat3 {
+    pub data: [[f32; 3]; 3],
+}
+
+impl Mat3 {
+    pub const IDENTITY: Mat3 = Mat3 {
+        data: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    };
+
+    pub fn from_rows(rows: [Vec3; 3]) -> Self {
+        Mat3 {
+            data: rows.map(|row| [row.x, row.y, row.z]),
+        }
+    }
+
+    pub fn row(&self, index: usize) -> Vec3 {
+        let [x, y, z] = self.data[index];
+        Vec3::new(x, y, z)
+    }
+
+    pub fn transpose(&self) -> Mat3 {
+        Mat3 {
+            data: std::array::from_fn(|i| std::array::from_fn(|j| self.data[j][i])),
+        }
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.row(0).dot(self.row(1).cross(self.row(2)))
+    }
+
+    ///Über die Adjunkte, `None` bei singulären Matrizen
+    pub fn inverse(&self) -> Option<Mat3> {
+        let (r0, r1, r2) = (self.row(0), self.row(1), self.row(2));
+        let determinant = r0.dot(r1.cross(r2));
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+        // Die Kreuzprodukte sind die Spalten der Inversen
+        let columns = Mat3::from_rows([r1.cross(r2), r2.cross(r0), r0.cross(r1)]);
+        Some(columns.transpose() * (1.0 / determinant))
+    }
+}
+
+impl Mul for Mat3 {
+    type Output = Mat3;
+    fn mul(self, other: Mat3) -> Mat3 {
+        Mat3 {
+            data: std::array::from_fn(|i| {
+                std::array::from_fn(|j| (0..3).map(|k| self.data[i][k] * other.data[k][j]).sum())
+            }),
+        }
+    }
+}
+
+impl Mul<f32> for Mat3 {
+    type Output = Mat3;
+    fn mul(self, scalar: f32) -> Mat3 {
+        Mat3 {
+            data: self.data.map(|row| row.map(|value| value * scalar)),
+        }
+    }
+}
+
+impl Mul<Mat3> for Vec3 {
+    type Output = Vec3;
+    fn mul(self, matrix: Mat3) -> Vec3 {
+        matrix.row(0) * self.x + matrix.row(1) * self.y + matrix.row(2) * self.z
+    }
+}
+
+/// 4x4-Matrix für affine Transformationen und Projektionen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat4 {
+    pub data: [[f32; 4]; 4],
+}
+
+impl Mat4 {
+    pub const IDENTITY: Mat4 = Mat4 {
+        data: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+    };
+
+    pub fn identity() -> Self {
+        Mat4::IDENTITY
+    }
+
+    pub fn from_rows(rows: [Vec4; 4]) -> Self {
+        Mat4 {
+            data: rows.map(|row| [row.x, row.y, row.z, row.w]),
+        }
+    }
+
+    pub fn row(&self, index: usize) -> Vec4 {
+        let [x, y, z, w] = self.data[index];
+        Vec4::new(x, y, z, w)
+    }
+
+    pub fn translate(tx: f32, ty: f32, tz: f32) -> Self {
+        let mut matrix = Mat4::IDENTITY;
+        matrix.data[3] = [tx, ty, tz, 1.0];
+        matrix
+    }
+
+    pub fn scale(sx: f32, sy: f32, sz: f32) -> Self {
+        let mut matrix = Mat4::IDENTITY;
+        matrix.data[0][0] = sx;
+        matrix.data[1][1] = sy;
+        matrix.data[2][2] = sz;
+        matrix
+    }
+
+    ///Dreht y nach z
+    pub fn rotate_x(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let mut matrix = Mat4::IDENTITY;
+        matrix.data[1][1] = cos;
+        matrix.data[1][2] = sin;
+        matrix.data[2][1] = -sin;
+        matrix.data[2][2] = cos;
+        matrix
+    }
+
+    ///Dreht z nach x
+    pub fn rotate_y(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let mut matrix = Mat4::IDENTITY;
+        matrix.data[0][0] = cos;
+        matrix.data[0][2] = -sin;
+        matrix.data[2][0] = sin;
+        matrix.data[2][2] = cos;
+        matrix
+    }
+
+    ///Dreht x nach y
+    pub fn rotate_z(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let mut matrix = Mat4::IDENTITY;
+        matrix.data[0][0] = cos;
+        matrix.data[0][1] = sin;
+        matrix.data[1][0] = -sin;
+        matrix.data[1][1] = cos;
+        matrix
+    }
+
+    ///Drehung um eine beliebige Achse (muss nicht normiert sein), für die Koordinatenachsen
+    ///gleich wie `rotate_x`, `rotate_y` und `rotate_z`
+    pub fn rotation_around_axis(axis: Vec3, angle_radians: f32) -> Mat4 {
+        let Vec3 { x, y, z } = axis.normalize();
+        let (sin, cos) = angle_radians.sin_cos();
+        let one_minus_cos = 1.0 - cos;
+
+        Mat4 {
+            data: [
+                [
+                    cos + x * x * one_minus_cos,
+                    x * y * one_minus_cos + z * sin,
+                    x * z * one_minus_cos - y * sin,
+                    0.0,
+                ],
+                [
+                    y * x * one_minus_cos - z * sin,
+                    cos + y * y * one_minus_cos,
+                    y * z * one_minus_cos + x * sin,
+                    0.0,
+                ],
+                [
+                    z * x * one_minus_cos + y * sin,
+                    z * y * one_minus_cos - x * sin,
+                    cos + z * z * one_minus_cos,
+                    0.0,
+                ],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    ///View-Matrix einer Kamera in `eye`, die auf `target` schaut. Danach liegt das Ziel
+    ///auf der positiven z-Achse
+    pub fn look_at(eye: Vec3, target: Vec3, up: Vec3) -> Mat4 {
+        let z = (target - eye).normalize();
+        let x = up.cross(z).normalize();
+        let y = z.cross(x);
+
+        Mat4 {
+            data: [
+                [x.x, y.x, z.x, 0.0],
+                [x.y, y.y, z.y, 0.0],
+                [x.z, y.z, z.z, 0.0],
+                [-x.dot(eye), -y.dot(eye), -z.dot(eye), 1.0],
+            ],
+        }
+    }
+
+    ///Perspektive mit vertikalem Öffnungswinkel in Radiant. Die Tiefe nach der Division
+    ///durch w läuft von 0 an der Near- bis 1 an der Far-Plane
+    pub fn perspective(fov_y: f32, aspect_ratio: f32, near: f32, far: f32) -> Mat4 {
+        let tan_half = (fov_y / 2.0).tan();
+        Mat4 {
+            data: [
+                [1.0 / (aspect_ratio * tan_half), 0.0, 0.0, 0.0],
+                [0.0, 1.0 / tan_half, 0.0, 0.0],
+                [0.0, 0.0, far / (far - near), 1.0],
+                [0.0, 0.0, (-far * near) / (far - near), 0.0],
+            ],
+        }
+    }
+
+    ///Parallelprojektion des Quaders auf x, y in -1 bis 1 und die Tiefe linear auf 0 bis 1
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Mat4 {
+        Mat4 {
+            data: [
+                [2.0 / (right - left), 0.0, 0.0, 0.0],
+                [0.0, 2.0 / (top - bottom), 0.0, 0.0],
+                [0.0, 0.0, 1.0 / (far - near), 0.0],
+                [
+                    -(right + left) / (right - left),
+                    -(top + bottom) / (top - bottom),
+                    -near / (far - near),
+                    1.0,
+                ],
+            ],
+        }
+    }
+
+    ///Ob die Matrix w aus der Tiefe berechnet, also perspektivisch projiziert
+    pub fn is_perspective(&self) -> bool {
+        self.data[2][3] != 0.0
+    }
+
+    ///Transformiert einen Punkt (w = 1) inklusive perspektivischer Division
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        (point.extend(1.0) * *self).project()
+    }
+
+    ///Transformiert eine Richtung (w = 0), die Translation wirkt nicht
+    pub fn transform_vector(&self, vector: Vec3) -> Vec3 {
+        vector * self.to_mat3()
+    }
+
+    ///Ergänzt einen 3x3-Block ohne Translation
+    pub fn from_mat3(matrix: &Mat3) -> Self {
+        let mut result = Mat4::IDENTITY;
+        for (row, source) in result.data.iter_mut().zip(matrix.data) {
+            row[..3].copy_from_slice(&source);
+        }
+        result
+    }
+
+    ///Linker oberer 3x3-Block mit Rotation und Skalierung
+    pub fn to_mat3(&self) -> Mat3 {
+        Mat3 {
+            data: std::array::from_fn(|i| std::array::from_fn(|j| self.data[i][j])),
+        }
+    }
+
+    ///Matrix für Normalen (inverse Transponierte des 3x3-Blocks), damit sie auch bei
+    ///ungleichmäßiger Skalierung senkrecht auf der Fläche bleiben
+    pub fn normal_matrix(&self) -> Option<Mat3> {
+        Some(self.to_mat3().inverse()?.transpose())
+    }
+
+    pub fn transpose(&self) -> Mat4 {
+        Mat4 {
+            data: std::array::from_fn(|i| std::array::from_fn(|j| self.data[j][i])),
+        }
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let (s, c) = self.minors();
+        determinant_from_minors(&s, &c)
+    }
+
+    ///Laplace-Entwicklung über die 2x2-Unterdeterminanten, `None` bei singulären Matrizen
+    pub fn inverse(&self) -> Option<Mat4> {
+        let (s, c) = self.minors();
+        let determinant = determinant_from_minors(&s, &c);
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+
+        let m = &self.data;
+        let adjugate = [
+            [
+                m[1][1] * c[5] - m[1][2] * c[4] + m[1][3] * c[3],
+                -m[0][1] * c[5] + m[0][2] * c[4] - m[0][3] * c[3],
+                m[3][1] * s[5] - m[3][2] * s[4] + m[3][3] * s[3],
+                -m[2][1] * s[5] + m[2][2] * s[4] - m[2][3] * s[3],
+            ],
+            [
+                -m[1][0] * c[5] + m[1][2] * c[2] - m[1][3] * c[1],
+                m[0][0] * c[5] - m[0][2] * c[2] + m[0][3] * c[1],
+                -m[3][0] * s[5] + m[3][2] * s[2] - m[3][3] * s[1],
+                m[2][0] * s[5] - m[2][2] * s[2] + m[2][3] * s[1],
+            ],
+            [
+                m[1][0] * c[4] - m[1][1] * c[2] + m[1][3] * c[0],
+                -m[0][0] * c[4] + m[0][1] * c[2] - m[0][3] * c[0],
+                m[3][0] * s[4] - m[3][1] * s[2] + m[3][3] * s[0],
+                -m[2][0] * s[4] + m[2][1] * s[2] - m[2][3] * s[0],
+            ],
+            [
+                -m[1][0] * c[3] + m[1][1] * c[1] - m[1][2] * c[0],
+                m[0][0] * c[3] - m[0][1] * c[1] + m[0][2] * c[0],
+                -m[3][0] * s[3] + m[3][1] * s[1] - m[3][2] * s[0],
+                m[2][0] * s[3] - m[2][1] * s[1] + m[2][2] * s[0],
+            ],
+        ];
+
+        let inverse_determinant = 1.0 / determinant;
+        Some(Mat4 {
+            data: adjugate.map(|row| row.map(|value| value * inverse_determinant)),
+        })
+    }
+
+    ///2x2-Unterdeterminanten der oberen (`s`) und unteren (`c`) beiden Zeilen
+    fn minors(&self) -> ([f32; 6], [f32; 6]) {
+        let m = &self.data;
+        let upper = |a: usize, b: usize| m[0][a] * m[1][b] - m[1][a] * m[0][b];
+        let lower = |a: usize, b: usize| m[2][a] * m[3][b] - m[3][a] * m[2][b];
+        (
+            [
+                upper(0, 1),
+                upper(0, 2),
+                upper(0, 3),
+                upper(1, 2),
+                upper(1, 3),
+                upper(2, 3),
+            ],
+            [
+                lower(0, 1),
+                lower(0, 2),
+                lower(0, 3),
+                lower(1, 2),
+                lower(1, 3),
+                lower(2, 3),
+            ],
+        )
+    }
+}
+
+fn determinant_from_minors(s: &[f32; 6], c: &[f32; 6]) -> f32 {
+    s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+    fn mul(self, other: Mat4) -> Mat4 {
+        Mat4 {
+            data: std::array::from_fn(|i| {
+                std::array::from_fn(|j| (0..4).map(|k| self.data[i][k] * other.data[k][j]).sum())
+            }),
+        }
+    }
+}
+
+impl Mul<Mat4> for Vec4 {
+    type Output = Vec4;
+    fn mul(self, matrix: Mat4) -> Vec4 {
+        matrix.row(0) * self.x
+            + matrix.row(1) * self.y
+            + matrix.row(2) * self.z
+            + matrix.row(3) * self.w
+    }
+}
+
+/// Einheitsquaternion für Drehungen. `a * b` dreht wie üblich erst um `b`, dann um `a`,
+/// also umgekehrt zu den Matrizen: `(a * b).to_mat4() == b.to_mat4() * a.to_mat4()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Quat::IDENTITY
+    }
+}
+
+impl Quat {
+    pub const IDENTITY: Quat = Quat {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+        w: 1.0,
+    };
+
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Quat { x, y, z, w }
+    }
+
+    ///Drehung um `axis` (muss nicht normiert sein), gleiche Richtung wie `Mat4::rotation_around_axis`
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Self {
+        let (sin, cos) = (angle / 2.0).sin_cos();
+        let axis = axis.normalize() * sin;
+        Quat::new(axis.x, axis.y, axis.z, cos)
+    }
+
+    ///Achse und Winkel (0 bis 2π), ohne Drehung ist die Achse x
+    pub fn to_axis_angle(self) -> (Vec3, f32) {
+        let q = self.normalize();
+        // Aus dem Vektoranteil statt aus `1 - w²`, sonst ungenau bei kleinen Winkeln
+        let axis = Vec3::new(q.x, q.y, q.z);
+        let sin = axis.magnitude();
+        if sin < 1e-6 {
+            return (Vec3::new(1.0, 0.0, 0.0), 0.0);
+        }
+        (axis / sin, 2.0 * sin.atan2(q.w))
+    }
+
+    ///Drehung um die Richtung von `vector`, um dessen Länge im Bogenmaß
+    pub fn from_rotation_vector(vector: Vec3) -> Self {
+        let angle = vector.magnitude();
+        if angle < 1e-9 {
+            return Quat::IDENTITY;
+        }
+        Quat::from_axis_angle(vector, angle)
+    }
+
+    ///Achse mal Winkel auf dem kürzeren Weg (höchstens π), Umkehrung von `from_rotation_vector`
+    pub fn to_rotation_vector(self) -> Vec3 {
+        let q = if self.w < 0.0 {
+            Quat::new(-self.x, -self.y, -self.z, -self.w)
+        } else {
+            self
+        };
+        let (axis, angle) = q.to_axis_angle();
+        axis * angle
+    }
+
+    ///Eulerwinkel in der Reihenfolge Y-X-Z: erst `roll` um z, dann `pitch` um x und
+    ///zuletzt `yaw` um y, jeweils um die mitgedrehten Achsen
+    pub fn from_euler(yaw: f32, pitch: f32, roll: f32) -> Self {
+        Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), yaw)
+            * Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), pitch)
+            * Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), roll)
+    }
+
+    ///Umkehrung von `from_euler`. Senkrecht nach oben oder unten (`pitch` = ±90°) sind
+    ///`yaw` und `roll` nicht unterscheidbar, dann ist `roll` 0
+    pub fn to_euler(self) -> (f32, f32, f32) {
+        let m = self.to_mat3().data;
+        // `data` ist die transponierte Matrix für Spaltenvektoren, also m[spalte][zeile]
+        let sin_pitch = -m[2][1];
+        if sin_pitch.abs() >= 1.0 - 1e-6 {
+            let pitch = std::f32::consts::FRAC_PI_2.copysign(sin_pitch);
+            return ((-m[0][2]).atan2(m[0][0]), pitch, 0.0);
+        }
+        (
+            m[2][0].atan2(m[2][2]),
+            sin_pitch.asin(),
+            m[0][1].atan2(m[1][1]),
+        )
+    }
+
+    ///Aus einer reinen Drehmatrix (orthonormal, Determinante 1)
+    pub fn from_mat3(matrix: &Mat3) -> Self {
+        // Spaltenvektor-Schreibweise r[zeile][spalte]
+        let r = matrix.transpose().data;
+        let trace = r[0][0] + r[1][1] + r[2][2];
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Quat::new(
+                (r[2][1] - r[1][2]) / s,
+                (r[0][2] - r[2][0]) / s,
+                (r[1][0] - r[0][1]) / s,
+                0.25 * s,
+            )
+        } else if r[0][0] > r[1][1] && r[0][0] > r[2][2] {
+            let s = (1.0 + r[0][0] - r[1][1] - r[2][2]).sqrt() * 2.0;
+            Quat::new(
+                0.25 * s,
+                (r[0][1] + r[1][0]) / s,
+                (r[0][2] + r[2][0]) / s,
+                (r[2][1] - r[1][2]) / s,
+            )
+        } else if r[1][1] > r[2][2] {
+            let s = (1.0 + r[1][1] - r[0][0] - r[2][2]).sqrt() * 2.0;
+            Quat::new(
+                (r[0][1] + r[1][0]) / s,
+                0.25 * s,
+                (r[1][2] + r[2][1]) / s,
+                (r[0][2] - r[2][0]) / s,
+            )
+        } else {
+            let s = (1.0 + r[2][2] - r[0][0] - r[1][1]).sqrt() * 2.0;
+            Quat::new(
+                (r[0][2] + r[2][0]) / s,
+                (r[1][2] + r[2][1]) / s,
+                0.25 * s,
+                (r[1][0] - r[0][1]) / s,
+            )
+        };
+        q.normalize()
+    }
+
+    pub fn dot(self, other: Quat) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    pub fn magnitude(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(self) -> Quat {
+        let magnitude = self.magnitude();
+        Quat::new(
+            self.x / magnitude,
+            self.y / magnitude,
+            self.z / magnitude,
+            self.w / magnitude,
+        )
+    }
+
+    pub fn conjugate(self) -> Quat {
+        Quat::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    ///Gegendrehung, bei Einheitsquaternionen gleich `conjugate`
+    pub fn inverse(self) -> Quat {
+        let c = self.conjugate();
+        let norm = self.dot(self);
+        Quat::new(c.x / norm, c.y / norm, c.z / norm, c.w / norm)
+    }
+
+    pub fn rotate(self, v: Vec3) -> Vec3 {
+        // v' = v + 2w(q × v) + 2q × (q × v), ohne den Umweg über die Matrix
+        let q = Vec3::new(self.x, self.y, self.z);
+        let t = q.cross(v) * 2.0;
+        v + t * self.w + q.cross(t)
+    }
+
+    ///Sphärische Interpolation auf dem kürzeren Weg, mit konstanter Winkelgeschwindigkeit
+    pub fn slerp(self, other: Quat, t: f32) -> Quat {
+        let mut cos = self.dot(other);
+        let mut other = other;
+        if cos < 0.0 {
+            other = Quat::new(-other.x, -other.y, -other.z, -other.w);
+            cos = -cos;
+        }
+
+        // Fast gleiche Drehungen: linear, sonst wird durch sin(angle) ≈ 0 geteilt
+        let (a, b) = if cos > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let angle = cos.acos();
+            let sin = angle.sin();
+            (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin)
+        };
+        Quat::new(
+            self.x * a + other.x * b,
+            self.y * a + other.y * b,
+            self.z * a + other.z * b,
+            self.w * a + other.w * b,
+        )
+        .normalize()
+    }
+
+    ///Drehmatrix im Zeilenvektor-Format aus `math`
+    pub fn to_mat3(self) -> Mat3 {
+        let Quat { x, y, z, w } = self;
+        Mat3 {
+            data: [
+                [
+                    1.0 - 2.0 * (y * y + z * z),
+                    2.0 * (x * y + w * z),
+                    2.0 * (x * z - w * y),
+                ],
+                [
+                    2.0 * (x * y - w * z),
+                    1.0 - 2.0 * (x * x + z * z),
+                    2.0 * (y * z + w * x),
+                ],
+                [
+                    2.0 * (x * z + w * y),
+                    2.0 * (y * z - w * x),
+                    1.0 - 2.0 * (x * x + y * y),
+                ],
+            ],
+        }
+    }
+
+    pub fn to_mat4(self) -> Mat4 {
+        Mat4::from_mat3(&self.to_mat3())
+    }
+}
+
+impl Mul for Quat {
+    type Output = Quat;
+    fn mul(self, o: Quat) -> Quat {
+        Quat::new(
+            self.w * o.x + self.x * o.w + self.y * o.z - self.z * o.y,
+            self.w * o.y - self.x * o.z + self.y * o.w + self.z * o.x,
+            self.w * o.z + self.x * o.y - self.y * o.x + self.z * o.w,
+            self.w * o.w - self.x * o.x - self.y * o.y - self.z * o.z,
+        )
+    }
+}
+
+impl Mul<Vec3> for Quat {
+    type Output = Vec3;
+    fn mul(self, v: Vec3) -> Vec3 {
+        self.rotate(v)
+    }
+}
+
+/// Lage eines Objekts: erst skaliert, dann gedreht und zuletzt verschoben
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        translation: Vec3::ZERO,
+        rotation: Quat::IDENTITY,
+        scale: Vec3 {
+            x: 1.0,
+            y: 1.0,
+            z: 1.0,
+        },
+    };
+
+    pub fn new(translation: Vec3, rotation: Quat, scale: Vec3) -> Self {
+        Transform {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    pub fn to_mat4(&self) -> Mat4 {
+        let Vec3 { x, y, z } = self.scale;
+        let t = self.translation;
+        Mat4::scale(x, y, z) * self.rotation.to_mat4() * Mat4::translate(t.x, t.y, t.z)
+    }
+
+    ///Zwischenstellung, die Drehung wird sphärisch interpoliert
+    pub fn interpolate(&self, other: &Transform, t: f32) -> Transform {
+        Transform {
+            translation: self.translation.lerp(other.translation, t),
+            rotation: self.rotation.slerp(other.rotation, t),
+            scale: self.scale.lerp(other.scale, t),
+        }
+    }
+}
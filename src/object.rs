@@ -1,6 +1,10 @@
 use crate::Polygon;
 use crate::point::Point;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::polygon::Tangent;
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
+};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
@@ -20,27 +24,27 @@ pub fn parse_obj_file(
             .trim()
             .to_string();
 
-        if line.starts_with("v ") {
-            let coords: Vec<f32> = line[2..]
+        if let Some(rest) = line.strip_prefix("v ") {
+            let coords: Vec<f32> = rest
                 .split_whitespace()
                 .filter_map(|part| part.parse::<f32>().ok())
                 .collect();
             if coords.len() == 3 {
                 vertices.push(Point::new(coords[0], -coords[2], coords[1]));
             }
-        } else if line.starts_with("vt ") {
-            let coords: Vec<f32> = line[3..]
+        } else if let Some(rest) = line.strip_prefix("vt ") {
+            let coords: Vec<f32> = rest
                 .split_whitespace()
                 .filter_map(|part| part.parse::<f32>().ok())
                 .collect();
             if coords.len() >= 2 {
                 tex_coords.push((coords[0], coords[1]));
             }
-        } else if line.starts_with("f ") {
+        } else if let Some(rest) = line.strip_prefix("f ") {
             let mut v_indices = Vec::new();
             let mut t_indices = Vec::new();
 
-            for part in line[2..].split_whitespace() {
+            for part in rest.split_whitespace() {
                 let mut split = part.split('/');
                 let v_idx = split
                     .next()
@@ -51,17 +55,17 @@ pub fn parse_obj_file(
                     .and_then(|s| s.parse::<usize>().ok())
                     .map(|i| i - 1);
 
-                if let (Some(v), Some(t)) = (v_idx, t_idx) {
+                if let Some(v) = v_idx {
                     v_indices.push(v);
+                }
+                if let Some(t) = t_idx {
                     t_indices.push(t);
                 }
             }
 
-            if v_indices.len() == 3 && t_indices.len() == 3 {
-                let mut t_indices_fixed = t_indices.clone();
-                t_indices_fixed.swap(1, 2);
-                faces.push((v_indices, t_indices_fixed));
-            } else if v_indices.len() >= 3 && t_indices.len() == v_indices.len() {
+            if v_indices.len() >= 3 && (t_indices.len() == v_indices.len() || t_indices.is_empty())
+            {
+                // Faces ohne Texturkoordinaten ("f 1 2 3" oder "f 1//1 2//2 3//3") werden einfarbig gerendert
                 faces.push((v_indices, t_indices));
             }
         }
@@ -71,9 +75,9 @@ pub fn parse_obj_file(
 }
 
 pub fn process_faces(
-    vertices: &Vec<Point>,
-    faces: &Vec<(Vec<usize>, Vec<usize>)>,
-    tex_coords: &Vec<(f32, f32)>,
+    vertices: &[Point],
+    faces: &[(Vec<usize>, Vec<usize>)],
+    tex_coords: &[(f32, f32)],
 ) -> Vec<Polygon> {
     faces
         .par_iter()
@@ -95,3 +99,163 @@ pub fn process_faces(
         })
         .collect()
 }
+
+///Setzt Eckennormalen aus dem Mittel der Flächennormalen aller Polygone mit derselben Ecke,
+///damit Rundungen glatt statt facettiert schattiert werden
+pub fn smooth_normals(polygons: &mut [Polygon]) {
+    let key = |point: &Point| (point.x.to_bits(), point.y.to_bits(), point.z.to_bits());
+
+    let mut sums: HashMap<(u32, u32, u32), Point> = HashMap::new();
+    for polygon in polygons.iter() {
+        if polygon.vertices.len() < 3 {
+            continue;
+        }
+        let normal = polygon.face_normal();
+        if !normal.x.is_finite() {
+            continue; // Degenerierte Fläche, z.B. an den Polen
+        }
+        for vertex in &polygon.vertices {
+            let sum = sums.entry(key(vertex)).or_insert(Point::new(0.0, 0.0, 0.0));
+            *sum += normal;
+        }
+    }
+
+    polygons.par_iter_mut().for_each(|polygon| {
+        let face_normal = if polygon.vertices.len() < 3 {
+            Point::new(0.0, 0.0, 0.0)
+        } else {
+            polygon.face_normal()
+        };
+        let normals = polygon
+            .vertices
+            .iter()
+            .map(|vertex| {
+                let normal = sums[&key(vertex)].normalize();
+                if normal.x.is_finite() {
+                    normal
+                } else {
+                    face_normal
+                }
+            })
+            .collect();
+        polygon.set_normals(normals);
+    });
+}
+
+/// Ecken mit gleicher Position, Normale, UV und Orientierung teilen sich eine Tangente
+type TangentKey = ([u32; 3], [u32; 3], [u32; 2], bool);
+
+///Erzeugt Eckentangenten für Normal Maps nach dem Vorgehen von MikkTSpace: die Tangenten der
+///Flächen werden in die Ebene der Eckennormale projiziert, nach dem Winkel an der Ecke
+///gewichtet und über gleiche Ecken gemittelt. Ohne Eckennormalen gilt die Flächennormale
+pub fn generate_tangents(polygons: &mut [Polygon]) {
+    let corners: Vec<Vec<(TangentKey, Point)>> = polygons.par_iter().map(corner_tangents).collect();
+
+    let mut sums: HashMap<TangentKey, Point> = HashMap::new();
+    for (key, tangent) in corners.iter().flatten() {
+        let sum = sums.entry(*key).or_insert(Point::new(0.0, 0.0, 0.0));
+        *sum += *tangent;
+    }
+
+    polygons
+        .par_iter_mut()
+        .zip(corners)
+        .for_each(|(polygon, corners)| {
+            if corners.is_empty() {
+                return;
+            }
+            let tangents = corners
+                .iter()
+                .enumerate()
+                .map(|(i, (key, _))| {
+                    let normal = vertex_normal(polygon, i);
+                    let sum = sums[key];
+                    let tangent = (sum - normal * normal.dot(sum)).normalize();
+                    let tangent = if tangent.x.is_finite() {
+                        tangent
+                    } else {
+                        any_perpendicular(normal)
+                    };
+                    Tangent::new(tangent, if key.3 { 1.0 } else { -1.0 })
+                })
+                .collect();
+            polygon.set_tangents(tangents);
+        });
+}
+
+///Gewichtete Tangente und Schlüssel für jede Ecke eines Polygons
+fn corner_tangents(polygon: &Polygon) -> Vec<(TangentKey, Point)> {
+    let count = polygon.vertices.len();
+    if count < 3 {
+        return Vec::new();
+    }
+
+    (0..count)
+        .map(|i| {
+            let (prev, next) = ((i + count - 1) % count, (i + 1) % count);
+            let position = polygon.vertices[i];
+            let normal = vertex_normal(polygon, i);
+            let uv = polygon.vertex_uv(i);
+            let (uv1, uv2) = (polygon.vertex_uv(next), polygon.vertex_uv(prev));
+
+            let edge1 = polygon.vertices[next] - position;
+            let edge2 = polygon.vertices[prev] - position;
+            let (du1, dv1) = (uv1.0 - uv.0, uv1.1 - uv.1);
+            let (du2, dv2) = (uv2.0 - uv.0, uv2.1 - uv.1);
+            let det = du1 * dv2 - du2 * dv1;
+
+            let key = |upright: bool| {
+                (
+                    [position.x, position.y, position.z].map(f32::to_bits),
+                    [normal.x, normal.y, normal.z].map(f32::to_bits),
+                    [uv.0, uv.1].map(f32::to_bits),
+                    upright,
+                )
+            };
+            if det.abs() < f32::EPSILON {
+                return (key(true), Point::new(0.0, 0.0, 0.0)); // Keine brauchbaren UVs
+            }
+
+            // Richtung von wachsendem u und wachsendem v auf der Fläche
+            let tangent = (edge1 * dv2 - edge2 * dv1) * (1.0 / det);
+            let along_v = (edge2 * du1 - edge1 * du2) * (1.0 / det);
+            // Die Bitangente zeigt zum oberen Rand der Textur, also gegen wachsendes v
+            let upright = normal.cross(tangent).dot(along_v) <= 0.0;
+
+            let project = |v: Point| (v - normal * normal.dot(v)).normalize();
+            let tangent = project(tangent);
+            let angle = project(edge1).dot(project(edge2)).clamp(-1.0, 1.0).acos();
+            if !tangent.x.is_finite() || !angle.is_finite() {
+                return (key(upright), Point::new(0.0, 0.0, 0.0));
+            }
+            (key(upright), tangent * angle)
+        })
+        .collect()
+}
+
+fn vertex_normal(polygon: &Polygon, index: usize) -> Point {
+    polygon
+        .normals
+        .get(index)
+        .copied()
+        .unwrap_or_else(|| polygon.face_normal())
+}
+
+///Irgendeine Richtung senkrecht zur Normale, für Ecken ohne brauchbare UVs
+fn any_perpendicular(normal: Point) -> Point {
+    let axis = if normal.x.abs() < 0.9 {
+        Point::new(1.0, 0.0, 0.0)
+    } else {
+        Point::new(0.0, 1.0, 0.0)
+    };
+    (axis - normal * normal.dot(axis)).normalize()
+}
+
+///Lädt eine OBJ-Datei mit geglätteten Normalen und Tangenten, bereit für Normal Maps
+pub fn load_obj(file_path: &str) -> Result<Vec<Polygon>, String> {
+    let (vertices, faces, tex_coords) = parse_obj_file(file_path)?;
+    let mut polygons = process_faces(&vertices, &faces, &tex_coords);
+    smooth_normals(&mut polygons);
+    generate_tangents(&mut polygons);
+    Ok(polygons)
+}
@@ -0,0 +1,212 @@
+use crate::Framebuffer;
+use crate::color::{Color, Rgba32F};
+use crate::material::{BlendMode, Material, blend_pixel};
+use rayon::prelude::*;
+
+/// Wie transparente Fragmente aufgelöst werden
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransparencyMode {
+    #[default]
+    Sorted, // Ganze Polygone von hinten nach vorne sortiert (falsch bei Durchdringungen)
+    FragmentLists, // Exakt: alle Fragmente pro Pixel sammeln und beim Auflösen sortieren
+    WeightedBlended, // Näherung nach McGuire/Bavoil, feste Speichergröße, keine Sortierung
+}
+
+/// Ende einer Fragmentliste
+const NO_FRAGMENT: u32 = u32::MAX;
+
+#[derive(Debug, Clone, Copy)]
+struct FragmentNode {
+    next: u32,
+    z: f32,
+    color: Rgba32F,
+    blend_mode: BlendMode,
+}
+
+/// Zwischenspeicher für transparente Fragmente bis `resolve_transparency`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OitBuffers {
+    heads: Vec<u32>,            // Pro Pixel Index des zuletzt eingefügten Fragments
+    nodes: Vec<FragmentNode>,   // Gemeinsamer Pool aller verketteten Fragmente
+    accumulation: Vec<Rgba32F>, // Gewichtete, vormultiplizierte Farbe und gewichtetes Alpha
+    revealage: Vec<f32>,        // Produkt aus (1 - Alpha), wie viel vom Hintergrund durchscheint
+}
+
+impl<C: Color> Framebuffer<C> {
+    /// Leert die OIT-Puffer und passt sie an Größe und Modus an
+    pub(crate) fn prepare_transparency(&mut self) {
+        let len = self.width * self.height;
+        let oit = &mut self.oit;
+        oit.nodes.clear();
+        oit.heads.clear();
+        oit.accumulation.clear();
+        oit.revealage.clear();
+
+        match self.transparency {
+            TransparencyMode::Sorted => {}
+            TransparencyMode::FragmentLists => oit.heads.resize(len, NO_FRAGMENT),
+            TransparencyMode::WeightedBlended => {
+                oit.accumulation
+                    .resize(len, Rgba32F::new(0.0, 0.0, 0.0, 0.0));
+                oit.revealage.resize(len, 1.0);
+            }
+        }
+    }
+
+    ///Ob Fragmente dieses Materials für `resolve_transparency` gesammelt werden
+    #[inline(always)]
+    pub(crate) fn collects_fragments(&self, material: &Material) -> bool {
+        match self.transparency {
+            TransparencyMode::Sorted => false,
+            TransparencyMode::FragmentLists => material.is_transparent(),
+            // Additiv und multiplikativ sind ohnehin unabhängig von der Reihenfolge
+            TransparencyMode::WeightedBlended => matches!(
+                material.blend_mode,
+                BlendMode::Alpha | BlendMode::Premultiplied
+            ),
+        }
+    }
+
+    ///Schreibt ein Fragment in Pixel `index`. Transparente Fragmente werden bei aktivem OIT
+    ///gesammelt statt direkt geblendet
+    #[inline(always)]
+    pub(crate) fn write_fragment(
+        &mut self,
+        index: usize,
+        z: f32,
+        color: Rgba32F,
+        material: &Material,
+    ) {
+        if self.collects_fragments(material) {
+            if material.passes_alpha_test(color) && z < self.z_buffer[index] {
+                self.push_transparent_fragment(index, z, color, material.blend_mode);
+            }
+            return;
+        }
+        material.write_fragment(&mut self.pixels[index], &mut self.z_buffer[index], z, color);
+    }
+
+    ///Nimmt ein transparentes Fragment auf, das den Tiefentest gegen die opake Geometrie bestanden hat
+    pub(crate) fn push_transparent_fragment(
+        &mut self,
+        index: usize,
+        z: f32,
+        color: Rgba32F,
+        blend_mode: BlendMode,
+    ) {
+        match self.transparency {
+            TransparencyMode::Sorted => self.blend_into(index, color, blend_mode),
+            TransparencyMode::FragmentLists => {
+                let oit = &mut self.oit;
+                oit.nodes.push(FragmentNode {
+                    next: oit.heads[index],
+                    z,
+                    color,
+                    blend_mode,
+                });
+                oit.heads[index] = (oit.nodes.len() - 1) as u32;
+            }
+            TransparencyMode::WeightedBlended => match blend_mode {
+                BlendMode::Additive | BlendMode::Multiply | BlendMode::Opaque => {
+                    self.blend_into(index, color, blend_mode);
+                }
+                BlendMode::Alpha | BlendMode::Premultiplied => {
+                    let alpha = color.a;
+                    let premultiplied = if blend_mode == BlendMode::Alpha {
+                        color * alpha
+                    } else {
+                        color
+                    };
+
+                    // Nähere Fragmente bekommen mehr Gewicht (z liegt zwischen 0 und 1)
+                    let weight = alpha * (3e3 * (1.0 - z.clamp(0.0, 1.0)).powi(3)).clamp(1e-2, 3e3);
+                    self.oit.accumulation[index] =
+                        self.oit.accumulation[index] + premultiplied.with_alpha(alpha) * weight;
+                    self.oit.revealage[index] *= 1.0 - alpha;
+                }
+            },
+        }
+    }
+
+    ///Verrechnet die gesammelten transparenten Fragmente mit den opaken Pixeln
+    pub fn resolve_transparency(&mut self) {
+        match self.transparency {
+            TransparencyMode::Sorted => {}
+            TransparencyMode::FragmentLists => {
+                let oit = &self.oit;
+                self.pixels
+                    .par_iter_mut()
+                    .zip(oit.heads.par_iter())
+                    .for_each(|(pixel, &head)| {
+                        let mut fragments = Vec::new();
+                        let mut node = head;
+                        while node != NO_FRAGMENT {
+                            let fragment = oit.nodes[node as usize];
+                            fragments.push(fragment);
+                            node = fragment.next;
+                        }
+                        fragments.sort_by(|a, b| b.z.total_cmp(&a.z));
+
+                        let mut color = pixel.to_linear();
+                        for fragment in fragments {
+                            color = blend_pixel(color, fragment.color, fragment.blend_mode);
+                        }
+                        *pixel = C::from_linear(color);
+                    });
+            }
+            TransparencyMode::WeightedBlended => {
+                let oit = &self.oit;
+                self.pixels
+                    .par_iter_mut()
+                    .zip(oit.accumulation.par_iter())
+                    .zip(oit.revealage.par_iter())
+                    .for_each(|((pixel, accumulation), &revealage)| {
+                        if revealage >= 1.0 {
+                            return; // Kein transparentes Fragment
+                        }
+                        let average = *accumulation * (1.0 / accumulation.a.max(1e-5));
+                        let color = average.lerp(pixel.to_linear(), revealage);
+                        *pixel = C::from_linear(color.with_alpha(1.0));
+                    });
+            }
+        }
+    }
+
+    ///OIT-Variante für MSAA: ein Fragment pro Pixel, Alpha anteilig nach den
+    ///abgedeckten Samples, die vor der opaken Geometrie liegen
+    pub(crate) fn push_transparent_fragment_msaa(
+        &mut self,
+        index: usize,
+        sample_depths: &[f32],
+        color: Rgba32F,
+        material: &Material,
+    ) {
+        if !material.passes_alpha_test(color) {
+            return;
+        }
+        let samples = self.msaa.sample_count();
+        let opaque = &self.sample_depths[index * samples..(index + 1) * samples];
+
+        let mut visible = 0;
+        let mut z_sum = 0.0;
+        for (&z, &opaque_z) in sample_depths.iter().zip(opaque) {
+            if z.is_finite() && z < opaque_z {
+                visible += 1;
+                z_sum += z;
+            }
+        }
+        if visible == 0 {
+            return;
+        }
+
+        let coverage = visible as f32 / samples as f32;
+        let color = color.with_alpha(color.a * coverage);
+        self.push_transparent_fragment(index, z_sum / visible as f32, color, material.blend_mode);
+    }
+
+    #[inline(always)]
+    fn blend_into(&mut self, index: usize, color: Rgba32F, blend_mode: BlendMode) {
+        let pixel = &mut self.pixels[index];
+        *pixel = C::from_linear(blend_pixel(pixel.to_linear(), color, blend_mode));
+    }
+}
@@ -0,0 +1,252 @@
+use crate::color::Rgba32F;
+use crate::environment::Environment;
+use crate::point::Point;
+use crate::texture::Texture;
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+/// Reflexionsgrad bei senkrechtem Blick für alle Nichtmetalle, wie in glTF
+const DIELECTRIC_F0: f32 = 0.04;
+
+/// Metallic-Roughness-Material wie in glTF. Die Grundfarbe kommt weiterhin aus
+/// `Polygon::texture` bzw. `Polygon::color` und wird mit `base_color` multipliziert
+#[derive(Debug, Clone)]
+pub struct PbrMaterial {
+    pub base_color: Rgba32F,     // Faktor auf Textur bzw. Polygonfarbe, linear
+    pub metallic: f32,           // 0.0 = Nichtmetall, 1.0 = Metall
+    pub roughness: f32,          // 0.0 = glatt, 1.0 = ganz matt
+    pub emissive: Rgba32F,       // Selbstleuchten, linear und ohne Obergrenze
+    pub normal_scale: f32,       // Stärke der Normal Map
+    pub occlusion_strength: f32, // 0.0 = Occlusion Map ignorieren, 1.0 = voll
+
+    pub metallic_roughness_map: Option<Arc<Texture>>, // Grün = Rauheit, Blau = Metall (linear)
+    pub normal_map: Option<Arc<Texture>>,             // Normalen im Tangentenraum (linear)
+    pub occlusion_map: Option<Arc<Texture>>,          // Rot = Umgebungsverdeckung (linear)
+    pub emissive_map: Option<Arc<Texture>>,           // Farbe des Selbstleuchtens (sRGB)
+}
+
+impl Default for PbrMaterial {
+    ///Standardwerte aus glTF: weißes, raues Metall
+    fn default() -> Self {
+        PbrMaterial::new(Rgba32F::WHITE, 1.0, 1.0)
+    }
+}
+
+impl PbrMaterial {
+    pub fn new(base_color: Rgba32F, metallic: f32, roughness: f32) -> Self {
+        PbrMaterial {
+            base_color,
+            metallic,
+            roughness,
+            emissive: Rgba32F::new(0.0, 0.0, 0.0, 1.0),
+            normal_scale: 1.0,
+            occlusion_strength: 1.0,
+
+            metallic_roughness_map: None,
+            normal_map: None,
+            occlusion_map: None,
+            emissive_map: None,
+        }
+    }
+
+    ///Metall und Rauheit werden mit den Faktoren multipliziert, die Textur gilt als linear
+    pub fn with_metallic_roughness_map(mut self, map: Arc<Texture>) -> Self {
+        self.metallic_roughness_map = Some(map);
+        self
+    }
+
+    pub fn with_normal_map(mut self, map: Arc<Texture>, scale: f32) -> Self {
+        self.normal_map = Some(map);
+        self.normal_scale = scale;
+        self
+    }
+
+    pub fn with_occlusion_map(mut self, map: Arc<Texture>, strength: f32) -> Self {
+        self.occlusion_map = Some(map);
+        self.occlusion_strength = strength;
+        self
+    }
+
+    ///Ohne Map leuchtet die ganze Fläche in `emissive`
+    pub fn with_emissive(mut self, emissive: Rgba32F, map: Option<Arc<Texture>>) -> Self {
+        self.emissive = emissive;
+        self.emissive_map = map;
+        self
+    }
+}
+
+/// Paralleles Licht, z.B. die Sonne
+#[derive(Debug, Clone, Copy)]
+pub struct DirectionalLight {
+    pub direction: Point, // Richtung, in die das Licht fällt (muss nicht normiert sein)
+    pub color: Rgba32F,   // Linear
+    pub intensity: f32,   // Bestrahlungsstärke auf einer senkrecht beleuchteten Fläche
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Point, color: Rgba32F, intensity: f32) -> Self {
+        DirectionalLight {
+            direction,
+            color,
+            intensity,
+        }
+    }
+}
+
+/// Werte eines Pixels nach dem Lesen der Maps, Grundlage für die Beleuchtung
+pub(crate) struct PbrSurface {
+    pub base_color: Rgba32F,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub occlusion: f32,
+    pub emissive: Rgba32F,
+}
+
+impl PbrMaterial {
+    ///Liest alle Maps an den UV-Koordinaten, `color` ist Textur bzw. Polygonfarbe
+    #[inline(always)]
+    pub(crate) fn surface(&self, color: Rgba32F, u: f32, v: f32) -> PbrSurface {
+        let (mut metallic, mut roughness) = (self.metallic, self.roughness);
+        if let Some(map) = &self.metallic_roughness_map {
+            let texel = map.sample(u, v);
+            roughness *= texel.g;
+            metallic *= texel.b;
+        }
+
+        let occlusion = match &self.occlusion_map {
+            Some(map) => 1.0 + self.occlusion_strength * (map.sample(u, v).r - 1.0),
+            None => 1.0,
+        };
+
+        let emissive = match &self.emissive_map {
+            Some(map) => self.emissive * map.sample(u, v),
+            None => self.emissive,
+        };
+
+        PbrSurface {
+            base_color: color * self.base_color,
+            metallic: metallic.clamp(0.0, 1.0),
+            // Ganz glatte Flächen ergäben eine unendlich schmale Glanzspitze
+            roughness: roughness.clamp(0.03, 1.0),
+            occlusion,
+            emissive,
+        }
+    }
+
+    ///Normale aus der Normal Map. `tangent` zeigt in Richtung wachsendem u,
+    ///`bitangent` in Richtung "oben" der Textur (kleiner werdendes v)
+    #[inline(always)]
+    pub(crate) fn perturb_normal(
+        &self,
+        normal: Point,
+        tangent: Point,
+        bitangent: Point,
+        u: f32,
+        v: f32,
+    ) -> Point {
+        let Some(map) = &self.normal_map else {
+            return normal;
+        };
+        let texel = map.sample(u, v);
+        let x = (texel.r * 2.0 - 1.0) * self.normal_scale;
+        let y = (texel.g * 2.0 - 1.0) * self.normal_scale;
+        let z = texel.b * 2.0 - 1.0;
+        let perturbed = (tangent * x + bitangent * y + normal * z).normalize();
+        if perturbed.x.is_finite() {
+            perturbed
+        } else {
+            normal
+        }
+    }
+}
+
+impl PbrSurface {
+    ///Cook-Torrance mit GGX für direktes Licht und bildbasiertes Licht aus der
+    ///vorgefilterten Umgebung. `normal` und `view` (zur Kamera) sind normiert
+    #[inline(always)]
+    pub(crate) fn shade(
+        &self,
+        normal: Point,
+        view: Point,
+        lights: &[DirectionalLight],
+        environment: Option<&Environment>,
+    ) -> Rgba32F {
+        let base = self.base_color;
+        let f0 = Rgba32F::new(DIELECTRIC_F0, DIELECTRIC_F0, DIELECTRIC_F0, 1.0)
+            .lerp(base, self.metallic);
+        let diffuse_color = base * (1.0 - self.metallic);
+        let n_dot_v = normal.dot(view).max(1e-4);
+        let alpha = self.roughness * self.roughness;
+
+        let mut radiance = Rgba32F::new(0.0, 0.0, 0.0, 0.0);
+        for light in lights {
+            let to_light = (light.direction * -1.0).normalize();
+            let n_dot_l = normal.dot(to_light);
+            if n_dot_l <= 0.0 {
+                continue;
+            }
+            let half = (view + to_light).normalize();
+            let n_dot_h = normal.dot(half).max(0.0);
+            let v_dot_h = view.dot(half).max(0.0);
+
+            let fresnel = fresnel_schlick(f0, v_dot_h);
+            let specular = fresnel
+                * (distribution_ggx(n_dot_h, alpha)
+                    * geometry_smith(n_dot_v, n_dot_l, self.roughness)
+                    / (4.0 * n_dot_v * n_dot_l));
+            let diffuse = (Rgba32F::WHITE - fresnel) * diffuse_color * (1.0 / PI);
+
+            radiance = radiance + (diffuse + specular) * light.color * (light.intensity * n_dot_l);
+        }
+
+        if let Some(environment) = environment {
+            // Diffuses Licht aus der unschärfsten Stufe als Näherung der Irradiance
+            let irradiance = environment.sample(normal, 1.0);
+            let reflected = normal * (2.0 * n_dot_v) - view;
+            let prefiltered = environment.sample(reflected, self.roughness);
+
+            let (scale, bias) = environment_brdf(n_dot_v, self.roughness);
+            let specular_color = f0 * scale + Rgba32F::new(bias, bias, bias, 0.0);
+            let ambient = diffuse_color * irradiance + prefiltered * specular_color;
+            radiance = radiance + ambient * self.occlusion;
+        }
+
+        (radiance + self.emissive).with_alpha(base.a)
+    }
+}
+
+///Normalverteilung der Mikrofacetten nach Trowbridge-Reitz (GGX)
+#[inline(always)]
+fn distribution_ggx(n_dot_h: f32, alpha: f32) -> f32 {
+    let alpha2 = alpha * alpha;
+    let denominator = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+    alpha2 / (PI * denominator * denominator)
+}
+
+///Selbstabschattung der Mikrofacetten nach Smith mit der Schlick-Näherung für direktes Licht
+#[inline(always)]
+fn geometry_smith(n_dot_v: f32, n_dot_l: f32, roughness: f32) -> f32 {
+    let k = (roughness + 1.0) * (roughness + 1.0) / 8.0;
+    let schlick = |n_dot_x: f32| n_dot_x / (n_dot_x * (1.0 - k) + k);
+    schlick(n_dot_v) * schlick(n_dot_l)
+}
+
+#[inline(always)]
+fn fresnel_schlick(f0: Rgba32F, cos: f32) -> Rgba32F {
+    let factor = (1.0 - cos).clamp(0.0, 1.0).powi(5);
+    f0 + (Rgba32F::WHITE - f0) * factor
+}
+
+///Analytische Näherung der vorintegrierten BRDF-Tabelle (Split Sum) nach Karis,
+///ergibt Skalierung und Offset für F0
+#[inline(always)]
+fn environment_brdf(n_dot_v: f32, roughness: f32) -> (f32, f32) {
+    let r = (
+        1.0 - roughness,
+        roughness * -0.0275 + 0.0425,
+        roughness * -0.572 + 1.04,
+        roughness * 0.022 - 0.04,
+    );
+    let a004 = (r.0 * r.0).min((-9.28 * n_dot_v).exp2()) * r.0 + r.1;
+    (a004 * -1.04 + r.2, a004 * 1.04 + r.3)
+}
@@ -1,23 +1,35 @@
 use crate::Point2D;
+use crate::color::Rgba8;
+use crate::material::Material;
+use crate::pbr::PbrMaterial;
 use crate::texture::Texture;
-use crate::{Matrix4x4, Point};
+use crate::{Mat4, Point};
 use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct Polygon {
     pub vertices: Vec<Point>,
     pub(crate) tex_coords: Vec<(f32, f32)>,
+    pub(crate) normals: Vec<Point>, // Eckennormalen, ohne wird die Flächennormale verwendet
+    pub(crate) tangents: Vec<Tangent>, // Für Normal Maps, z.B. aus `object::generate_tangents`
     pub texture: Option<Arc<Texture>>,
-    pub color: u32,
+    pub color: Rgba8,
+    pub material: Material,
+    pub pbr: Option<Arc<PbrMaterial>>, // Ersetzt die unbeleuchtete Farbe durch PBR-Beleuchtung
 }
 
 impl Polygon {
-    pub fn new(colorout: u32) -> Self {
+    ///Farbe als `Rgba8` oder gepackt als 0xAARRGGBB
+    pub fn new(colorout: impl Into<Rgba8>) -> Self {
         Polygon {
             vertices: Vec::new(),
             tex_coords: Vec::new(),
+            normals: Vec::new(),
+            tangents: Vec::new(),
             texture: None,
-            color: colorout,
+            color: colorout.into(),
+            material: Material::default(),
+            pbr: None,
         }
     }
 
@@ -28,8 +40,56 @@ impl Polygon {
         self.tex_coords = tex_vec;
     }
 
-    pub fn set_color(&mut self, color: u32) {
-        self.color = color;
+    ///Eine Normale pro Ecke, z.B. aus `object::smooth_normals`
+    pub fn set_normals(&mut self, normals: Vec<Point>) {
+        self.normals = normals;
+    }
+
+    ///Eine Tangente pro Ecke, passend zu den Normalen
+    pub fn set_tangents(&mut self, tangents: Vec<Tangent>) {
+        self.tangents = tangents;
+    }
+
+    ///UV-Koordinaten der Ecke `index`, ohne Texturkoordinaten (0, 0)
+    pub fn vertex_uv(&self, index: usize) -> (f32, f32) {
+        self.tex_coords.get(index).copied().unwrap_or((0.0, 0.0))
+    }
+
+    ///Normale aus den ersten drei Ecken, zeigt zur Vorderseite
+    pub fn face_normal(&self) -> Point {
+        let edge1 = self.vertices[1] - self.vertices[0];
+        let edge2 = self.vertices[2] - self.vertices[0];
+        edge1.cross(edge2).normalize()
+    }
+
+    ///Verschiebt, dreht und skaliert das Polygon samt Normalen und Tangenten, z.B. mit
+    ///`Transform::to_mat4`. Spiegelungen (negative Determinante) kehren die Vorderseite um
+    pub fn transform(&mut self, matrix: &Mat4) {
+        for vertex in &mut self.vertices {
+            *vertex = matrix.transform_point(*vertex);
+        }
+        if let Some(normal_matrix) = matrix.normal_matrix() {
+            for normal in &mut self.normals {
+                *normal = (*normal * normal_matrix).normalize();
+            }
+        }
+        let handedness = matrix.determinant().signum();
+        for tangent in &mut self.tangents {
+            tangent.direction = matrix.transform_vector(tangent.direction).normalize();
+            tangent.handedness *= handedness;
+        }
+    }
+
+    pub fn set_color(&mut self, color: impl Into<Rgba8>) {
+        self.color = color.into();
+    }
+
+    pub fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    pub fn set_pbr_material(&mut self, material: Arc<PbrMaterial>) {
+        self.pbr = Some(material);
     }
 
     pub fn add_point(&mut self, point: Point) {
@@ -41,45 +101,132 @@ impl Polygon {
 pub struct Polygon2D {
     pub vertices: Vec<Point2D>,
     pub uv_coords: Vec<(f32, f32)>,
+    pub normals: Vec<Point>, // Normalen in Weltkoordinaten, nur bei beleuchteten Materialien
+    pub to_camera: Vec<Point>, // Von der Ecke zur Kamera in Weltkoordinaten, wie `normals`
+    pub tangents: Vec<Tangent>, // Wie `normals`, nur wenn das Polygon Tangenten hat
+}
+
+/// Tangente einer Ecke wie bei MikkTSpace bzw. glTF. `direction` zeigt in Richtung
+/// wachsendem u, die Bitangente `normal × direction * handedness` zum oberen Rand der Textur
+#[derive(Debug, Clone, Copy)]
+pub struct Tangent {
+    pub direction: Point,
+    pub handedness: f32, // 1.0 oder -1.0, bei gespiegelten UVs negativ
+}
+
+impl Tangent {
+    pub fn new(direction: Point, handedness: f32) -> Self {
+        Tangent {
+            direction,
+            handedness,
+        }
+    }
+}
+
+/// Ecke beim Clipping, UV, Normale, Blickrichtung und Tangente werden mit der Position
+/// interpoliert
+#[derive(Clone, Copy)]
+struct ClipVertex {
+    view: Point,
+    uv: (f32, f32),
+    normal: Point,
+    to_camera: Point,
+    tangent: Tangent,
+}
+
+impl ClipVertex {
+    fn lerp(self, other: ClipVertex, t: f32) -> ClipVertex {
+        let mix = |a: Point, b: Point| a + (b - a) * t;
+        ClipVertex {
+            view: mix(self.view, other.view),
+            uv: (
+                self.uv.0 + (other.uv.0 - self.uv.0) * t,
+                self.uv.1 + (other.uv.1 - self.uv.1) * t,
+            ),
+            normal: mix(self.normal, other.normal),
+            to_camera: mix(self.to_camera, other.to_camera),
+            tangent: Tangent::new(
+                mix(self.tangent.direction, other.tangent.direction),
+                self.tangent.handedness,
+            ),
+        }
+    }
 }
 
 pub fn project_polygon(
     polygon: &Polygon,
-    view_matrix: &Matrix4x4,
-    projection_matrix: &Matrix4x4,
+    view_matrix: &Mat4,
+    projection_matrix: &Mat4,
+    camera_position: Point,
+    near_plane: f32,
     screen_width: usize,
     screen_height: usize,
 ) -> Polygon2D {
     let mut vertices_2d: Vec<Point2D> = Vec::new();
     let mut uv_coords_2d: Vec<(f32, f32)> = Vec::new();
+    let mut normals_2d: Vec<Point> = Vec::new();
+    let mut to_camera_2d: Vec<Point> = Vec::new();
+    let mut tangents_2d: Vec<Tangent> = Vec::new();
+
+    // Normalen und Blickrichtungen braucht nur die Beleuchtung
+    let lit = polygon.material.is_reflective() || polygon.pbr.is_some();
+    let face_normal = if lit && polygon.normals.len() != polygon.vertices.len() {
+        polygon.face_normal()
+    } else {
+        Point::new(0.0, 0.0, 0.0)
+    };
+    let has_tangents = lit && polygon.tangents.len() == polygon.vertices.len();
+    let no_tangent = Tangent::new(Point::new(0.0, 0.0, 0.0), 1.0);
+
+    // Bei der Parallelprojektion schauen alle Punkte aus derselben Richtung zur Kamera,
+    // das ist die dritte Spalte der View-Matrix (siehe `Camera::view_matrix`)
+    let perspective = projection_matrix.is_perspective();
+    let parallel_to_camera = Point::new(
+        -view_matrix.data[0][2],
+        -view_matrix.data[1][2],
+        -view_matrix.data[2][2],
+    );
 
     // Transformiere alle Punkte in den View-Space
-    let mut view_vertices: Vec<Point> = polygon
+    let view_vertices: Vec<ClipVertex> = polygon
         .vertices
         .iter()
-        .map(|vertex| view_matrix.multiply_point(vertex))
+        .enumerate()
+        .map(|(i, vertex)| ClipVertex {
+            view: view_matrix.transform_point(*vertex),
+            // Polygone ohne Texturkoordinaten (einfarbig) bekommen (0, 0)
+            uv: polygon.vertex_uv(i),
+            normal: polygon.normals.get(i).copied().unwrap_or(face_normal),
+            to_camera: if perspective {
+                camera_position - *vertex
+            } else {
+                parallel_to_camera
+            },
+            tangent: polygon.tangents.get(i).copied().unwrap_or(no_tangent),
+        })
         .collect();
 
     // Clippe gegen die Near-Plane damit nicht komische obstruktionen entstehen
-    let near_plane = 0.1;
-    view_vertices = clip_polygon_to_near_plane(&view_vertices, near_plane);
+    let view_vertices = clip_polygon_to_near_plane(&view_vertices, near_plane);
 
     // Prüfe ob das Polygon noch existiert
     if view_vertices.len() < 3 {
         return Polygon2D {
             vertices: vertices_2d,
             uv_coords: uv_coords_2d,
+            normals: normals_2d,
+            to_camera: to_camera_2d,
+            tangents: tangents_2d,
         };
     }
 
     // Projiziere alle übriggebliebenen Punkte
-    for (vertex, uv) in view_vertices.iter().zip(&polygon.tex_coords) {
+    for vertex in &view_vertices {
         // Projiziere den Punkt in den Clip-Space
-        let projected = projection_matrix.multiply_point(vertex);
+        let projected = projection_matrix.transform_point(vertex.view);
 
-        // Perspektivische Division
-        let x_ndc = projected.x / projected.z;
-        let y_ndc = projected.y / projected.z;
+        // `transform_point` teilt schon durch w, x und y sind damit NDC
+        let (x_ndc, y_ndc) = (projected.x, projected.y);
 
         // Konvertiere in Bildschirmkoordinaten
         let screen_x = ((screen_width as f32 / 2.0) * (1.0 + x_ndc)).round();
@@ -92,44 +239,46 @@ pub fn project_polygon(
             z: projected.z, // Tiefeninformation ändern sich nicht
         });
 
-        uv_coords_2d.push(*uv);
+        uv_coords_2d.push(vertex.uv);
+
+        if lit {
+            normals_2d.push(vertex.normal);
+            to_camera_2d.push(vertex.to_camera);
+        }
+        if has_tangents {
+            tangents_2d.push(vertex.tangent);
+        }
     }
 
     Polygon2D {
         vertices: vertices_2d,
         uv_coords: uv_coords_2d,
+        normals: normals_2d,
+        to_camera: to_camera_2d,
+        tangents: tangents_2d,
     }
 }
 
-fn clip_polygon_to_near_plane(vertices: &Vec<Point>, near: f32) -> Vec<Point> {
+fn clip_polygon_to_near_plane(vertices: &[ClipVertex], near: f32) -> Vec<ClipVertex> {
     let mut clipped_vertices = Vec::new();
 
     for i in 0..vertices.len() {
         let current = vertices[i];
         let next = vertices[(i + 1) % vertices.len()];
 
-        let current_inside = current.z >= near;
-        let next_inside = next.z >= near;
+        let current_inside = current.view.z >= near;
+        let next_inside = next.view.z >= near;
 
-        // Beide Punkte innerhalb
-        if current_inside && next_inside {
-            clipped_vertices.push(next);
+        // Ecken bleiben in ihrer Reihenfolge, ein ungeclipptes Polygon kommt unverändert heraus
+        if current_inside {
+            clipped_vertices.push(current);
         }
         // Schnittpunkt
-        else if current_inside || next_inside {
-            let t = (near - current.z) / (next.z - current.z);
-            let intersection = Point {
-                x: current.x + t * (next.x - current.x),
-                y: current.y + t * (next.y - current.y),
-                z: near,
-            };
-
-            if current_inside {
-                clipped_vertices.push(intersection);
-            } else {
-                clipped_vertices.push(intersection);
-                clipped_vertices.push(next);
-            }
+        if current_inside != next_inside {
+            let t = (near - current.view.z) / (next.view.z - current.view.z);
+            let mut intersection = current.lerp(next, t);
+            intersection.view.z = near;
+            clipped_vertices.push(intersection);
         }
     }
 
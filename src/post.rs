@@ -0,0 +1,451 @@
+use crate::Framebuffer;
+use crate::camera::{Camera, Projection};
+use crate::color::{Color, Rgba32F, linear_to_srgb, srgb_to_linear};
+use crate::texture::Texture;
+use rayon::prelude::*;
+
+/// Wann ein Effekt in `render_scene` läuft
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PostStage {
+    Hdr, // Vor dem Tone Mapping, mit Werten über 1.0 (z.B. Bloom, Nebel)
+    #[default]
+    Ldr, // Nach Tone Mapping, Kanten und FXAA (z.B. Farbkorrektur, Vignette)
+}
+
+/// Linearer Farbpuffer und Z-Buffer, auf dem die Effekte arbeiten
+pub struct PostFrame<'a> {
+    pub width: usize,
+    pub height: usize,
+    pub color: Vec<Rgba32F>,
+    pub depth: &'a [f32],
+}
+
+/// Ein Effekt der Post-Processing-Kette, siehe `Framebuffer::post_effects`
+pub trait PostEffect: Send + Sync {
+    fn stage(&self) -> PostStage {
+        PostStage::Ldr
+    }
+
+    fn apply(&self, frame: &mut PostFrame);
+}
+
+impl<C: Color> Framebuffer<C> {
+    ///Führt alle Effekte der Stufe in der Reihenfolge von `post_effects` aus
+    pub fn apply_post_effects(&mut self, stage: PostStage) {
+        if !self
+            .post_effects
+            .iter()
+            .any(|effect| effect.stage() == stage)
+        {
+            return;
+        }
+
+        let mut frame = PostFrame {
+            width: self.width,
+            height: self.height,
+            color: self.pixels.par_iter().map(|p| p.to_linear()).collect(),
+            depth: &self.z_buffer,
+        };
+        for effect in self.post_effects.iter().filter(|e| e.stage() == stage) {
+            effect.apply(&mut frame);
+        }
+
+        self.pixels
+            .par_iter_mut()
+            .zip(frame.color.par_iter())
+            .for_each(|(pixel, &color)| *pixel = C::from_linear(color));
+    }
+}
+
+/// Überstrahlen heller Bereiche: Helligkeitsfilter, Gaußscher Weichzeichner, Aufaddieren
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bloom {
+    pub threshold: f32, // Ab dieser Helligkeit strahlt ein Pixel über
+    pub intensity: f32, // Stärke des aufaddierten Leuchtens
+    pub radius: usize,  // Radius des Weichzeichners in Pixeln
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Bloom {
+            threshold: 1.0,
+            intensity: 0.6,
+            radius: 8,
+        }
+    }
+}
+
+impl PostEffect for Bloom {
+    fn stage(&self) -> PostStage {
+        PostStage::Hdr
+    }
+
+    fn apply(&self, frame: &mut PostFrame) {
+        let (width, height) = (frame.width, frame.height);
+
+        // Weicher Übergang an der Schwelle statt hartem Abschneiden
+        let knee = self.threshold * 0.5;
+        let bright: Vec<Rgba32F> = frame
+            .color
+            .par_iter()
+            .map(|&color| {
+                let luma = color.luma();
+                let soft = ((luma - self.threshold + knee) / (2.0 * knee + 1e-5)).clamp(0.0, 1.0);
+                let weight = (luma - self.threshold).max(soft * soft * knee) / luma.max(1e-5);
+                color * weight
+            })
+            .collect();
+
+        let blurred = gaussian_blur(&bright, width, height, self.radius);
+        frame
+            .color
+            .par_iter_mut()
+            .zip(blurred.par_iter())
+            .for_each(|(color, &glow)| {
+                *color = (*color + glow * self.intensity).with_alpha(color.a);
+            });
+    }
+}
+
+///Separierbarer Gaußscher Weichzeichner, beide Durchläufe parallel über Zeilen
+fn gaussian_blur(source: &[Rgba32F], width: usize, height: usize, radius: usize) -> Vec<Rgba32F> {
+    let sigma = (radius as f32 / 2.0).max(0.5);
+    let weights: Vec<f32> = (0..=radius)
+        .map(|i| (-((i * i) as f32) / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total = weights[0] + 2.0 * weights[1..].iter().sum::<f32>();
+    let weights: Vec<f32> = weights.iter().map(|w| w / total).collect();
+    let radius = radius as isize;
+
+    let blur_pass = |input: &[Rgba32F], horizontal: bool| {
+        let mut output = vec![Rgba32F::default(); input.len()];
+        output
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(y, row)| {
+                for (x, out) in row.iter_mut().enumerate() {
+                    let mut sum = Rgba32F::default();
+                    for i in -radius..=radius {
+                        let (sx, sy) = if horizontal {
+                            ((x as isize + i).clamp(0, width as isize - 1), y as isize)
+                        } else {
+                            (x as isize, (y as isize + i).clamp(0, height as isize - 1))
+                        };
+                        let weight = weights[i.unsigned_abs()];
+                        sum = sum + input[sy as usize * width + sx as usize] * weight;
+                    }
+                    *out = sum;
+                }
+            });
+        output
+    };
+
+    let horizontal = blur_pass(source, true);
+    blur_pass(&horizontal, false)
+}
+
+/// Abdunkeln zum Bildrand hin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vignette {
+    pub strength: f32, // 0 = aus, 1 = Ecken schwarz
+    pub radius: f32,   // Abstand zur Mitte (0 - 1, 1 = Ecke), ab dem abgedunkelt wird
+    pub softness: f32, // Breite des Übergangs
+}
+
+impl Default for Vignette {
+    fn default() -> Self {
+        Vignette {
+            strength: 0.6,
+            radius: 0.5,
+            softness: 0.5,
+        }
+    }
+}
+
+impl PostEffect for Vignette {
+    fn apply(&self, frame: &mut PostFrame) {
+        let (width, height) = (frame.width as f32, frame.height as f32);
+        let (cx, cy) = (width / 2.0, height / 2.0);
+        let max_distance = (cx * cx + cy * cy).sqrt();
+
+        frame
+            .color
+            .par_chunks_mut(frame.width)
+            .enumerate()
+            .for_each(|(y, row)| {
+                let dy = y as f32 + 0.5 - cy;
+                for (x, color) in row.iter_mut().enumerate() {
+                    let dx = x as f32 + 0.5 - cx;
+                    let distance = (dx * dx + dy * dy).sqrt() / max_distance;
+                    let t = ((distance - self.radius) / self.softness.max(1e-5)).clamp(0.0, 1.0);
+                    let falloff = 1.0 - self.strength * t * t * (3.0 - 2.0 * t);
+                    *color = (*color * falloff).with_alpha(color.a);
+                }
+            });
+    }
+}
+
+/// Farbkorrektur über eine 3D-Lookup-Tabelle, die Einträge sind sRGB-kodiert
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorGradingLut {
+    pub size: usize,        // Einträge pro Achse
+    pub data: Vec<Rgba32F>, // Index r + g * size + b * size²
+    pub strength: f32,      // Mischung mit dem Original, 1 = nur LUT
+}
+
+impl ColorGradingLut {
+    ///LUT, die jede Farbe auf sich selbst abbildet
+    pub fn identity(size: usize) -> Self {
+        Self::from_fn(size, |color| color)
+    }
+
+    ///Erstellt die LUT aus einer Funktion auf sRGB-kodierten Farben, `size` muss mindestens 2 sein
+    pub fn from_fn(size: usize, f: impl Fn(Rgba32F) -> Rgba32F) -> Self {
+        assert!(size >= 2, "LUT braucht mindestens 2 Einträge pro Achse");
+        let step = 1.0 / (size - 1) as f32;
+        let mut data = Vec::with_capacity(size * size * size);
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    data.push(f(Rgba32F::new(
+                        r as f32 * step,
+                        g as f32 * step,
+                        b as f32 * step,
+                        1.0,
+                    )));
+                }
+            }
+        }
+        ColorGradingLut {
+            size,
+            data,
+            strength: 1.0,
+        }
+    }
+
+    ///Liest eine LUT im üblichen Streifenformat: `size` Kacheln der Größe size x size
+    ///nebeneinander, Blau wählt die Kachel
+    pub fn from_texture(texture: &Texture) -> Self {
+        let size = texture.height;
+        assert!(size >= 2, "LUT braucht mindestens 2 Einträge pro Achse");
+        assert_eq!(
+            texture.width,
+            size * size,
+            "LUT muss size² x size Pixel groß sein"
+        );
+
+        let mut data = Vec::with_capacity(size * size * size);
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    let index = (g * texture.width + b * size + r) * 4;
+                    let texel = &texture.data[index..index + 4];
+                    data.push(Rgba32F::new(
+                        texel[0] as f32 / 255.0,
+                        texel[1] as f32 / 255.0,
+                        texel[2] as f32 / 255.0,
+                        1.0,
+                    ));
+                }
+            }
+        }
+        ColorGradingLut {
+            size,
+            data,
+            strength: 1.0,
+        }
+    }
+
+    ///Trilineare Interpolation zwischen den acht umliegenden Einträgen
+    #[inline(always)]
+    fn lookup(&self, color: Rgba32F) -> Rgba32F {
+        let max = (self.size - 1) as f32;
+        let coordinate = |value: f32| {
+            let scaled = value.clamp(0.0, 1.0) * max;
+            let low = (scaled as usize).min(self.size - 2);
+            (low, scaled - low as f32)
+        };
+        let (r0, tr) = coordinate(color.r);
+        let (g0, tg) = coordinate(color.g);
+        let (b0, tb) = coordinate(color.b);
+
+        let at =
+            |r: usize, g: usize, b: usize| self.data[r + g * self.size + b * self.size * self.size];
+        let plane = |b: usize| {
+            let low = at(r0, g0, b).lerp(at(r0 + 1, g0, b), tr);
+            let high = at(r0, g0 + 1, b).lerp(at(r0 + 1, g0 + 1, b), tr);
+            low.lerp(high, tg)
+        };
+        plane(b0).lerp(plane(b0 + 1), tb)
+    }
+}
+
+impl PostEffect for ColorGradingLut {
+    fn apply(&self, frame: &mut PostFrame) {
+        frame.color.par_iter_mut().for_each(|color| {
+            let encoded = Rgba32F::new(
+                linear_to_srgb(color.r.clamp(0.0, 1.0)),
+                linear_to_srgb(color.g.clamp(0.0, 1.0)),
+                linear_to_srgb(color.b.clamp(0.0, 1.0)),
+                color.a,
+            );
+            let graded = self.lookup(encoded);
+            let graded = Rgba32F::new(
+                srgb_to_linear(graded.r),
+                srgb_to_linear(graded.g),
+                srgb_to_linear(graded.b),
+                color.a,
+            );
+            *color = color.lerp(graded, self.strength);
+        });
+    }
+}
+
+/// Exponentieller Nebel nach der Entfernung aus dem Z-Buffer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthFog {
+    pub color: Rgba32F, // Lineare Nebelfarbe, auch für den leeren Hintergrund
+    pub density: f32,   // Dichte pro Welteinheit
+    pub start: f32,     // Entfernung, ab der der Nebel beginnt
+    pub near: f32,      // Near- und Far-Plane der Kamera zum Linearisieren der Tiefe
+    pub far: f32,
+    pub projection: Projection,
+}
+
+impl DepthFog {
+    pub fn new(color: Rgba32F, density: f32, camera: &Camera) -> Self {
+        DepthFog {
+            color,
+            density,
+            start: 0.0,
+            near: camera.near,
+            far: camera.far,
+            projection: camera.projection,
+        }
+    }
+
+    ///Rechnet die gespeicherte Tiefe (0 an der Near-, 1 an der Far-Plane) in die Entfernung um
+    #[inline(always)]
+    fn view_distance(&self, depth: f32) -> f32 {
+        if !depth.is_finite() {
+            return f32::INFINITY;
+        }
+        self.projection.view_depth(depth, self.near, self.far)
+    }
+}
+
+impl PostEffect for DepthFog {
+    fn stage(&self) -> PostStage {
+        PostStage::Hdr
+    }
+
+    fn apply(&self, frame: &mut PostFrame) {
+        let depth = frame.depth;
+        frame
+            .color
+            .par_iter_mut()
+            .zip(depth.par_iter())
+            .for_each(|(color, &z)| {
+                let distance = (self.view_distance(z) - self.start).max(0.0);
+                let visibility = (-self.density * distance).exp();
+                *color = self.color.lerp(*color, visibility).with_alpha(color.a);
+            });
+    }
+}
+
+/// Schärfen per Unsharp Mask mit den vier direkten Nachbarn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sharpen {
+    pub strength: f32,
+}
+
+impl Default for Sharpen {
+    fn default() -> Self {
+        Sharpen { strength: 0.5 }
+    }
+}
+
+impl PostEffect for Sharpen {
+    fn apply(&self, frame: &mut PostFrame) {
+        let (width, height) = (frame.width, frame.height);
+        let source = frame.color.clone();
+        let at = |x: isize, y: isize| {
+            let x = x.clamp(0, width as isize - 1) as usize;
+            let y = y.clamp(0, height as isize - 1) as usize;
+            source[y * width + x]
+        };
+
+        frame
+            .color
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(y, row)| {
+                let y = y as isize;
+                for (x, color) in row.iter_mut().enumerate() {
+                    let x = x as isize;
+                    let neighbors = at(x - 1, y) + at(x + 1, y) + at(x, y - 1) + at(x, y + 1);
+                    let detail = *color - neighbors * 0.25;
+                    let sharpened = *color + detail * self.strength;
+                    *color = Rgba32F::new(
+                        sharpened.r.max(0.0),
+                        sharpened.g.max(0.0),
+                        sharpened.b.max(0.0),
+                        color.a,
+                    );
+                }
+            });
+    }
+}
+
+/// Farbsäume zum Rand hin: Rot wird nach außen, Blau nach innen verschoben
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChromaticAberration {
+    pub strength: f32, // Verschiebung in Pixeln in den Bildecken
+}
+
+impl Default for ChromaticAberration {
+    fn default() -> Self {
+        ChromaticAberration { strength: 2.0 }
+    }
+}
+
+impl PostEffect for ChromaticAberration {
+    fn apply(&self, frame: &mut PostFrame) {
+        let (width, height) = (frame.width, frame.height);
+        let source = frame.color.clone();
+        let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+        let max_distance = (cx * cx + cy * cy).sqrt();
+
+        frame
+            .color
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(y, row)| {
+                let py = y as f32 + 0.5;
+                for (x, color) in row.iter_mut().enumerate() {
+                    let px = x as f32 + 0.5;
+                    // Richtung von der Mitte, Länge wächst zum Rand hin
+                    let (dx, dy) = ((px - cx) / max_distance, (py - cy) / max_distance);
+                    let (ox, oy) = (dx * self.strength, dy * self.strength);
+
+                    let red = sample_bilinear(&source, width, height, px + ox, py + oy);
+                    let blue = sample_bilinear(&source, width, height, px - ox, py - oy);
+                    *color = Rgba32F::new(red.r, color.g, blue.b, color.a);
+                }
+            });
+    }
+}
+
+///Bilineares Lesen an Pixelkoordinaten (Pixelmitte bei +0.5), Ränder geklemmt
+#[inline(always)]
+fn sample_bilinear(source: &[Rgba32F], width: usize, height: usize, x: f32, y: f32) -> Rgba32F {
+    let x = (x - 0.5).clamp(0.0, width as f32 - 1.0);
+    let y = (y - 0.5).clamp(0.0, height as f32 - 1.0);
+    let (x0, y0) = (x as usize, y as usize);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let (tx, ty) = (x - x0 as f32, y - y0 as f32);
+
+    let top = source[y0 * width + x0].lerp(source[y0 * width + x1], tx);
+    let bottom = source[y1 * width + x0].lerp(source[y1 * width + x1], tx);
+    top.lerp(bottom, ty)
+}
@@ -0,0 +1,128 @@
+use crate::background::Background;
+use crate::color::{Color, Rgba32F};
+use crate::post::PostStage;
+use crate::{Camera, Framebuffer, Polygon, Polygon2D, RenderMode, TransparencyMode, polygon};
+use rayon::prelude::*;
+
+/// Rendert alle Polygone aus Sicht der Kamera in den Framebuffer.
+/// Braucht kein Fenster, damit Tests und Benchmarks dieselbe Pipeline nutzen können.
+pub fn render_scene<C: Color>(
+    polygons: &[Polygon],
+    camera: &Camera,
+    framebuffer: &mut Framebuffer<C>,
+) {
+    let view_matrix = camera.view_matrix(); // Neuberechnung der View-Matrix nach veränderter camera
+    let projection_matrix = camera.projection_matrix();
+    let (width, height) = (framebuffer.width, framebuffer.height);
+    let mode = framebuffer.render_mode;
+
+    framebuffer.clear(); // Framebuffer leeren damit nich sachen übermalt werden
+    framebuffer.draw_background(camera);
+
+    let projected_polygons: Vec<_> = polygons
+        .par_iter()
+        .filter_map(|polygon| {
+            // Im reinen Wireframe sollen auch Rückseiten sichtbar sein
+            if mode != RenderMode::Wireframe && is_backface(polygon, camera) {
+                return None;
+            }
+
+            let projected = polygon::project_polygon(
+                polygon,
+                &view_matrix,
+                &projection_matrix,
+                camera.position,
+                camera.near,
+                width,
+                height,
+            );
+
+            let texture_option = polygon.texture.as_ref().map(|arc| arc.as_ref());
+
+            let color = polygon.color.to_linear();
+
+            Some((
+                projected,
+                texture_option,
+                color,
+                polygon.material,
+                polygon.pbr.as_deref(),
+            ))
+        })
+        .collect();
+
+    // Flächen
+    match mode {
+        RenderMode::Shaded | RenderMode::WireframeOverShaded => {
+            let (transparent, opaque): (Vec<_>, Vec<_>) = projected_polygons
+                .iter()
+                .partition(|(_, _, _, material, _)| material.is_transparent());
+
+            for (projected, texture, color, material, pbr) in opaque {
+                framebuffer.draw_polygon_shaded(projected, *texture, *color, material, *pbr);
+            }
+
+            // Transparente Polygone danach von hinten nach vorne, ohne Tiefe zu schreiben
+            let mut transparent: Vec<_> = transparent
+                .into_iter()
+                .map(|entry| (average_depth(&entry.0), entry))
+                .collect();
+            if framebuffer.transparency == TransparencyMode::Sorted {
+                transparent.sort_by(|a, b| b.0.total_cmp(&a.0));
+            }
+            for (_, (projected, texture, color, material, pbr)) in transparent {
+                framebuffer.draw_polygon_shaded(projected, *texture, *color, material, *pbr);
+            }
+        }
+        RenderMode::HiddenLine => {
+            // Flächen in Hintergrundfarbe füllen, damit der Z-Buffer verdeckte Kanten ausblendet
+            let fill = match framebuffer.background {
+                Background::Solid(color) => color,
+                _ => Rgba32F::BLACK,
+            };
+            for (projected, ..) in &projected_polygons {
+                framebuffer.draw_polygon(projected, None, fill);
+            }
+        }
+        RenderMode::Wireframe => {}
+    }
+
+    framebuffer.resolve_msaa();
+    framebuffer.resolve_transparency();
+    framebuffer.apply_post_effects(PostStage::Hdr);
+    framebuffer.resolve_tone_mapping();
+
+    // Kanten, im reinen Wireframe ohne Tiefentest
+    if mode != RenderMode::Shaded {
+        let line_color = framebuffer.line_color.to_linear();
+        let depth_test = mode != RenderMode::Wireframe;
+        for (projected, ..) in &projected_polygons {
+            framebuffer.draw_polygon_edges(projected, line_color, depth_test);
+        }
+    }
+
+    if framebuffer.fxaa {
+        framebuffer.apply_fxaa();
+    }
+
+    framebuffer.apply_post_effects(PostStage::Ldr);
+}
+
+/// Mittlere Bildschirmtiefe eines projizierten Polygons, zum Sortieren der Transparenz
+fn average_depth(polygon: &Polygon2D) -> f32 {
+    if polygon.vertices.is_empty() {
+        return f32::INFINITY;
+    }
+    polygon.vertices.iter().map(|v| v.z).sum::<f32>() / polygon.vertices.len() as f32
+}
+
+pub fn is_backface(polygon: &Polygon, camera: &Camera) -> bool {
+    if polygon.vertices.len() < 3 {
+        return true; // Kann kein gültiges Polygon sein wenn weniger als 3 Ecken
+    }
+
+    let normal = polygon.face_normal();
+
+    let view_direction = camera.to_camera(polygon.vertices[0]).normalize();
+    normal.dot(view_direction) < 0.0
+}
@@ -0,0 +1,198 @@
+use crate::Polygon2D;
+use crate::color::Rgba32F;
+use crate::environment::Environment;
+use crate::material::Material;
+use crate::pbr::{DirectionalLight, PbrMaterial};
+use crate::point::Point;
+use crate::polygon::Tangent;
+
+/// Wie ein Pixel über Textur bzw. Polygonfarbe hinaus schattiert wird
+#[derive(Clone, Copy)]
+pub(crate) enum SurfaceModel<'a> {
+    ///Spiegelt die Umgebung nach `Material::reflectivity`
+    Reflection {
+        environment: &'a Environment,
+        reflectivity: f32,
+        roughness: f32,
+    },
+    ///Metallic-Roughness mit direktem und bildbasiertem Licht
+    Pbr {
+        material: &'a PbrMaterial,
+        environment: Option<&'a Environment>,
+        lights: &'a [DirectionalLight],
+    },
+}
+
+impl<'a> SurfaceModel<'a> {
+    ///PBR hat Vorrang, sonst nur bei spiegelnden Materialien und vorhandener Umgebung
+    pub(crate) fn new(
+        material: &Material,
+        pbr: Option<&'a PbrMaterial>,
+        environment: Option<&'a Environment>,
+        lights: &'a [DirectionalLight],
+    ) -> Option<Self> {
+        if let Some(pbr) = pbr {
+            return Some(SurfaceModel::Pbr {
+                material: pbr,
+                environment,
+                lights,
+            });
+        }
+        match environment {
+            Some(environment) if material.is_reflective() => Some(SurfaceModel::Reflection {
+                environment,
+                reflectivity: material.reflectivity,
+                roughness: material.roughness,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Eckendaten eines Dreiecks, die pro Pixel interpoliert werden
+pub(crate) struct SurfaceTriangle<'a> {
+    model: SurfaceModel<'a>,
+    normals: [Point; 3],
+    to_camera: [Point; 3],
+    uvs: [(f32, f32); 3],
+    tangents: TangentSource,
+}
+
+/// Woher der Tangentenraum für die Normal Map kommt
+enum TangentSource {
+    None,                   // Keine Normal Map oder keine brauchbaren UVs
+    Vertices([Tangent; 3]), // Beim Laden erzeugte Eckentangenten, werden interpoliert
+    Triangle(Point, Point), // Tangente und Bitangente aus den UVs dieses Dreiecks
+}
+
+impl<'a> SurfaceTriangle<'a> {
+    ///`None`, wenn die Projektion keine Normalen mitliefert
+    pub(crate) fn new(
+        model: SurfaceModel<'a>,
+        polygon: &Polygon2D,
+        indices: [usize; 3],
+    ) -> Option<Self> {
+        if polygon.normals.len() != polygon.vertices.len()
+            || polygon.to_camera.len() != polygon.vertices.len()
+        {
+            return None;
+        }
+        let to_camera = indices.map(|i| polygon.to_camera[i]);
+        let uvs = indices.map(|i| polygon.uv_coords[i]);
+        let tangents = match model {
+            SurfaceModel::Pbr { material, .. } if material.normal_map.is_some() => {
+                if polygon.tangents.len() == polygon.vertices.len() {
+                    TangentSource::Vertices(indices.map(|i| polygon.tangents[i]))
+                } else {
+                    triangle_tangent_frame(to_camera, uvs)
+                        .map_or(TangentSource::None, |(t, b)| TangentSource::Triangle(t, b))
+                }
+            }
+            _ => TangentSource::None,
+        };
+        Some(SurfaceTriangle {
+            model,
+            normals: indices.map(|i| polygon.normals[i]),
+            to_camera,
+            uvs,
+            tangents,
+        })
+    }
+
+    ///Schattiert die Farbe aus Textur bzw. Polygon, `b0` bis `b2` sind die baryzentrischen
+    ///Gewichte der Ecken
+    #[inline(always)]
+    pub(crate) fn shade(&self, color: Rgba32F, b0: f32, b1: f32, b2: f32) -> Rgba32F {
+        let interpolate = |p: [Point; 3]| (p[0] * b0 + p[1] * b1 + p[2] * b2).normalize();
+        let normal = interpolate(self.normals);
+        let view = interpolate(self.to_camera);
+        if !normal.x.is_finite() || !view.x.is_finite() {
+            return color;
+        }
+
+        match self.model {
+            SurfaceModel::Reflection {
+                environment,
+                reflectivity,
+                roughness,
+            } => reflect(environment, color, normal, view, reflectivity, roughness),
+            SurfaceModel::Pbr {
+                material,
+                environment,
+                lights,
+            } => {
+                let u = b0 * self.uvs[0].0 + b1 * self.uvs[1].0 + b2 * self.uvs[2].0;
+                let v = b0 * self.uvs[0].1 + b1 * self.uvs[1].1 + b2 * self.uvs[2].1;
+
+                let frame = match self.tangents {
+                    TangentSource::Vertices(tangents) => Some((
+                        tangents[0].direction * b0
+                            + tangents[1].direction * b1
+                            + tangents[2].direction * b2,
+                        tangents[0].handedness,
+                    )),
+                    TangentSource::Triangle(tangent, bitangent) => {
+                        Some((tangent, bitangent.dot(normal.cross(tangent)).signum()))
+                    }
+                    TangentSource::None => None,
+                };
+                let normal = match frame {
+                    Some((tangent, handedness)) => {
+                        // Gram-Schmidt, damit der Rahmen zur interpolierten Normale passt
+                        let tangent = (tangent - normal * normal.dot(tangent)).normalize();
+                        let bitangent = normal.cross(tangent) * handedness;
+                        if tangent.x.is_finite() {
+                            material.perturb_normal(normal, tangent, bitangent, u, v)
+                        } else {
+                            normal
+                        }
+                    }
+                    None => normal,
+                };
+
+                material
+                    .surface(color, u, v)
+                    .shade(normal, view, lights, environment)
+            }
+        }
+    }
+}
+
+///Mischt die Umgebung in Richtung des an der Normale gespiegelten Blickstrahls zur Farbe
+#[inline(always)]
+fn reflect(
+    environment: &Environment,
+    color: Rgba32F,
+    normal: Point,
+    view: Point,
+    reflectivity: f32,
+    roughness: f32,
+) -> Rgba32F {
+    let cos = normal.dot(view);
+    let reflected = normal * (2.0 * cos) - view;
+    let environment = environment.sample(reflected, roughness);
+
+    // Schlick: an streifenden Winkeln spiegelt jede Fläche stärker, raue etwas weniger
+    let grazing = (1.0 - roughness).max(reflectivity);
+    let fresnel = reflectivity + (grazing - reflectivity) * (1.0 - cos.abs()).powi(5);
+    color.lerp(environment.with_alpha(color.a), fresnel)
+}
+
+///Tangente (wachsendes u) und Bitangente (kleiner werdendes v, also "oben" in der Textur)
+///aus Kanten und UV-Differenzen des Dreiecks. Die Positionen ergeben sich aus den
+///Vektoren zur Kamera, da alle zur selben Kamera zeigen
+fn triangle_tangent_frame(to_camera: [Point; 3], uvs: [(f32, f32); 3]) -> Option<(Point, Point)> {
+    let edge1 = to_camera[0] - to_camera[1];
+    let edge2 = to_camera[0] - to_camera[2];
+    let (du1, dv1) = (uvs[1].0 - uvs[0].0, uvs[1].1 - uvs[0].1);
+    let (du2, dv2) = (uvs[2].0 - uvs[0].0, uvs[2].1 - uvs[0].1);
+
+    let det = du1 * dv2 - du2 * dv1;
+    if det.abs() < f32::EPSILON {
+        return None; // Keine oder degenerierte UVs
+    }
+    let inv_det = 1.0 / det;
+    let tangent = (edge1 * dv2 - edge2 * dv1) * inv_det;
+    let bitangent = (edge2 * du1 - edge1 * du2) * inv_det;
+    Some((tangent, bitangent * -1.0))
+}
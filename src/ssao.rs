@@ -0,0 +1,320 @@
+use crate::camera::{Camera, Projection};
+use crate::point::Point;
+use crate::post::{PostEffect, PostFrame, PostStage};
+use rayon::prelude::*;
+
+/// Seitenlänge des wiederholten Rotationsmusters, das der Weichzeichner wieder glättet
+const NOISE_SIZE: usize = 4;
+
+/// Screen-Space Ambient Occlusion: rekonstruiert Positionen und Normalen aus dem Z-Buffer,
+/// tastet die Halbkugel über jedem Pixel ab und dunkelt verdeckte Bereiche ab
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ssao {
+    pub radius: f32,        // Radius der abgetasteten Halbkugel in Welteinheiten
+    pub intensity: f32,     // 0 = aus, 1 = voll verdeckte Pixel werden schwarz
+    pub bias: f32,          // Verhindert Selbstverdeckung auf ebenen Flächen
+    pub samples: usize,     // Abtastpunkte pro Pixel
+    pub blur_radius: usize, // Radius des bilateralen Weichzeichners in Pixeln
+
+    pub fov: f32, // Kameradaten zum Rekonstruieren der View-Space-Positionen
+    pub aspect_ratio: f32,
+    pub near: f32,
+    pub far: f32,
+    pub projection: Projection,
+}
+
+impl Ssao {
+    pub fn new(camera: &Camera) -> Self {
+        Ssao {
+            radius: 0.5,
+            intensity: 1.0,
+            bias: 0.025,
+            samples: 16,
+            blur_radius: 4,
+
+            fov: camera.fov,
+            aspect_ratio: camera.aspect_ratio,
+            near: camera.near,
+            far: camera.far,
+            projection: camera.projection,
+        }
+    }
+
+    ///Berechnet die Verdeckung pro Pixel (1 = frei, 0 = voll verdeckt), bereits weichgezeichnet
+    pub fn ambient_occlusion(&self, depth: &[f32], width: usize, height: usize) -> Vec<f32> {
+        let view = ViewReconstruction::new(self, width, height);
+        let positions: Vec<Option<Point>> = (0..width * height)
+            .into_par_iter()
+            .map(|i| view.position((i % width) as f32, (i / width) as f32, depth[i]))
+            .collect();
+
+        let kernel = self.kernel();
+        let position_at = |x: isize, y: isize| {
+            if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+                None
+            } else {
+                positions[y as usize * width + x as usize]
+            }
+        };
+
+        let mut occlusion = vec![1.0; width * height];
+        occlusion
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(y, row)| {
+                for (x, ao) in row.iter_mut().enumerate() {
+                    let Some(position) = positions[y * width + x] else {
+                        continue; // Hintergrund
+                    };
+                    let Some(normal) =
+                        reconstruct_normal(position, x as isize, y as isize, &position_at)
+                    else {
+                        continue;
+                    };
+
+                    // Tangentenraum um die Normale, pro Pixel gedreht gegen Bänderung
+                    let angle = noise_angle(x, y);
+                    let random = Point::new(angle.cos(), angle.sin(), 0.0);
+                    let tangent = (random - normal * random.dot(normal)).normalize();
+                    let tangent = if tangent.x.is_finite() {
+                        tangent
+                    } else {
+                        normal.cross(Point::new(0.0, 1.0, 0.0)).normalize()
+                    };
+                    let bitangent = normal.cross(tangent);
+
+                    let mut occluded = 0.0;
+                    for sample in &kernel {
+                        let offset = tangent * sample.x + bitangent * sample.y + normal * sample.z;
+                        let target = position + offset * self.radius;
+
+                        let Some((sx, sy)) = view.project(target) else {
+                            continue;
+                        };
+                        let Some(scene) = position_at(sx as isize, sy as isize) else {
+                            continue;
+                        };
+
+                        // Nur Verdecker in der Nähe zählen voll, weit entfernte blenden aus
+                        if scene.z <= target.z - self.bias {
+                            let range = self.radius / (position.z - scene.z).abs().max(1e-5);
+                            occluded += range.min(1.0);
+                        }
+                    }
+
+                    let occlusion = occluded / kernel.len() as f32;
+                    *ao = (1.0 - occlusion * self.intensity).clamp(0.0, 1.0);
+                }
+            });
+
+        bilateral_blur(&occlusion, &positions, width, height, self.blur_radius)
+    }
+
+    ///Abtastpunkte in der Halbkugel (z > 0), zur Mitte hin dichter. Fest statt zufällig,
+    ///damit das Ergebnis reproduzierbar ist
+    fn kernel(&self) -> Vec<Point> {
+        let count = self.samples.max(1);
+        (0..count)
+            .map(|i| {
+                let (u, v, w) = (
+                    hash(i as u32 * 3),
+                    hash(i as u32 * 3 + 1),
+                    hash(i as u32 * 3 + 2),
+                );
+                let direction = Point::new(u * 2.0 - 1.0, v * 2.0 - 1.0, w.max(0.05)).normalize();
+                let t = i as f32 / count as f32;
+                direction * (0.1 + 0.9 * t * t)
+            })
+            .collect()
+    }
+}
+
+impl PostEffect for Ssao {
+    fn stage(&self) -> PostStage {
+        PostStage::Hdr
+    }
+
+    fn apply(&self, frame: &mut PostFrame) {
+        let occlusion = self.ambient_occlusion(frame.depth, frame.width, frame.height);
+        frame
+            .color
+            .par_iter_mut()
+            .zip(occlusion.par_iter())
+            .for_each(|(color, &ao)| {
+                *color = (*color * ao).with_alpha(color.a);
+            });
+    }
+}
+
+/// Umkehrung der Projektion aus `project_polygon`, inklusive der Division durch die Tiefe
+struct ViewReconstruction {
+    width: f32,
+    height: f32,
+    scale_x: f32, // Einträge der Projektionsmatrix für x und y
+    scale_y: f32,
+    near: f32,
+    far: f32,
+    projection: Projection,
+}
+
+impl ViewReconstruction {
+    fn new(ssao: &Ssao, width: usize, height: usize) -> Self {
+        let (scale_x, scale_y) = match ssao.projection {
+            Projection::Perspective => {
+                let fov_rad = (ssao.fov.to_radians() / 2.0).tan();
+                (1.0 / (ssao.aspect_ratio * fov_rad), 1.0 / fov_rad)
+            }
+            Projection::Orthographic { height } => {
+                (2.0 / (height * ssao.aspect_ratio), 2.0 / height)
+            }
+        };
+        ViewReconstruction {
+            width: width as f32,
+            height: height as f32,
+            scale_x,
+            scale_y,
+            near: ssao.near,
+            far: ssao.far,
+            projection: ssao.projection,
+        }
+    }
+
+    ///w der Projektionsmatrix, die Perspektive teilt durch die Entfernung
+    #[inline(always)]
+    fn w(&self, view_z: f32) -> f32 {
+        match self.projection {
+            Projection::Perspective => view_z,
+            Projection::Orthographic { .. } => 1.0,
+        }
+    }
+
+    #[inline(always)]
+    fn position(&self, x: f32, y: f32, depth: f32) -> Option<Point> {
+        if !depth.is_finite() {
+            return None;
+        }
+        let z = self.projection.view_depth(depth, self.near, self.far);
+        let x_ndc = 2.0 * (x + 0.5) / self.width - 1.0;
+        let y_ndc = 1.0 - 2.0 * (y + 0.5) / self.height;
+        let w = self.w(z);
+        Some(Point::new(
+            x_ndc * w / self.scale_x,
+            y_ndc * w / self.scale_y,
+            z,
+        ))
+    }
+
+    #[inline(always)]
+    fn project(&self, point: Point) -> Option<(f32, f32)> {
+        if point.z <= self.near {
+            return None;
+        }
+        let w = self.w(point.z);
+        let x_ndc = point.x * self.scale_x / w;
+        let y_ndc = point.y * self.scale_y / w;
+        Some((
+            (self.width / 2.0) * (1.0 + x_ndc),
+            (self.height / 2.0) * (1.0 - y_ndc),
+        ))
+    }
+}
+
+///Normale aus den Nachbarpositionen. Pro Achse wird der Nachbar mit der kleineren
+///Tiefendifferenz genommen, damit Kanten zu anderen Objekten keine falschen Normalen ergeben
+fn reconstruct_normal(
+    center: Point,
+    x: isize,
+    y: isize,
+    position_at: &impl Fn(isize, isize) -> Option<Point>,
+) -> Option<Point> {
+    // Ableitung in positiver Richtung der Achse
+    let derivative = |next: Option<Point>, previous: Option<Point>| match (next, previous) {
+        (Some(n), Some(p)) if (n.z - center.z).abs() <= (p.z - center.z).abs() => Some(n - center),
+        (_, Some(p)) => Some(center - p),
+        (Some(n), None) => Some(n - center),
+        (None, None) => None,
+    };
+
+    let dx = derivative(position_at(x + 1, y), position_at(x - 1, y))?;
+    let dy = derivative(position_at(x, y + 1), position_at(x, y - 1))?;
+
+    // Bildschirm-y zeigt nach unten, so zeigt die Normale zur Kamera (negatives z)
+    let normal = dx.cross(dy).normalize();
+    normal.x.is_finite().then_some(normal)
+}
+
+///Separierbarer Weichzeichner, der nur über ähnliche Tiefen mittelt, damit Kanten scharf bleiben
+fn bilateral_blur(
+    source: &[f32],
+    positions: &[Option<Point>],
+    width: usize,
+    height: usize,
+    radius: usize,
+) -> Vec<f32> {
+    if radius == 0 {
+        return source.to_vec();
+    }
+    let radius = radius as isize;
+    let sigma = radius as f32 / 2.0;
+
+    let pass = |input: &[f32], horizontal: bool| {
+        let mut output = input.to_vec();
+        output
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(y, row)| {
+                for (x, out) in row.iter_mut().enumerate() {
+                    let Some(center) = positions[y * width + x] else {
+                        continue;
+                    };
+
+                    let (mut sum, mut total) = (0.0, 0.0);
+                    for i in -radius..=radius {
+                        let (sx, sy) = if horizontal {
+                            (x as isize + i, y as isize)
+                        } else {
+                            (x as isize, y as isize + i)
+                        };
+                        if sx < 0 || sy < 0 || sx >= width as isize || sy >= height as isize {
+                            continue;
+                        }
+                        let index = sy as usize * width + sx as usize;
+                        let Some(neighbor) = positions[index] else {
+                            continue;
+                        };
+
+                        let spatial = (-((i * i) as f32) / (2.0 * sigma * sigma)).exp();
+                        let depth_difference = (neighbor.z - center.z) / (center.z * 0.05);
+                        let weight = spatial * (-depth_difference * depth_difference).exp();
+                        sum += input[index] * weight;
+                        total += weight;
+                    }
+                    if total > 0.0 {
+                        *out = sum / total;
+                    }
+                }
+            });
+        output
+    };
+
+    let horizontal = pass(source, true);
+    pass(&horizontal, false)
+}
+
+///Drehwinkel aus einem kleinen, sich wiederholenden Muster
+#[inline(always)]
+fn noise_angle(x: usize, y: usize) -> f32 {
+    let cell = (y % NOISE_SIZE) * NOISE_SIZE + x % NOISE_SIZE;
+    hash(cell as u32 + 1000) * std::f32::consts::TAU
+}
+
+///Einfacher Integer-Hash auf 0.0 - 1.0
+#[inline(always)]
+fn hash(mut value: u32) -> f32 {
+    value ^= value >> 16;
+    value = value.wrapping_mul(0x7FEB352D);
+    value ^= value >> 15;
+    value = value.wrapping_mul(0x846CA68B);
+    value ^= value >> 16;
+    value as f32 / u32::MAX as f32
+}
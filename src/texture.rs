@@ -1,13 +1,25 @@
-use image::{GenericImageView, open};
+use crate::color::{ColorSpace, Rgba8, Rgba32F};
+use image::{ColorType, GenericImageView, open};
 
 #[derive(Debug, Clone)]
 pub struct Texture {
     pub width: usize,
     pub height: usize,
-    pub data: Vec<u8>, // RGBA-Werte).
+    pub data: Vec<u8>,           // RGBA-Werte).
+    pub color_space: ColorSpace, // Farbtexturen sind sRGB, Datentexturen linear
 }
 
 impl Texture {
+    ///Textur aus RGBA-Daten, standardmäßig als sRGB-Farbtextur
+    pub fn new(width: usize, height: usize, data: Vec<u8>) -> Self {
+        Texture {
+            width,
+            height,
+            data,
+            color_space: ColorSpace::Srgb,
+        }
+    }
+
     pub fn from_file(filepath: &str) -> Self {
         let img = open(filepath).expect("Fehler beim Laden der Texturdatei!");
 
@@ -17,10 +29,114 @@ impl Texture {
 
         let data = img.into_raw();
 
-        Texture {
-            width: width as usize,
-            height: height as usize,
+        Texture::new(width as usize, height as usize, data)
+    }
+
+    pub fn with_color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+
+    ///Liest den Texel an den UV-Koordinaten als lineare Farbe
+    #[inline(always)]
+    pub fn sample(&self, u: f32, v: f32) -> Rgba32F {
+        let texel = self.texel(u, v);
+        match self.color_space {
+            ColorSpace::Srgb => texel.to_linear(),
+            ColorSpace::Linear => Rgba32F::from(texel),
+        }
+    }
+
+    ///Liest den gespeicherten Texel an den UV-Koordinaten (nächster Nachbar, Ränder geklemmt)
+    #[inline(always)]
+    pub fn texel(&self, u: f32, v: f32) -> Rgba8 {
+        let tex_width = self.width as f32;
+        let tex_height = self.height as f32;
+
+        // Skalierung auf Texturgröße
+        let tex_x = (u * tex_width).clamp(0.0, tex_width - 1.0) as usize;
+        let tex_y = (v * tex_height).clamp(0.0, tex_height - 1.0) as usize;
+
+        // Berechne den Textur-Index
+        let tex_index = (tex_y * self.width + tex_x) * 4;
+        let color = &self.data[tex_index..tex_index + 4];
+        Rgba8::new(color[0], color[1], color[2], color[3])
+    }
+}
+
+/// Textur mit linearen Gleitkommawerten, z.B. Umgebungslicht aus `.hdr`- oder `.exr`-Dateien
+#[derive(Debug, Clone)]
+pub struct HdrTexture {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<Rgba32F>,
+}
+
+impl HdrTexture {
+    pub fn new(width: usize, height: usize, data: Vec<Rgba32F>) -> Self {
+        HdrTexture {
+            width,
+            height,
             data,
         }
     }
+
+    ///Lädt Radiance HDR oder OpenEXR, Werte über 1.0 bleiben erhalten. Andere Formate
+    ///(z.B. PNG) gelten als sRGB und werden linearisiert
+    pub fn from_file(filepath: &str) -> Self {
+        let img = open(filepath).expect("Fehler beim Laden der HDR-Datei!");
+        let (width, height) = img.dimensions();
+
+        let data = match img.color() {
+            ColorType::Rgb32F | ColorType::Rgba32F => img
+                .to_rgba32f()
+                .pixels()
+                .map(|pixel| Rgba32F::new(pixel[0], pixel[1], pixel[2], pixel[3]))
+                .collect(),
+            _ => img
+                .to_rgba8()
+                .pixels()
+                .map(|pixel| Rgba8::from(pixel.0).to_linear())
+                .collect(),
+        };
+
+        HdrTexture::new(width as usize, height as usize, data)
+    }
+
+    ///Liest den Texel an den UV-Koordinaten (nächster Nachbar, Ränder geklemmt)
+    #[inline(always)]
+    pub fn sample(&self, u: f32, v: f32) -> Rgba32F {
+        let tex_x = (u * self.width as f32).clamp(0.0, self.width as f32 - 1.0) as usize;
+        let tex_y = (v * self.height as f32).clamp(0.0, self.height as f32 - 1.0) as usize;
+        self.data[tex_y * self.width + tex_x]
+    }
+
+    ///Bilineares Lesen an den UV-Koordinaten, Ränder geklemmt
+    #[inline(always)]
+    pub fn sample_bilinear(&self, u: f32, v: f32) -> Rgba32F {
+        let x = (u * self.width as f32 - 0.5).clamp(0.0, self.width as f32 - 1.0);
+        let y = (v * self.height as f32 - 0.5).clamp(0.0, self.height as f32 - 1.0);
+        let (x0, y0) = (x as usize, y as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (tx, ty) = (x - x0 as f32, y - y0 as f32);
+
+        let top = self.data[y0 * self.width + x0].lerp(self.data[y0 * self.width + x1], tx);
+        let bottom = self.data[y1 * self.width + x0].lerp(self.data[y1 * self.width + x1], tx);
+        top.lerp(bottom, ty)
+    }
+
+    ///Halbe Auflösung (mindestens 1x1), jeder Texel ist der Mittelwert von 2x2 Texeln
+    pub fn downsample(&self) -> HdrTexture {
+        let (width, height) = ((self.width / 2).max(1), (self.height / 2).max(1));
+        let texel = |x: usize, y: usize| {
+            self.data[y.min(self.height - 1) * self.width + x.min(self.width - 1)]
+        };
+        let data = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width * 2, i / width * 2);
+                (texel(x, y) + texel(x + 1, y) + texel(x, y + 1) + texel(x + 1, y + 1)) * 0.25
+            })
+            .collect();
+        HdrTexture::new(width, height, data)
+    }
 }
@@ -0,0 +1,83 @@
+use crate::Framebuffer;
+use crate::color::{Color, Rgba32F};
+use rayon::prelude::*;
+
+/// Bildet lineare HDR-Farben auf den darstellbaren Bereich 0.0 - 1.0 ab
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneMapping {
+    #[default]
+    None, // Keine Abbildung, Werte über 1.0 werden erst bei der Ausgabe abgeschnitten
+    Reinhard,   // x / (1 + x), weich aber etwas flau
+    AcesFilmic, // Annäherung der ACES-Filmkurve nach Narkowicz
+    Uncharted2, // Filmische Kurve von John Hable
+}
+
+/// Weißpunkt der Uncharted2-Kurve, wird auf 1.0 abgebildet
+const UNCHARTED2_WHITE: f32 = 11.2;
+
+impl ToneMapping {
+    /// Nächster Operator, zum Durchschalten per Taste
+    pub fn next(self) -> Self {
+        match self {
+            ToneMapping::None => ToneMapping::Reinhard,
+            ToneMapping::Reinhard => ToneMapping::AcesFilmic,
+            ToneMapping::AcesFilmic => ToneMapping::Uncharted2,
+            ToneMapping::Uncharted2 => ToneMapping::None,
+        }
+    }
+
+    ///Wendet den Operator auf einen linearen Kanalwert an
+    #[inline(always)]
+    pub fn map(self, x: f32) -> f32 {
+        let x = x.max(0.0);
+        match self {
+            ToneMapping::None => x,
+            ToneMapping::Reinhard => x / (1.0 + x),
+            ToneMapping::AcesFilmic => {
+                ((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)).clamp(0.0, 1.0)
+            }
+            ToneMapping::Uncharted2 => {
+                // Die Kurve ist recht dunkel, daher wie im Original mit doppelter Belichtung
+                uncharted2_curve(2.0 * x) / uncharted2_curve(UNCHARTED2_WHITE)
+            }
+        }
+    }
+
+    ///Belichtung (in Blendenstufen) und Operator auf eine Farbe, Alpha bleibt unverändert
+    #[inline(always)]
+    pub fn apply(self, color: Rgba32F, exposure: f32) -> Rgba32F {
+        let scale = exposure.exp2();
+        Rgba32F::new(
+            self.map(color.r * scale),
+            self.map(color.g * scale),
+            self.map(color.b * scale),
+            color.a,
+        )
+    }
+}
+
+#[inline(always)]
+fn uncharted2_curve(x: f32) -> f32 {
+    const A: f32 = 0.15; // Schulter
+    const B: f32 = 0.50; // Linearer Teil
+    const C: f32 = 0.10; // Winkel des linearen Teils
+    const D: f32 = 0.20; // Fuß
+    const E: f32 = 0.02;
+    const F: f32 = 0.30;
+    ((x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F)) - E / F
+}
+
+impl<C: Color> Framebuffer<C> {
+    ///Wendet Belichtung und Tone Mapping auf `pixels` an. Nur mit `Rgba32F` sinnvoll,
+    ///ein 8-Bit-Framebuffer hat Werte über 1.0 bereits beim Schreiben verloren
+    pub fn resolve_tone_mapping(&mut self) {
+        let (mapping, exposure) = (self.tone_mapping, self.exposure);
+        if mapping == ToneMapping::None && exposure == 0.0 {
+            return;
+        }
+
+        self.pixels.par_iter_mut().for_each(|pixel| {
+            *pixel = C::from_linear(mapping.apply(pixel.to_linear(), exposure));
+        });
+    }
+}
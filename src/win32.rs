@@ -0,0 +1,873 @@
+use rake::demo::{run_command, run_tick};
+use rake::render;
+use rake::ssao::Ssao;
+use rake::{
+    Action, ActionState, Bindings, Camera, CameraController, CameraPath, CollisionMesh, Console,
+    Cvar, Cvars, Demo, DemoTick, FlyController, FpsController, Framebuffer, Key, OrbitController,
+    Point, Polygon, RenderMode, Rgba32F, Texture, ToneMapping, object,
+};
+
+use lazy_static::lazy_static;
+use std::collections::BTreeSet;
+use std::ffi::CString;
+use std::ptr::null_mut;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use winapi::ctypes::c_int;
+use winapi::shared::minwindef::{LPARAM, LRESULT, UINT, WPARAM};
+use winapi::shared::windef::{HBITMAP, HDC, HWND, POINT, RECT};
+use winapi::um::libloaderapi::GetModuleHandleA;
+use winapi::um::wingdi::{
+    BI_RGB, BITMAPINFO, BITMAPINFOHEADER, BitBlt, CreateCompatibleDC, CreateDIBSection,
+    DeleteObject, SRCCOPY, SelectObject,
+};
+use winapi::um::winuser::{
+    CS_HREDRAW, CS_VREDRAW, CW_USEDEFAULT, CreateWindowExA, DefWindowProcA, DispatchMessageW,
+    GET_WHEEL_DELTA_WPARAM, GET_XBUTTON_WPARAM, GetClientRect, GetCursorPos, GetWindowRect, MSG,
+    PM_REMOVE, PeekMessageW, PostQuitMessage, RegisterClassA, SW_SHOW, SetCursorPos, ShowCursor,
+    ShowWindow, TranslateMessage, UpdateWindow, VK_ADD, VK_BACK, VK_CONTROL, VK_DOWN, VK_ESCAPE,
+    VK_F1, VK_F12, VK_LEFT, VK_MENU, VK_OEM_3, VK_OEM_5, VK_RETURN, VK_RIGHT, VK_SHIFT, VK_SPACE,
+    VK_SUBTRACT, VK_TAB, VK_UP, WHEEL_DELTA, WM_CHAR, WM_DESTROY, WM_KEYDOWN, WM_KEYUP,
+    WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEWHEEL, WM_QUIT,
+    WM_RBUTTONDOWN, WM_RBUTTONUP, WM_XBUTTONDOWN, WM_XBUTTONUP, WNDCLASSA, WS_OVERLAPPEDWINDOW,
+    WS_VISIBLE, XBUTTON1,
+};
+
+static mut WINDOW_WIDTH: usize = 800;
+static mut WINDOW_HEIGHT: usize = 600;
+static mut POLYGONS: Option<Vec<Polygon>> = None;
+
+lazy_static! {
+    static ref CAMERA: Mutex<Camera> = Mutex::new(Camera::new(
+        Point::new(0.0, 0.0, -5.0),      // Startposition der Kamera
+        Point::new(0.0, 0.0, -1.0),      // Blickrichtung
+        Point::new(0.0, 1.0, 0.0),       // "Up"-Vektor
+        60.0,                            // Field of View (FOV)
+        16f32 / 9f32,                    // Seitenverhältnis
+        0.1,                             // Near-Clipping
+        100.0                            // Far-Clipping
+    ));
+}
+
+lazy_static! {
+    // Tastenbelegung, mit `bind` in der Konsole änderbar
+    static ref BINDINGS: Mutex<Bindings> = Mutex::new(Bindings::default());
+    static ref HELD_KEYS: Mutex<BTreeSet<Key>> = Mutex::new(BTreeSet::new());
+    static ref MOUSE_LOOK: Mutex<bool> = Mutex::new(true); // Aus hält auch die Bewegung an
+    static ref WHEEL: Mutex<f32> = Mutex::new(0.0); // Zoom in Mausrasten seit dem letzten Schritt
+    static ref LOOK: Mutex<(f32, f32)> = Mutex::new((0.0, 0.0)); // Mausbewegung seit dem letzten Schritt
+}
+
+lazy_static! {
+    // Mit `--record datei` bzw. `--playdemo datei` gestartet
+    static ref RECORDING: Mutex<Option<(Demo, String)>> = Mutex::new(None);
+    static ref PLAYBACK: Mutex<Option<Playback>> = Mutex::new(None);
+}
+
+lazy_static! {
+    // Mit `--path datei` geladen, 'K' startet und hält an. Wird nicht in Demos aufgenommen
+    static ref CAMERA_PATH: Mutex<Option<CameraPath>> = Mutex::new(None);
+    static ref PATH_TIME: Mutex<Option<f32>> = Mutex::new(None); // Zeit der laufenden Fahrt
+}
+
+/// Laufende Wiedergabe, ersetzt Tastatur und Maus für die Kamera
+struct Playback {
+    demo: Demo,
+    next: usize,           // Nächster Schritt
+    desync: Option<usize>, // Erster Schritt, an dem die Position abweicht
+}
+
+lazy_static! {
+    // Mit 'C' durchschalten, 'F' richtet die Orbit-Kamera auf das Modell aus
+    static ref CONTROLLERS: Mutex<Vec<Box<dyn CameraController>>> = Mutex::new(vec![
+        Box::new(FpsController),
+        Box::new(OrbitController::new(Point::new(0.0, 0.0, 0.0), 5.0)),
+        Box::new(FlyController::default()),
+    ]);
+    static ref ACTIVE_CONTROLLER: Mutex<usize> = Mutex::new(0);
+    // Befehle, die die Kamera verändern, laufen erst im nächsten Simulationsschritt
+    static ref COMMANDS: Mutex<Vec<Action>> = Mutex::new(Vec::new());
+}
+
+lazy_static! {
+    static ref RENDER_MODE: Mutex<RenderMode> = Mutex::new(RenderMode::Shaded);
+}
+
+lazy_static! {
+    static ref TONE_MAPPING: Mutex<ToneMapping> = Mutex::new(ToneMapping::None);
+}
+
+lazy_static! {
+    // Einstellungen für Bewegung und Darstellung, mit ^ bzw. ` in der Konsole änderbar
+    static ref CVARS: Mutex<Cvars> = Mutex::new(Cvars::new());
+    static ref CONSOLE: Mutex<Console> = Mutex::new(Console::new().with_config(CONFIG_FILE));
+}
+
+/// Änderung der Belichtung pro Tastendruck
+const EXPOSURE_STEP: f32 = 0.5;
+
+/// Gespeicherte Konsolenvariablen, wird beim Start gelesen und beim Beenden geschrieben
+const CONFIG_FILE: &str = "rake.cfg";
+
+/// Gespeicherte Tastenbelegung, ebenso beim Start gelesen und beim Beenden geschrieben
+const BINDINGS_FILE: &str = "bindings.cfg";
+
+/// Windows-Prozedur - Hier wird das Rendering gesteuert
+unsafe extern "system" fn window_proc(
+    hwnd: HWND,
+    msg: UINT,
+    w_param: WPARAM,
+    l_param: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_QUIT => {
+            PostQuitMessage(0);
+            0
+        }
+
+        // Wenn das Fenster zerstört wurde
+        WM_DESTROY => {
+            if let Err(error) = CVARS.lock().unwrap().save(CONFIG_FILE) {
+                println!("{error}");
+            }
+            if let Err(error) = BINDINGS.lock().unwrap().save(BINDINGS_FILE) {
+                println!("{error}");
+            }
+            if let Some((demo, path)) = RECORDING.lock().unwrap().take() {
+                match demo.save(&path) {
+                    Ok(()) => println!("Demo {path}: {} Schritte", demo.ticks.len()),
+                    Err(error) => println!("{error}"),
+                }
+            }
+            // Beende die Anwendung
+            PostQuitMessage(0);
+            0
+        }
+
+        WM_KEYDOWN => {
+            let key = key_from_vk(w_param as i32);
+            let action = key.and_then(|key| BINDINGS.lock().unwrap().action(key));
+            if action == Some(Action::ToggleConsole) {
+                trigger(Action::ToggleConsole);
+            } else if CONSOLE.lock().unwrap().open {
+                let mut console = CONSOLE.lock().unwrap();
+                match w_param as i32 {
+                    VK_RETURN => {
+                        console.submit(&mut CVARS.lock().unwrap(), &mut BINDINGS.lock().unwrap())
+                    }
+                    VK_BACK => console.backspace(),
+                    VK_UP => console.history_previous(),
+                    VK_DOWN => console.history_next(),
+                    VK_TAB => console.complete(&CVARS.lock().unwrap()),
+                    VK_ESCAPE => console.open = false,
+                    _ => {}
+                }
+            } else if let Some(key) = key {
+                key_down(key);
+            }
+            0
+        }
+
+        WM_CHAR => {
+            let mut console = CONSOLE.lock().unwrap();
+            if let Some(c) = char::from_u32(w_param as u32)
+                && console.open
+                && !matches!(c, '^' | '`' | '~' | '°')
+            {
+                console.type_char(c);
+            }
+            0
+        }
+
+        WM_KEYUP => {
+            if let Some(key) = key_from_vk(w_param as i32) {
+                HELD_KEYS.lock().unwrap().remove(&key);
+            }
+            0
+        }
+
+        WM_LBUTTONDOWN | WM_RBUTTONDOWN | WM_MBUTTONDOWN | WM_XBUTTONDOWN => {
+            if !CONSOLE.lock().unwrap().open {
+                key_down(mouse_button(msg, w_param));
+            }
+            // Für die Zusatztasten erwartet Windows TRUE
+            (msg == WM_XBUTTONDOWN) as LRESULT
+        }
+
+        WM_LBUTTONUP | WM_RBUTTONUP | WM_MBUTTONUP | WM_XBUTTONUP => {
+            HELD_KEYS
+                .lock()
+                .unwrap()
+                .remove(&mouse_button(msg, w_param));
+            (msg == WM_XBUTTONUP) as LRESULT
+        }
+
+        WM_MOUSEWHEEL => {
+            let steps = GET_WHEEL_DELTA_WPARAM(w_param) as f32 / WHEEL_DELTA as f32;
+            let key = if steps > 0.0 {
+                Key::WheelUp
+            } else {
+                Key::WheelDown
+            };
+            let action = BINDINGS.lock().unwrap().action(key);
+            if !CONSOLE.lock().unwrap().open {
+                // Zoom behält feine Rasten hochauflösender Mausräder, alles andere löst einmal aus
+                match action {
+                    Some(Action::ZoomIn) => *WHEEL.lock().unwrap() += steps.abs(),
+                    Some(Action::ZoomOut) => *WHEEL.lock().unwrap() -= steps.abs(),
+                    Some(action) => trigger(action),
+                    None => {}
+                }
+            }
+            0
+        }
+
+        _ => DefWindowProcA(hwnd, msg, w_param, l_param),
+    }
+}
+
+/// Plattformunabhängige Taste zu einem virtuellen Tastencode von Windows
+fn key_from_vk(vk: i32) -> Option<Key> {
+    Some(match vk {
+        0x30..=0x39 | 0x41..=0x5A => Key::Char(vk as u8 as char),
+        VK_F1..=VK_F12 => Key::F((vk - VK_F1 + 1) as u8),
+        VK_LEFT => Key::Left,
+        VK_UP => Key::Up,
+        VK_RIGHT => Key::Right,
+        VK_DOWN => Key::Down,
+        VK_SPACE => Key::Space,
+        VK_SHIFT => Key::Shift,
+        VK_CONTROL => Key::Control,
+        VK_MENU => Key::Alt,
+        VK_RETURN => Key::Enter,
+        VK_ESCAPE => Key::Escape,
+        VK_TAB => Key::Tab,
+        VK_BACK => Key::Backspace,
+        VK_ADD => Key::KeypadPlus,
+        VK_SUBTRACT => Key::KeypadMinus,
+        VK_OEM_3 => Key::Grave,     // ` auf amerikanischer Tastatur
+        VK_OEM_5 => Key::Backslash, // ^ auf deutscher Tastatur
+        _ => return None,
+    })
+}
+
+fn mouse_button(msg: UINT, w_param: WPARAM) -> Key {
+    match msg {
+        WM_LBUTTONDOWN | WM_LBUTTONUP => Key::MouseLeft,
+        WM_RBUTTONDOWN | WM_RBUTTONUP => Key::MouseRight,
+        WM_MBUTTONDOWN | WM_MBUTTONUP => Key::MouseMiddle,
+        _ if GET_XBUTTON_WPARAM(w_param) == XBUTTON1 => Key::Mouse4,
+        _ => Key::Mouse5,
+    }
+}
+
+///Merkt sich die Taste und löst beim ersten Druck ihre Aktion aus, nicht bei Wiederholung
+fn key_down(key: Key) {
+    if !HELD_KEYS.lock().unwrap().insert(key) {
+        return;
+    }
+    let action = BINDINGS.lock().unwrap().action(key);
+    if let Some(action) = action {
+        trigger(action);
+    }
+}
+
+///Einmalige Aktionen, gehaltene wie die Bewegung wertet `update_scene` aus
+fn trigger(action: Action) {
+    match action {
+        // Bei der Wiedergabe kommen diese Befehle aus der Aufnahme
+        Action::CycleController
+        | Action::FrameScene
+        | Action::View(_)
+        | Action::ToggleProjection
+            if PLAYBACK.lock().unwrap().is_none() =>
+        {
+            COMMANDS.lock().unwrap().push(action);
+        }
+        Action::ToggleConsole => {
+            // Beim Öffnen alle Tasten loslassen, damit die Kamera nicht weiterläuft
+            CONSOLE.lock().unwrap().toggle();
+            HELD_KEYS.lock().unwrap().clear();
+        }
+        Action::ToggleMouseLook => {
+            let mut mouse_look = MOUSE_LOOK.lock().unwrap();
+            *mouse_look = !*mouse_look;
+        }
+        Action::CycleRenderMode => {
+            let mut mode = RENDER_MODE.lock().unwrap();
+            *mode = mode.next();
+        }
+        Action::CycleToneMapping => {
+            let mut tone_mapping = TONE_MAPPING.lock().unwrap();
+            *tone_mapping = tone_mapping.next();
+        }
+        Action::ToggleSsao => {
+            CVARS.lock().unwrap().execute("toggle r_ssao").ok();
+        }
+        Action::ExposureUp => change_exposure(EXPOSURE_STEP),
+        Action::ExposureDown => change_exposure(-EXPOSURE_STEP),
+        // Die Kamerafahrt steht nicht in der Aufnahme, die Kamera liefe an ihr vorbei
+        Action::PlayPath if demo_running() => {
+            println!("Keine Kamerafahrt während Aufnahme oder Wiedergabe");
+        }
+        Action::PlayPath => {
+            let mut time = PATH_TIME.lock().unwrap();
+            if time.take().is_some() {
+                stop_camera_path();
+            } else if let Some(path) = CAMERA_PATH.lock().unwrap().as_ref() {
+                *time = Some(path.start());
+            }
+        }
+        Action::ZoomIn => *WHEEL.lock().unwrap() += 1.0,
+        Action::ZoomOut => *WHEEL.lock().unwrap() -= 1.0,
+        _ => {}
+    }
+}
+
+///Läuft eine Aufnahme oder Wiedergabe
+fn demo_running() -> bool {
+    RECORDING.lock().unwrap().is_some() || PLAYBACK.lock().unwrap().is_some()
+}
+
+fn change_exposure(step: f32) {
+    let mut cvars = CVARS.lock().unwrap();
+    let exposure = cvars.get_float("r_exposure").unwrap_or(0.0);
+    cvars.set_value("r_exposure", exposure + step).ok();
+}
+
+fn register_cvars(cvars: &mut Cvars) {
+    Camera::register_cvars(cvars);
+    cvars.register(
+        Cvar::new("r_exposure", 0.0, "Exposure in stops, also + and -").with_range(-16.0, 16.0),
+    );
+    cvars.register(Cvar::new(
+        "r_ssao",
+        false,
+        "Screen space ambient occlusion, also O",
+    ));
+    cvars.register(Cvar::new("r_fxaa", false, "FXAA edge smoothing"));
+    cvars.register(Cvar::new("r_aalines", false, "Antialiased wireframe lines"));
+}
+
+///Überträgt die Konsolenvariablen einmal pro Bild auf Kamera und Framebuffer
+fn apply_cvars(framebuffer: &mut Framebuffer<Rgba32F>) {
+    let cvars = CVARS.lock().unwrap();
+    let mut camera = CAMERA.lock().unwrap();
+    let fov = camera.fov;
+    camera.apply_cvars(&cvars);
+    if PATH_TIME.lock().unwrap().is_some() {
+        camera.fov = fov; // Den Öffnungswinkel bestimmt die Kamerafahrt
+    }
+    framebuffer.exposure = cvars.get_float("r_exposure").unwrap_or(0.0);
+    framebuffer.fxaa = cvars.get_bool("r_fxaa").unwrap_or(false);
+    framebuffer.antialiased_lines = cvars.get_bool("r_aalines").unwrap_or(false);
+}
+
+unsafe fn handle_input() {
+    if CONSOLE.lock().unwrap().open || !*MOUSE_LOOK.lock().unwrap() {
+        return;
+    }
+    let (delta_x, delta_y) = process_mouse_input();
+    if PLAYBACK.lock().unwrap().is_some() {
+        return;
+    }
+    let mut look = LOOK.lock().unwrap();
+    *look = (look.0 + delta_x, look.1 + delta_y);
+}
+
+///Mausbewegung seit dem letzten Aufruf, der Zeiger wird wieder in die Fenstermitte gesetzt
+unsafe fn process_mouse_input() -> (f32, f32) {
+    let mut cursor_pos = POINT { x: 0, y: 0 };
+    GetCursorPos(&mut cursor_pos);
+
+    let window_center_x = WINDOW_WIDTH as i32 / 2;
+    let window_center_y = WINDOW_HEIGHT as i32 / 2;
+
+    let delta_x = (cursor_pos.x - window_center_x) as f32;
+    let delta_y = (cursor_pos.y - window_center_y) as f32;
+
+    SetCursorPos(window_center_x, window_center_y);
+    (delta_x, delta_y)
+}
+
+///`--record datei` nimmt die Sitzung auf, `--playdemo datei` spielt eine Aufnahme ab.
+///Die Wiedergabe übernimmt Kamera, Steuerung und Konsolenvariablen der Aufnahme.
+///`--path datei` lädt eine Kamerafahrt und startet sie, außer bei Aufnahme oder Wiedergabe
+fn start_demo(timestep: f32) {
+    let args: Vec<String> = std::env::args().collect();
+    let option = |name: &str| {
+        let index = args.iter().position(|arg| arg == name)?;
+        args.get(index + 1).cloned()
+    };
+
+    if let Some(path) = option("--path") {
+        match CameraPath::load(&path) {
+            Ok(camera_path) => {
+                println!("Kamerafahrt {path}: {:.1} s", camera_path.duration());
+                if option("--playdemo").is_none() && option("--record").is_none() {
+                    *PATH_TIME.lock().unwrap() = Some(camera_path.start());
+                }
+                *CAMERA_PATH.lock().unwrap() = Some(camera_path);
+            }
+            Err(error) => println!("{error}"),
+        }
+    }
+
+    let mut camera = CAMERA.lock().unwrap();
+    let mut controllers = CONTROLLERS.lock().unwrap();
+    let mut active = ACTIVE_CONTROLLER.lock().unwrap();
+    if let Some(path) = option("--playdemo") {
+        let demo = match Demo::load(&path) {
+            Ok(demo) => demo,
+            Err(error) => return println!("{error}"),
+        };
+        *active = match demo.find_controller(&controllers) {
+            Ok(index) => index,
+            Err(error) => return println!("{error}"),
+        };
+        if demo.timestep != timestep {
+            println!("Demo {path} hat einen anderen Zeitschritt, Abweichungen sind zu erwarten");
+        }
+        for error in CVARS.lock().unwrap().exec_config(&demo.cvars) {
+            println!("{error}");
+        }
+        demo.start.restore(&mut camera);
+        controllers[*active].activate(&camera);
+        controllers[*active].restore_state(&demo.controller_state);
+        println!("Demo {path}: {:.1} s", demo.duration());
+        *PLAYBACK.lock().unwrap() = Some(Playback {
+            demo,
+            next: 0,
+            desync: None,
+        });
+    } else if let Some(path) = option("--record") {
+        // Konsolenvariablen wirken erst mit `apply_cvars`, also schon jetzt übernehmen
+        let cvars = CVARS.lock().unwrap();
+        camera.apply_cvars(&cvars);
+        let demo = Demo::new(timestep, &camera, controllers[*active].as_ref(), &cvars);
+        println!("Aufnahme nach {path}");
+        *RECORDING.lock().unwrap() = Some((demo, path));
+    }
+}
+
+/// Initialisierung eines Fensters
+fn init_window() -> HWND {
+    unsafe {
+        let class_name = CString::new("Rake").unwrap();
+
+        let h_instance = GetModuleHandleA(null_mut());
+
+        let wnd_class = WNDCLASSA {
+            style: CS_HREDRAW | CS_VREDRAW, // Stil (neu zeichnen bei Fensterbreiten-/Höhenänderung)
+            lpfnWndProc: Some(window_proc), // Zeiger auf die Windows-Prozedur
+            cbClsExtra: 0,                  // Keine zusätzlichen Bytes in der Fensterklasse
+            cbWndExtra: 0,                  // Keine zusätzlichen Bytes im Fenster
+            hInstance: h_instance,          // Anwendungsinstanz-Handle
+            hIcon: null_mut(),              // Standardsymbol
+            hCursor: null_mut(),            // Standard-Cursor
+            hbrBackground: (1 + 1) as _,    // Hintergrundfarbe (Weiß)
+            lpszMenuName: null_mut(),       // Kein Menü
+            lpszClassName: class_name.as_ptr(), // Klassenname
+        };
+
+        if RegisterClassA(&wnd_class) == 0 {
+            panic!("Fensterklasse konnte nicht registriert werden!");
+        }
+
+        let hwnd = CreateWindowExA(
+            0,                                      // Keine zusätzlichen Fensterstile
+            class_name.as_ptr(),                    // Klassenname
+            CString::new("rake").unwrap().as_ptr(), // Fenstertitel
+            WS_OVERLAPPEDWINDOW | WS_VISIBLE,       // Standardfensterstil
+            CW_USEDEFAULT,                          // Standard-X-Position
+            CW_USEDEFAULT,                          // Standard-Y-Position
+            WINDOW_WIDTH as c_int,                  // Fensterbreite
+            WINDOW_HEIGHT as c_int,                 // Fensterhöhe
+            null_mut(),                             // Kein übergeordnetes Fenster
+            null_mut(),                             // Kein Menü
+            h_instance,                             // Anwendungsinstanz-Handle
+            null_mut(),                             // Keine zusätzlichen Anwendungen
+        );
+
+        if hwnd.is_null() {
+            panic!("Fenster konnte nicht erstellt werden!");
+        }
+
+        ShowWindow(hwnd, SW_SHOW);
+        UpdateWindow(hwnd);
+
+        hwnd
+    }
+}
+
+static mut WINDOW_HDC: Option<HDC> = None;
+
+unsafe fn get_window_hdc(hwnd: HWND) -> HDC {
+    if let Some(hdc) = WINDOW_HDC {
+        return hdc;
+    }
+
+    let hdc = winapi::um::winuser::GetDC(hwnd);
+    WINDOW_HDC = Some(hdc);
+    hdc
+}
+
+unsafe fn draw_frame(
+    framebuffer: &Framebuffer<Rgba32F>,
+    width: usize,
+    height: usize,
+    hbitmap: HBITMAP,
+    pixels: *mut u32,
+    hdc: HDC,
+    window_hdc: HDC,
+) {
+    unsafe {
+        // Das DIB erwartet 0xAARRGGBB, daher explizit aus dem Pixelformat umwandeln
+        framebuffer.write_argb(std::slice::from_raw_parts_mut(pixels, width * height));
+    }
+
+    let old_object = SelectObject(hdc, hbitmap as *mut _);
+
+    BitBlt(
+        window_hdc,
+        0,
+        0,
+        width as i32,
+        height as i32,
+        hdc,
+        0,
+        0,
+        SRCCOPY,
+    );
+
+    // Ressourcenfreigabe
+    SelectObject(hdc, old_object);
+}
+
+///Ein Schritt der Simulation, mit Eingaben aus Tastatur und Maus oder aus der Wiedergabe
+fn update_scene(delta_time: f32, polygons: &[Polygon]) {
+    let Some(tick) = next_tick() else {
+        return;
+    };
+    // Während der Kamerafahrt verfallen die Eingaben
+    if play_camera_path(delta_time) {
+        return;
+    }
+
+    let mut camera = CAMERA.lock().unwrap();
+    let mut controllers = CONTROLLERS.lock().unwrap();
+    let mut active = ACTIVE_CONTROLLER.lock().unwrap();
+    for command in &tick.commands {
+        run_command(
+            *command,
+            &mut camera,
+            &mut controllers,
+            &mut active,
+            polygons,
+        );
+        if *command == Action::CycleController {
+            println!("Kamera: {}", controllers[*active].name());
+        }
+    }
+    run_tick(
+        &mut camera,
+        controllers[*active].as_mut(),
+        &tick,
+        delta_time,
+    );
+
+    if let Some((demo, _)) = RECORDING.lock().unwrap().as_mut() {
+        demo.record(tick, &camera);
+    } else if let Some(playback) = PLAYBACK.lock().unwrap().as_mut()
+        && playback.desync.is_none()
+        && camera.position != tick.position
+    {
+        playback.desync = Some(playback.next - 1);
+    }
+}
+
+///Bewegt die Kamera entlang der laufenden Kamerafahrt, `false` wenn keine läuft
+fn play_camera_path(delta_time: f32) -> bool {
+    let mut playing = PATH_TIME.lock().unwrap();
+    let Some(time) = playing.as_mut() else {
+        return false;
+    };
+    let path = CAMERA_PATH.lock().unwrap();
+    let Some(path) = path.as_ref() else {
+        return false;
+    };
+    *time += delta_time;
+    if let Some(pose) = path.sample(*time) {
+        pose.apply(&mut CAMERA.lock().unwrap());
+    }
+    if !path.looping && *time >= path.end() {
+        *playing = None;
+        stop_camera_path();
+    }
+    true
+}
+
+///Die Steuerung übernimmt die Kamera dort, wo die Fahrt aufgehört hat
+fn stop_camera_path() {
+    let mut controllers = CONTROLLERS.lock().unwrap();
+    controllers[*ACTIVE_CONTROLLER.lock().unwrap()].activate(&CAMERA.lock().unwrap());
+    println!("Kamerafahrt beendet");
+}
+
+///Nächster Schritt der Wiedergabe, sonst aus den gehaltenen Tasten und der Mausbewegung
+///seit dem letzten Schritt. Am Ende der Wiedergabe `None`, danach geht es live weiter
+fn next_tick() -> Option<DemoTick> {
+    let mut playback = PLAYBACK.lock().unwrap();
+    if let Some(current) = playback.as_mut() {
+        if let Some(tick) = current.demo.ticks.get(current.next) {
+            current.next += 1;
+            return Some(tick.clone());
+        }
+        match current.desync {
+            Some(index) => println!("Demo beendet, Abweichung ab Schritt {index}"),
+            None => println!("Demo beendet: {} Schritte", current.demo.ticks.len()),
+        }
+        *playback = None;
+        // Während der Wiedergabe gesammelte Eingaben verwerfen
+        *WHEEL.lock().unwrap() = 0.0;
+        COMMANDS.lock().unwrap().clear();
+        return None;
+    }
+
+    let input = ActionState::from_keys(&BINDINGS.lock().unwrap(), &*HELD_KEYS.lock().unwrap());
+    Some(DemoTick {
+        input,
+        look: std::mem::take(&mut *LOOK.lock().unwrap()),
+        zoom: std::mem::take(&mut *WHEEL.lock().unwrap()),
+        commands: std::mem::take(&mut *COMMANDS.lock().unwrap()),
+        paused: !*MOUSE_LOOK.lock().unwrap(),
+        ..DemoTick::default()
+    })
+}
+
+fn render_scene(polygons: &[Polygon], framebuffer: &mut Framebuffer<Rgba32F>) {
+    let camera = CAMERA.lock().unwrap();
+
+    // SSAO braucht die aktuellen Kameradaten, daher jedes Bild neu anlegen
+    framebuffer.post_effects.clear();
+    if CVARS.lock().unwrap().get_bool("r_ssao").unwrap_or(false) {
+        framebuffer.post_effects.push(Arc::new(Ssao::new(&camera)));
+    }
+
+    render::render_scene(polygons, &camera, framebuffer);
+}
+
+unsafe fn setup_mouse(hwnd: HWND) {
+    ShowCursor(0);
+
+    let mut rect = RECT {
+        left: 0,
+        top: 0,
+        right: 0,
+        bottom: 0,
+    };
+    GetClientRect(hwnd, &mut rect);
+
+    let window_center_x = WINDOW_WIDTH as i32 / 2;
+    let window_center_y = WINDOW_HEIGHT as i32 / 2;
+    SetCursorPos(window_center_x, window_center_y);
+}
+
+unsafe fn create_bitmap_info(framebuffer: &Framebuffer<Rgba32F>) -> BITMAPINFO {
+    let mut bitmap_info: BITMAPINFO = std::mem::zeroed();
+    bitmap_info.bmiHeader.biSize = size_of::<BITMAPINFOHEADER>() as u32;
+    bitmap_info.bmiHeader.biWidth = framebuffer.width as i32;
+    bitmap_info.bmiHeader.biHeight = -(framebuffer.height as i32); // Negative Höhe damit Top-Down-Rendering erfolgt
+    bitmap_info.bmiHeader.biPlanes = 1;
+    bitmap_info.bmiHeader.biBitCount = 32; // (ARGB)
+    bitmap_info.bmiHeader.biCompression = BI_RGB;
+    bitmap_info
+}
+
+pub fn run() {
+    unsafe {
+        let hwnd = init_window();
+
+        {
+            let mut cvars = CVARS.lock().unwrap();
+            register_cvars(&mut cvars);
+            if std::path::Path::new(CONFIG_FILE).exists() {
+                match cvars.load(CONFIG_FILE) {
+                    Ok(errors) => errors.iter().for_each(|error| println!("{error}")),
+                    Err(error) => println!("{error}"),
+                }
+            }
+        }
+        if std::path::Path::new(BINDINGS_FILE).exists() {
+            match BINDINGS.lock().unwrap().load(BINDINGS_FILE) {
+                Ok(errors) => errors.iter().for_each(|error| println!("{error}")),
+                Err(error) => println!("{error}"),
+            }
+        }
+
+        // HDR-Framebuffer, Belichtung und Tone Mapping werden vor der Ausgabe angewendet
+        let mut framebuffer = Framebuffer::<Rgba32F>::with_format(WINDOW_WIDTH, WINDOW_HEIGHT);
+
+        /*
+        let mut texture_input = String::new();
+        let texture_path = loop {
+            print!("Enter texture path: ");
+            io::stdout().flush().unwrap();
+            texture_input.clear();
+            io::stdin().read_line(&mut texture_input).expect("Failed to read input");
+            let trimmed = texture_input.trim();
+            if Path::new(trimmed).exists() {
+                break trimmed;
+            } else {
+                println!("Invalid path. Please try again.");
+            }
+        };
+
+        let mut obj_input = String::new();
+        let obj_path = loop {
+            print!("Enter obj file path: ");
+            io::stdout().flush().unwrap();
+            obj_input.clear();
+            io::stdin().read_line(&mut obj_input).expect("Failed to read input");
+            let trimmed = obj_input.trim();
+            if Path::new(trimmed).exists() {
+                break trimmed;
+            } else {
+                println!("Invalid path. Please try again.");
+            }
+        };
+
+        println!("Texture file path: {}", texture_path);
+        println!("OBJ file path: {}", obj_path);
+
+        let texture = Texture::from_file(texture_path);
+        */
+
+        let texture = Texture::from_file(r#"capsule0.jpg"#);
+
+        let obj_path = r#"capsule.obj"#;
+
+        let mut triangles = object::load_obj(obj_path).expect("Failed to load .obj file");
+
+        println!("Triangles: {:#?}", triangles.len());
+
+        let shared_texture = Arc::new(texture);
+        for triangle in triangles.iter_mut() {
+            triangle.set_texture(shared_texture.clone());
+        }
+
+        {
+            // Ebene unter dem Modell, auf der die Kamera mit dem Auge in Höhe 0 steht
+            let mut camera = CAMERA.lock().unwrap();
+            let ground = -camera.capsule.height;
+            camera.collision = Some(Arc::new(
+                CollisionMesh::new(&triangles).with_ground_plane(ground),
+            ));
+        }
+
+        POLYGONS = Some(triangles);
+
+        let mut bitmap_info = create_bitmap_info(&framebuffer);
+        let window_hdc = get_window_hdc(hwnd);
+        let hdc: HDC = CreateCompatibleDC(window_hdc);
+        let mut pixels: *mut u32 = null_mut();
+        let mut hbitmap = CreateDIBSection(
+            hdc,
+            &bitmap_info,
+            0,
+            &mut pixels as *mut *mut u32 as *mut *mut _,
+            null_mut(),
+            0,
+        );
+
+        const UPDATE_RATE: u64 = 60;
+        const TIMESTEP: f32 = 1.0 / UPDATE_RATE as f32;
+        start_demo(TIMESTEP);
+        let mut previous_time = Instant::now();
+        let mut lag = 0.0;
+        let mut msg: MSG = std::mem::zeroed();
+        let mut rect: RECT = std::mem::zeroed();
+
+        setup_mouse(hwnd);
+
+        loop {
+            if GetWindowRect(hwnd, &mut rect) != 0 {
+                let new_width = (rect.right - rect.left) as usize;
+                let new_height = (rect.bottom - rect.top) as usize;
+
+                if new_width != WINDOW_WIDTH || new_height != WINDOW_HEIGHT {
+                    WINDOW_WIDTH = new_width;
+                    WINDOW_HEIGHT = new_height;
+
+                    framebuffer.resize(WINDOW_WIDTH, WINDOW_HEIGHT);
+
+                    if hbitmap != null_mut() {
+                        DeleteObject(hbitmap as _);
+                    }
+
+                    bitmap_info.bmiHeader.biWidth = WINDOW_WIDTH as i32;
+                    bitmap_info.bmiHeader.biHeight = -(WINDOW_HEIGHT as i32);
+
+                    let mut new_pixels: *mut u32 = null_mut();
+                    hbitmap = CreateDIBSection(
+                        hdc,
+                        &bitmap_info,
+                        0,
+                        &mut new_pixels as *mut *mut u32 as *mut *mut _,
+                        null_mut(),
+                        0,
+                    );
+
+                    if hbitmap.is_null() || new_pixels.is_null() {
+                        panic!("Failed to recreate DIB section after window resize.");
+                    }
+
+                    pixels = new_pixels;
+                }
+            }
+            let current_time = Instant::now();
+            let delta_time = (current_time - previous_time).as_secs_f32();
+            previous_time = current_time;
+
+            lag += delta_time;
+
+            //Nachrichten abarbeiten ohne zu blockieren
+            //User Input etc
+            while PeekMessageW(&mut msg, null_mut(), 0, 0, PM_REMOVE) > 0 {
+                if msg.message == WM_QUIT {
+                    return;
+                }
+                TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+
+            handle_input();
+
+            framebuffer.render_mode = *RENDER_MODE.lock().unwrap();
+            framebuffer.tone_mapping = *TONE_MAPPING.lock().unwrap();
+            apply_cvars(&mut framebuffer);
+
+            // Simulation mit festem Zeitschritt, dann alle Polygone in den framebuffer zeichnen
+            if let Some(ref polygons) = POLYGONS {
+                while lag >= TIMESTEP {
+                    update_scene(TIMESTEP, polygons);
+                    lag -= TIMESTEP;
+                }
+
+                render_scene(polygons, &mut framebuffer);
+            }
+            CONSOLE.lock().unwrap().draw(&mut framebuffer);
+
+            // Zeichne den Frame in das Fenster
+            draw_frame(
+                &framebuffer,
+                framebuffer.width,
+                framebuffer.height,
+                hbitmap,
+                pixels,
+                hdc,
+                window_hdc,
+            );
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Kamerafahrten aus Schlüsseln.
+
+use rake::{Camera, CameraPath, Easing, Interpolation, Keyframe, Point, Quat};
+
+const EPSILON: f32 = 1e-4;
+
+fn assert_close(a: Point, b: Point) {
+    assert!((a - b).magnitude() < EPSILON, "{a:?} != {b:?}");
+}
+
+fn key(time: f32, x: f32, y: f32) -> Keyframe {
+    Keyframe::new(time, Point::new(x, y, 0.0), Quat::IDENTITY, 60.0)
+}
+
+///Blickrichtung der Drehung, die Kamera schaut im eigenen Raum entlang +z
+fn view(orientation: Quat) -> Point {
+    orientation.rotate(Point::new(0.0, 0.0, 1.0))
+}
+
+#[test]
+fn keys_are_hit_exactly() {
+    let path = CameraPath::new(vec![
+        key(2.0, 4.0, 0.0),
+        key(0.0, 0.0, 0.0),
+        key(1.0, 1.0, 3.0),
+    ]);
+    assert_eq!(path.start(), 0.0);
+    assert_eq!(path.duration(), 2.0);
+    for interpolation in [
+        Interpolation::Linear,
+        Interpolation::CatmullRom,
+        Interpolation::Bezier,
+    ] {
+        let path = path.clone().with_interpolation(interpolation);
+        for key in &path.keyframes {
+            assert_close(path.sample(key.time).unwrap().position, key.position);
+        }
+        // Außerhalb bleibt die Kamera am ersten bzw. letzten Schlüssel stehen
+        assert_close(
+            path.sample(-1.0).unwrap().position,
+            Point::new(0.0, 0.0, 0.0),
+        );
+        assert_close(
+            path.sample(5.0).unwrap().position,
+            Point::new(4.0, 0.0, 0.0),
+        );
+    }
+    assert_eq!(CameraPath::default().sample(0.0), None);
+}
+
+#[test]
+fn catmull_rom_is_smooth_where_linear_has_a_corner() {
+    let keys = vec![key(0.0, 0.0, 0.0), key(1.0, 1.0, 1.0), key(2.0, 2.0, 0.0)];
+    let linear = CameraPath::new(keys.clone()).with_interpolation(Interpolation::Linear);
+    assert_close(
+        linear.sample(0.5).unwrap().position,
+        Point::new(0.5, 0.5, 0.0),
+    );
+
+    // Am mittleren Schlüssel ist die Kurve waagrecht, die Geschwindigkeit stetig
+    let spline = CameraPath::new(keys);
+    let velocity = |time: f32| {
+        let h = 1e-3;
+        (spline.sample(time + h).unwrap().position - spline.sample(time - h).unwrap().position)
+            * (0.5 / h)
+    };
+    let corner = velocity(1.0);
+    assert!(corner.y.abs() < 1e-2, "{corner:?}");
+    assert!((velocity(0.999) - velocity(1.001)).magnitude() < 1e-2);
+    assert!((linear.sample(0.999).unwrap().position.y - 0.999).abs() < EPSILON);
+    assert!(spline.sample(0.5).unwrap().position.y > 0.5);
+}
+
+#[test]
+fn easing_slows_down_at_keys() {
+    assert_eq!(Easing::Linear.apply(0.25), 0.25);
+    assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+    assert!(Easing::EaseIn.apply(0.25) < 0.25);
+    assert!(Easing::EaseOut.apply(0.25) > 0.25);
+    for easing in [Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+        assert_eq!(easing.apply(0.0), 0.0);
+        assert_eq!(easing.apply(1.0), 1.0);
+    }
+
+    let path = CameraPath::new(vec![
+        key(0.0, 0.0, 0.0).with_easing(Easing::EaseInOut),
+        key(1.0, 10.0, 0.0),
+    ])
+    .with_interpolation(Interpolation::Linear);
+    assert_close(
+        path.sample(0.5).unwrap().position,
+        Point::new(5.0, 0.0, 0.0),
+    );
+    // Kurz nach dem Start und vor dem Ende kaum Bewegung
+    assert!(path.sample(0.01).unwrap().position.x < 0.01);
+    assert!(path.sample(0.99).unwrap().position.x > 9.99);
+}
+
+#[test]
+fn bezier_handles_shape_the_curve() {
+    let keys = vec![
+        key(0.0, 0.0, 0.0).with_handles(Point::new(0.0, 0.0, 0.0), Point::new(0.0, 3.0, 0.0)),
+        key(1.0, 3.0, 0.0).with_handles(Point::new(0.0, 3.0, 0.0), Point::new(0.0, 0.0, 0.0)),
+    ];
+    let bezier = CameraPath::new(keys.clone()).with_interpolation(Interpolation::Bezier);
+    // Bogen nach oben: 3/4 der Griffhöhe in der Mitte
+    assert_close(
+        bezier.sample(0.5).unwrap().position,
+        Point::new(1.5, 2.25, 0.0),
+    );
+    // Catmull-Rom übergeht die Griffe
+    let spline = CameraPath::new(keys);
+    assert_close(
+        spline.sample(0.5).unwrap().position,
+        Point::new(1.5, 0.0, 0.0),
+    );
+}
+
+#[test]
+fn orientation_and_fov_are_interpolated() {
+    let a = Keyframe::look_at(
+        0.0,
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(0.0, 0.0, 1.0),
+        40.0,
+    );
+    let b = Keyframe::look_at(
+        1.0,
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(1.0, 0.0, 0.0),
+        80.0,
+    );
+    let path = CameraPath::new(vec![a, b]).with_interpolation(Interpolation::Linear);
+    let pose = path.sample(0.5).unwrap();
+    assert!((pose.fov - 60.0).abs() < EPSILON);
+    let half = std::f32::consts::FRAC_1_SQRT_2;
+    assert_close(view(pose.orientation), Point::new(half, 0.0, half));
+
+    let mut camera = Camera::new(
+        Point::new(5.0, 5.0, 5.0),
+        Point::new(0.0, 0.0, 1.0),
+        Point::new(0.0, 1.0, 0.0),
+        90.0,
+        1.0,
+        0.1,
+        100.0,
+    );
+    camera.velocity = Point::new(1.0, 0.0, 0.0);
+    path.sample(1.0).unwrap().apply(&mut camera);
+    assert_eq!(camera.fov, 80.0);
+    assert_eq!(camera.position, Point::new(0.0, 0.0, 0.0));
+    assert_eq!(camera.velocity, Point::new(0.0, 0.0, 0.0));
+    // `forward` zeigt von der Blickrichtung weg
+    assert_close(camera.forward, Point::new(-1.0, 0.0, 0.0));
+}
+
+#[test]
+fn orientation_turns_smoothly_through_keys() {
+    let turn = |time: f32, degrees: f32| {
+        Keyframe::new(
+            time,
+            Point::new(0.0, 0.0, 0.0),
+            Quat::from_euler(degrees.to_radians(), 0.0, 0.0),
+            60.0,
+        )
+    };
+    let keys = vec![turn(0.0, 0.0), turn(1.0, 10.0), turn(2.0, 60.0)];
+    // Drehrate in Grad pro Sekunde kurz vor und kurz nach dem mittleren Schlüssel
+    let rates = |path: &CameraPath| {
+        let yaw = |time: f32| {
+            path.sample(time)
+                .unwrap()
+                .orientation
+                .to_euler()
+                .0
+                .to_degrees()
+        };
+        let step = 1e-2;
+        (
+            (yaw(1.0) - yaw(1.0 - step)) / step,
+            (yaw(1.0 + step) - yaw(1.0)) / step,
+        )
+    };
+
+    let linear = CameraPath::new(keys.clone()).with_interpolation(Interpolation::Linear);
+    let (before, after) = rates(&linear);
+    assert!((before - 10.0).abs() < 0.5 && (after - 50.0).abs() < 0.5);
+
+    for interpolation in [Interpolation::CatmullRom, Interpolation::Bezier] {
+        let path = CameraPath::new(keys.clone()).with_interpolation(interpolation);
+        for key in &path.keyframes {
+            let orientation = path.sample(key.time).unwrap().orientation;
+            assert_close(view(orientation), view(key.orientation));
+        }
+        let (before, after) = rates(&path);
+        assert!((before - 30.0).abs() < 1.0, "{before}");
+        assert!((after - 30.0).abs() < 1.0, "{after}");
+    }
+}
+
+#[test]
+fn turntable_circles_target_and_loops() {
+    let target = Point::new(1.0, 0.0, 2.0);
+    let path = CameraPath::turntable(target, 5.0, 2.0, 8.0, 50.0);
+    assert!(path.looping);
+    assert_eq!(path.duration(), 8.0);
+    // Beim Schleifen fehlt das letzte Bild, es gleicht dem ersten
+    assert_eq!(path.frame_count(30.0), 240);
+    assert_eq!(
+        CameraPath::new(vec![key(0.0, 0.0, 0.0), key(1.0, 1.0, 0.0)]).frame_count(30.0),
+        31
+    );
+
+    for step in 0..40 {
+        let time = step as f32 * 0.37;
+        let pose = path.sample(time).unwrap();
+        let offset = pose.position - target;
+        let radius = (offset.x * offset.x + offset.z * offset.z).sqrt();
+        assert!((radius - 5.0).abs() < 0.05, "{time}: {radius}");
+        assert!((offset.y - 2.0).abs() < EPSILON);
+        let towards = (target - pose.position).normalize();
+        assert!(view(pose.orientation).dot(towards) > 0.999, "{time}");
+    }
+    assert_close(
+        path.sample(9.0).unwrap().position,
+        path.sample(1.0).unwrap().position,
+    );
+}
+
+#[test]
+fn parse_text_format() {
+    let path = CameraPath::parse(
+        "// Kamerafahrt\n\
+         interpolation bezier\n\
+         loop\n\
+         key 2 0 0 -5 look 0 0 0 ease inout\n\
+         key 0 0 0 -5 angles -90 0 0 fov 45\n\
+         handles 0 0 0 1 0 0\n\
+         \n\
+         key 1 5 0 0 look 0 0 0 // zur Seite\n",
+    )
+    .unwrap();
+    assert_eq!(path.interpolation, Interpolation::Bezier);
+    assert!(path.looping);
+    assert_eq!(path.keyframes.len(), 3);
+    let times: Vec<_> = path.keyframes.iter().map(|key| key.time).collect();
+    assert_eq!(times, [0.0, 1.0, 2.0]);
+    assert_eq!(path.keyframes[0].fov, 45.0);
+    assert_eq!(
+        path.keyframes[0].handles,
+        Some((Point::new(0.0, 0.0, 0.0), Point::new(1.0, 0.0, 0.0)))
+    );
+    // Ohne `fov` gilt der vorige Schlüssel in der Datei
+    assert_eq!(path.keyframes[1].fov, 45.0);
+    assert_eq!(path.keyframes[2].fov, 60.0);
+    assert_eq!(path.keyframes[2].easing, Easing::EaseInOut);
+    // `angles -90 0 0` und `look` auf den Ursprung schauen beide entlang +z
+    assert_close(
+        view(path.keyframes[0].orientation),
+        Point::new(0.0, 0.0, 1.0),
+    );
+    assert_close(
+        view(path.keyframes[2].orientation),
+        Point::new(0.0, 0.0, 1.0),
+    );
+
+    for (text, line, message) in [
+        (
+            "key 0 0 0 0 look 0 0 1\nkey x 0 0 0 look 0 0 1",
+            "line 2:",
+            "not a number",
+        ),
+        ("handles 0 0 0 0 0 0", "line 1:", "without a previous key"),
+        ("interpolation cubic", "line 1:", "Unknown interpolation"),
+        (
+            "\nkey 0 0 0 0 look 0 0 1 ease fast",
+            "line 2:",
+            "Unknown easing",
+        ),
+        (
+            "key 0 0 0 0 toward 0 0 1",
+            "line 1:",
+            "Expected look or angles",
+        ),
+        ("fly", "line 1:", "Unknown path command"),
+    ] {
+        let error = CameraPath::parse(text).unwrap_err();
+        assert!(
+            error.starts_with(line) && error.contains(message),
+            "{error}"
+        );
+    }
+    assert!(
+        CameraPath::load("does/not/exist.path")
+            .unwrap_err()
+            .starts_with("Failed to read camera path")
+    );
+}
@@ -0,0 +1,59 @@
+//! Clipping an der Near-Plane.
+
+use rake::polygon::project_polygon;
+use rake::{Camera, Point, Polygon};
+
+const WIDTH: usize = 200;
+const HEIGHT: usize = 100;
+
+#[test]
+fn uvs_stay_with_their_corners_through_the_near_clip() {
+    // Kamera im Ursprung, die entlang +Z schaut
+    let camera = Camera::new(
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(0.0, 0.0, -1.0),
+        Point::new(0.0, 1.0, 0.0),
+        60.0,
+        WIDTH as f32 / HEIGHT as f32,
+        0.1,
+        100.0,
+    );
+    let mut triangle = Polygon::new(0xFFFFFFFF);
+    // Die zweite Ecke liegt hinter der Near-Plane und wird abgeschnitten
+    for corner in [
+        Point::new(-1.0, 1.0, 5.0),
+        Point::new(0.0, 0.0, -5.0),
+        Point::new(1.0, -1.0, 5.0),
+    ] {
+        triangle.add_point(corner);
+    }
+    triangle.set_tex_coords(vec![(0.0, 0.0), (0.5, 1.0), (1.0, 0.0)]);
+
+    let projected = project_polygon(
+        &triangle,
+        &camera.view_matrix(),
+        &camera.projection_matrix(),
+        camera.position,
+        camera.near,
+        WIDTH,
+        HEIGHT,
+    );
+    assert_eq!(projected.vertices.len(), 4);
+    assert_eq!(projected.uv_coords.len(), 4);
+    // Erste und letzte Ecke bleiben an ihrem Platz, dazwischen die Schnittpunkte
+    assert_eq!(projected.uv_coords[0], (0.0, 0.0));
+    assert_eq!(projected.uv_coords[3], (1.0, 0.0));
+    let (left, right) = (projected.vertices[0], projected.vertices[3]);
+    assert!(left.x < WIDTH as f32 / 2.0 && left.y < HEIGHT as f32 / 2.0);
+    assert!(right.x > WIDTH as f32 / 2.0 && right.y > HEIGHT as f32 / 2.0);
+    // Bei z = 0.1 liegen die Schnittpunkte bei 49% bzw. 51% der Kanten
+    for (uv, expected) in projected.uv_coords[1..3]
+        .iter()
+        .zip([(0.245, 0.49), (0.755, 0.49)])
+    {
+        assert!(
+            (uv.0 - expected.0).abs() < 1e-5 && (uv.1 - expected.1).abs() < 1e-5,
+            "{uv:?}"
+        );
+    }
+}
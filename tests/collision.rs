@@ -0,0 +1,236 @@
+//! Kollision der Kamera mit der Szene: Gleiten, Stufen, Hänge und kein Durchtunneln.
+
+use rake::{ActionState, Camera, Capsule, CollisionMesh, Point, Polygon};
+use std::sync::Arc;
+
+const TIMESTEP: f32 = 1.0 / 60.0;
+
+fn quad(corners: [(f32, f32, f32); 4]) -> Polygon {
+    let mut polygon = Polygon::new(0xFFFFFFFF);
+    for (x, y, z) in corners {
+        polygon.add_point(Point::new(x, y, z));
+    }
+    polygon
+}
+
+fn floor(y: f32) -> Polygon {
+    quad([
+        (-20.0, y, -20.0),
+        (20.0, y, -20.0),
+        (20.0, y, 20.0),
+        (-20.0, y, 20.0),
+    ])
+}
+
+///Senkrechte Wand in der Ebene x = `x`
+fn wall(x: f32) -> Polygon {
+    quad([
+        (x, -1.0, -20.0),
+        (x, 5.0, -20.0),
+        (x, 5.0, 20.0),
+        (x, -1.0, 20.0),
+    ])
+}
+
+///Quader von `min` bis `max`, nur Deckel und die Seite zu -x
+fn block(min: Point, max: Point) -> Vec<Polygon> {
+    vec![
+        quad([
+            (min.x, max.y, min.z),
+            (max.x, max.y, min.z),
+            (max.x, max.y, max.z),
+            (min.x, max.y, max.z),
+        ]),
+        quad([
+            (min.x, min.y, min.z),
+            (min.x, max.y, min.z),
+            (min.x, max.y, max.z),
+            (min.x, min.y, max.z),
+        ]),
+    ]
+}
+
+///Hang, der ab x = 0 mit dem Winkel `degrees` ansteigt
+fn slope(degrees: f32) -> Polygon {
+    let rise = 20.0 * degrees.to_radians().tan();
+    quad([
+        (0.0, 0.0, -20.0),
+        (20.0, rise, -20.0),
+        (20.0, rise, 20.0),
+        (0.0, 0.0, 20.0),
+    ])
+}
+
+fn player(position: Point, polygons: &[Polygon]) -> Camera {
+    let mut camera = Camera::new(
+        position,
+        Point::new(0.0, 0.0, 1.0),
+        Point::new(0.0, 1.0, 0.0),
+        60.0,
+        16.0 / 9.0,
+        0.1,
+        100.0,
+    );
+    camera.collision = Some(Arc::new(CollisionMesh::new(polygons)));
+    camera
+}
+
+///Fußhöhe einer stehenden Kamera
+fn feet(camera: &Camera) -> f32 {
+    camera.position.y - camera.capsule.height
+}
+
+///Bewegt die Kamera mit fester waagrechter Geschwindigkeit durch `update_movement`
+fn walk(camera: &mut Camera, velocity: Point, ticks: usize) {
+    for _ in 0..ticks {
+        // Am Boden bremst die Reibung jeden Schritt auf 60 %
+        camera.velocity = if camera.on_ground {
+            velocity * (1.0 / 0.6)
+        } else {
+            velocity
+        };
+        camera.update_movement(TIMESTEP, &ActionState::new(), (0.0, 0.0));
+    }
+}
+
+#[test]
+fn sphere_sweep_hits_face_edge_and_vertex() {
+    let mesh = CollisionMesh::new(&[quad([
+        (0.0, 0.0, 0.0),
+        (0.0, 1.0, 0.0),
+        (0.0, 1.0, 1.0),
+        (0.0, 0.0, 1.0),
+    ])]);
+
+    // Fläche: Kugel mit Radius 0.5 startet bei x = -2
+    let hit = mesh
+        .sweep_sphere(Point::new(-2.0, 0.5, 0.5), 0.5, Point::new(4.0, 0.0, 0.0))
+        .unwrap();
+    assert!((hit.time - 0.375).abs() < 1e-5, "{hit:?}");
+    assert!((hit.normal - Point::new(-1.0, 0.0, 0.0)).magnitude() < 1e-5);
+
+    // Kante bei y = 1: die Kugel streift sie mit dem Mittelpunkt 0.3 darüber
+    let hit = mesh
+        .sweep_sphere(Point::new(-2.0, 1.3, 0.5), 0.5, Point::new(4.0, 0.0, 0.0))
+        .unwrap();
+    assert!(
+        (hit.point - Point::new(0.0, 1.0, 0.5)).magnitude() < 1e-4,
+        "{hit:?}"
+    );
+    assert!((hit.time - (2.0 - 0.4) / 4.0).abs() < 1e-4);
+
+    // Ecke
+    let hit = mesh
+        .sweep_sphere(Point::new(-2.0, 1.3, 1.0), 0.5, Point::new(4.0, 0.0, 0.0))
+        .unwrap();
+    assert!(
+        (hit.point - Point::new(0.0, 1.0, 1.0)).magnitude() < 1e-4,
+        "{hit:?}"
+    );
+
+    // Knapp vorbei
+    assert!(
+        mesh.sweep_sphere(Point::new(-2.0, 1.6, 0.5), 0.5, Point::new(4.0, 0.0, 0.0))
+            .is_none()
+    );
+}
+
+#[test]
+fn falls_onto_floor_and_stands() {
+    let mut camera = player(Point::new(0.0, 5.0, 0.0), &[floor(0.0)]);
+    walk(&mut camera, Point::new(0.0, 0.0, 0.0), 120);
+
+    assert!(camera.on_ground);
+    assert_eq!(camera.vertical_velocity, 0.0);
+    assert!(feet(&camera).abs() < 0.01, "{:?}", camera.position);
+}
+
+#[test]
+fn slides_along_wall() {
+    let polygons = [floor(0.0), wall(2.0)];
+    let mut camera = player(Point::new(0.0, 2.0, 0.0), &polygons);
+    walk(&mut camera, Point::new(3.0, 0.0, 3.0), 60);
+
+    let radius = camera.capsule.radius;
+    assert!(
+        camera.position.x <= 2.0 - radius + 1e-3,
+        "{:?}",
+        camera.position
+    );
+    assert!(camera.position.x > 2.0 - radius - 0.05);
+    // Entlang der Wand geht es weiter, fast mit voller Geschwindigkeit
+    assert!(camera.position.z > 2.5, "{:?}", camera.position);
+    assert!(camera.velocity.x.abs() < 0.1 && camera.velocity.z > 2.9);
+}
+
+#[test]
+fn steps_up_low_stairs_but_not_walls() {
+    let mut polygons = vec![floor(0.0)];
+    polygons.extend(block(
+        Point::new(1.0, 0.0, -20.0),
+        Point::new(20.0, 0.3, 20.0),
+    ));
+    let mut camera = player(Point::new(0.0, 2.0, 0.0), &polygons);
+    walk(&mut camera, Point::new(0.0, 0.0, 0.0), 60);
+    walk(&mut camera, Point::new(2.0, 0.0, 0.0), 60);
+    assert!(camera.position.x > 1.5, "{:?}", camera.position);
+    assert!((feet(&camera) - 0.3).abs() < 0.01, "{:?}", camera.position);
+    assert!(camera.on_ground);
+
+    let mut polygons = vec![floor(0.0)];
+    polygons.extend(block(
+        Point::new(1.0, 0.0, -20.0),
+        Point::new(20.0, 1.0, 20.0),
+    ));
+    let mut camera = player(Point::new(0.0, 2.0, 0.0), &polygons);
+    walk(&mut camera, Point::new(0.0, 0.0, 0.0), 60);
+    walk(&mut camera, Point::new(2.0, 0.0, 0.0), 60);
+    assert!(camera.position.x < 1.0, "{:?}", camera.position);
+    assert!(feet(&camera).abs() < 0.01);
+}
+
+#[test]
+fn walks_gentle_slopes_and_slides_off_steep_ones() {
+    let mut camera = player(Point::new(10.0, 10.0, 0.0), &[floor(0.0), slope(30.0)]);
+    walk(&mut camera, Point::new(0.0, 0.0, 0.0), 120);
+    assert!(camera.on_ground);
+    let x = camera.position.x;
+    walk(&mut camera, Point::new(0.0, 0.0, 0.0), 60);
+    assert!(
+        (camera.position.x - x).abs() < 1e-3,
+        "rutscht auf begehbarem Hang"
+    );
+
+    // Bergab bleibt die Kamera am Boden, statt abzuheben
+    for _ in 0..30 {
+        walk(&mut camera, Point::new(-3.0, 0.0, 0.0), 1);
+        assert!(camera.on_ground, "{:?}", camera.position);
+    }
+
+    let mut camera = player(Point::new(10.0, 25.0, 0.0), &[floor(0.0), slope(60.0)]);
+    walk(&mut camera, Point::new(0.0, 0.0, 0.0), 300);
+    // Am Fuß des Hangs auf dem flachen Boden angekommen
+    assert!(camera.position.x < 1.0, "{:?}", camera.position);
+    assert!(camera.on_ground);
+}
+
+#[test]
+fn fast_motion_does_not_tunnel() {
+    let polygons = [floor(0.0), wall(2.0)];
+    let mut camera = player(Point::new(0.0, 2.0, 0.0), &polygons);
+    walk(&mut camera, Point::new(0.0, 0.0, 0.0), 60);
+    // Ein Schritt weit über die Wand hinaus
+    walk(&mut camera, Point::new(600.0, 0.0, 0.0), 3);
+    assert!(camera.position.x < 2.0, "{:?}", camera.position);
+}
+
+#[test]
+fn ground_plane_replaces_floor_mesh() {
+    let capsule = Capsule::default();
+    let mesh = CollisionMesh::new(&[]).with_ground_plane(-capsule.height);
+    let mut camera = player(Point::new(0.0, 3.0, 0.0), &[]);
+    camera.collision = Some(Arc::new(mesh));
+    walk(&mut camera, Point::new(0.0, 0.0, 0.0), 120);
+    assert!(camera.on_ground);
+    assert!(camera.position.y.abs() < 0.01, "{:?}", camera.position);
+}
@@ -0,0 +1,142 @@
+//! Kamerasteuerungen: Orbit, Flugmodus und Umschalten ohne Sprung.
+
+use rake::{
+    Action, ActionState, Camera, CameraController, FlyController, FpsController, OrbitController,
+    Point, Polygon, Quat,
+};
+
+fn assert_close(a: Point, b: Point) {
+    assert!((a - b).magnitude() < 1e-4, "{a:?} != {b:?}");
+}
+
+///Blickrichtung ist `-forward`, muss auf das Ziel zeigen
+fn assert_looks_at(camera: &Camera, target: Point) {
+    let to_target = (target - camera.position).normalize();
+    assert_close(camera.forward.normalize() * -1.0, to_target);
+}
+
+#[test]
+fn orbit_keeps_distance_and_looks_at_target() {
+    let target = Point::new(1.0, 2.0, -3.0);
+    let mut orbit = OrbitController::new(target, 4.0);
+    let mut camera = Camera::default();
+
+    for delta in [(120.0, 0.0), (0.0, -80.0), (-300.0, 200.0)] {
+        orbit.look(&mut camera, delta);
+        assert!(((camera.position - target).magnitude() - 4.0).abs() < 1e-4);
+        assert_looks_at(&camera, target);
+    }
+}
+
+#[test]
+fn activate_takes_over_current_view() {
+    let mut camera =
+        Camera::with_default_lens(Point::new(3.0, 2.0, 5.0), Point::new(0.0, 0.0, 1.0));
+    let to_camera = camera.position.normalize();
+    camera.yaw = to_camera.z.atan2(to_camera.x);
+    camera.pitch = to_camera.y.asin();
+    camera.update_forward();
+    let before = camera.position;
+
+    let mut orbit = OrbitController::new(Point::new(0.0, 0.0, 0.0), 1.0);
+    orbit.activate(&camera);
+    orbit.zoom(&mut camera, 0.0);
+
+    assert_close(camera.position, before);
+    assert_looks_at(&camera, Point::new(0.0, 0.0, 0.0));
+}
+
+#[test]
+fn frame_centers_bounding_box() {
+    let mut polygon = Polygon::new(0xFFFFFFFF);
+    polygon.add_point(Point::new(10.0, 0.0, 0.0));
+    polygon.add_point(Point::new(12.0, 2.0, 0.0));
+    polygon.add_point(Point::new(10.0, 2.0, 4.0));
+
+    let mut camera = Camera::default();
+    let mut orbit = OrbitController::new(Point::new(0.0, 0.0, 0.0), 1.0);
+    orbit.frame(&mut camera, &[polygon]);
+
+    let center = Point::new(11.0, 1.0, 2.0);
+    assert_close(orbit.target, center);
+    assert_looks_at(&camera, center);
+    // Umkugel mit Radius sqrt(6) passt in das vertikale Sichtfeld von 60°
+    let radius = 6.0f32.sqrt();
+    assert!((orbit.distance - radius / 30f32.to_radians().sin()).abs() < 1e-3);
+}
+
+#[test]
+fn dolly_stops_at_min_distance() {
+    let mut orbit = OrbitController::new(Point::new(0.0, 0.0, 0.0), 2.0);
+    let mut camera = Camera::default();
+    for _ in 0..100 {
+        orbit.zoom(&mut camera, 5.0);
+    }
+    assert_eq!(orbit.distance, orbit.min_distance);
+    assert!(camera.position.magnitude() > 0.0);
+}
+
+#[test]
+fn euler_angles_match_forward_formula() {
+    let mut camera = Camera::default();
+    for (yaw, pitch) in [(0.3, 0.2), (-2.0, -1.2), (2.8, 1.5)] {
+        (camera.yaw, camera.pitch) = (yaw, pitch);
+        camera.update_forward();
+        let expected = Point::new(
+            yaw.cos() * pitch.cos(),
+            pitch.sin(),
+            yaw.sin() * pitch.cos(),
+        );
+        assert_close(camera.forward, expected);
+        // Ohne Rollen bleibt die Hochachse in der senkrechten Ebene durch die Blickrichtung
+        assert!(camera.up.y > 0.0);
+        assert!(camera.up.dot(camera.forward).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn fly_mode_loops_over_the_pole() {
+    let mut camera = Camera::default();
+    let mut fly = FlyController::default();
+    // In kleinen Schritten um mehr als 180° nach oben drehen, dabei nie begrenzt
+    let steps = 200;
+    let pixels = std::f32::consts::PI * 1.2 / camera.look_sensitivity / steps as f32;
+    for _ in 0..steps {
+        fly.look(&mut camera, (0.0, -pixels));
+    }
+    // Nach mehr als einer halben Drehung steht die Kamera auf dem Kopf
+    assert!(camera.up.y < 0.0, "{:?}", camera.up);
+    assert!((camera.up.magnitude() - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn fly_roll_is_dropped_by_fps() {
+    let mut camera = Camera::default();
+    let mut input = ActionState::new();
+    input.press(Action::RollLeft);
+    FlyController::default().update(&mut camera, 0.5, &input);
+    assert!((camera.roll - 0.75).abs() < 1e-4, "{}", camera.roll);
+    assert!(camera.up.x.abs() > 0.1);
+
+    // Eulerwinkel und Drehung bleiben zueinander passend
+    let orientation = camera.orientation;
+    camera.update_forward();
+    assert!(camera.orientation.dot(orientation).abs() > 1.0 - 1e-5);
+
+    let mut fps = FpsController;
+    fps.update(&mut camera, 0.0, &ActionState::new());
+    assert_eq!(camera.roll, 0.0);
+    assert!(camera.up.x.abs() < 1e-5);
+}
+
+#[test]
+fn look_left_turns_without_losing_euler_angles() {
+    let mut camera = Camera::default();
+    camera.set_orientation(Quat::IDENTITY);
+    let yaw = camera.yaw;
+    camera.look_left();
+    assert!((camera.yaw - yaw).abs() > 0.0);
+    let forward = camera.forward;
+    camera.update_forward();
+    assert_close(camera.forward, forward);
+}
@@ -0,0 +1,297 @@
+//! Konsolenvariablen, Konfigurationsdatei und Konsole.
+
+use rake::{
+    Action, ActionState, Bindings, Camera, Console, Cvar, CvarValue, Cvars, Framebuffer, Rgba32F,
+};
+
+fn cvars() -> Cvars {
+    let mut cvars = Cvars::new();
+    cvars.register(Cvar::new("sv_gravity", 80.0, "Gravity").with_range(0.0, 1000.0));
+    cvars.register(Cvar::new("r_ssao", false, "Ambient occlusion"));
+    cvars.register(Cvar::new("r_samples", 4, "Samples").with_range(1.0, 16.0));
+    cvars.register(Cvar::new("name", "player", "Player name"));
+    cvars.register(Cvar::new("session", 1, "Not saved").temporary());
+    cvars
+}
+
+#[test]
+fn values_are_typed_and_clamped() {
+    let mut cvars = cvars();
+    cvars.set("SV_Gravity", "12.5").unwrap();
+    assert_eq!(cvars.get_float("sv_gravity"), Some(12.5));
+    cvars.set("sv_gravity", "5000").unwrap();
+    assert_eq!(cvars.get_float("sv_gravity"), Some(1000.0));
+
+    cvars.set("r_ssao", "on").unwrap();
+    assert_eq!(cvars.get_bool("r_ssao"), Some(true));
+    cvars.set_value("r_samples", 0).unwrap();
+    assert_eq!(cvars.get_int("r_samples"), Some(1));
+    cvars.set_value("sv_gravity", 3).unwrap();
+    assert_eq!(cvars.get_float("sv_gravity"), Some(3.0));
+
+    // Ungültige Werte ändern nichts
+    assert!(cvars.set("r_samples", "viele").is_err());
+    assert!(cvars.set("r_ssao", "2").is_err());
+    assert!(cvars.set_value("r_ssao", 1.5).is_err());
+    assert!(cvars.set("unbekannt", "1").is_err());
+    for text in ["nan", "inf", "-Infinity"] {
+        assert!(cvars.set("sv_gravity", text).is_err(), "{text}");
+    }
+    assert!(cvars.set_value("sv_gravity", f32::NAN).is_err());
+    assert!(cvars.set_value("sv_gravity", f32::INFINITY).is_err());
+    assert_eq!(cvars.get_float("sv_gravity"), Some(3.0));
+    assert_eq!(cvars.get_int("r_samples"), Some(1));
+    assert_eq!(cvars.get_bool("r_ssao"), Some(true));
+
+    cvars.reset("sv_gravity").unwrap();
+    assert_eq!(
+        cvars.get("sv_gravity").unwrap().value,
+        CvarValue::Float(80.0)
+    );
+}
+
+#[test]
+fn ranges_must_not_be_empty() {
+    let mut cvars = Cvars::new();
+    // Ganzzahlige Grenzen werden nach innen gerundet
+    cvars.register(Cvar::new("r_level", 1, "Level").with_range(0.5, 2.5));
+    cvars.set("r_level", "9").unwrap();
+    assert_eq!(cvars.get_int("r_level"), Some(2));
+    cvars.register(Cvar::new("r_scale", 0.5, "Scale").with_range(0.2, 0.8));
+    cvars.set("r_scale", "0").unwrap();
+    assert_eq!(cvars.get_float("r_scale"), Some(0.2));
+}
+
+#[test]
+#[should_panic(expected = "contains no integer")]
+fn int_range_without_integer_is_rejected() {
+    Cvar::new("r_level", 1, "Level").with_range(0.2, 0.8);
+}
+
+#[test]
+#[should_panic(expected = "is empty")]
+fn reversed_range_is_rejected() {
+    Cvar::new("sv_gravity", 80.0, "Gravity").with_range(1000.0, 0.0);
+}
+
+#[test]
+fn register_keeps_existing_value() {
+    let mut cvars = cvars();
+    cvars.set("sv_gravity", "20").unwrap();
+    cvars.register(Cvar::new("sv_gravity", 80.0, "Gravity"));
+    assert_eq!(cvars.get_float("sv_gravity"), Some(20.0));
+    // Anderer Typ ersetzt den alten Wert
+    cvars.register(Cvar::new("sv_gravity", true, "Gravity"));
+    assert_eq!(cvars.get_bool("sv_gravity"), Some(true));
+}
+
+#[test]
+fn execute_console_commands() {
+    let mut cvars = cvars();
+    let info = cvars.execute("sv_gravity").unwrap();
+    assert!(
+        info.contains("\"80\"") && info.contains("Gravity"),
+        "{info}"
+    );
+
+    cvars.execute("sv_gravity 40").unwrap();
+    assert_eq!(cvars.get_float("sv_gravity"), Some(40.0));
+    cvars.execute("set name \"big bad wolf\"").unwrap();
+    assert_eq!(cvars.get_text("name"), Some("big bad wolf"));
+    cvars.execute("toggle r_ssao").unwrap();
+    assert_eq!(cvars.get_bool("r_ssao"), Some(true));
+    cvars.execute("reset sv_gravity").unwrap();
+    assert_eq!(cvars.get_float("sv_gravity"), Some(80.0));
+
+    let list = cvars.execute("cvarlist r_").unwrap();
+    assert_eq!(list, "r_samples \"4\"\nr_ssao \"1\"\n2 cvars");
+    assert!(cvars.execute("toggle name").is_err());
+    assert!(cvars.execute("quit").is_err());
+    assert_eq!(cvars.execute("   ").unwrap(), "");
+}
+
+#[test]
+fn config_round_trip() {
+    let mut cvars = cvars();
+    cvars.execute("sv_gravity 12.25").unwrap();
+    cvars.execute("name \"a b\"").unwrap();
+    cvars.execute("session 7").unwrap();
+    let config = cvars.to_config();
+    assert!(!config.contains("session"), "{config}");
+
+    let mut loaded = self::cvars();
+    assert!(loaded.exec_config(&config).is_empty());
+    assert_eq!(loaded.get_float("sv_gravity"), Some(12.25));
+    assert_eq!(loaded.get_text("name"), Some("a b"));
+    assert_eq!(loaded.get_int("session"), Some(1));
+
+    // `//`, Anführungszeichen und Backslashes im Wert überstehen die Datei
+    let text = r#"http://example.com/"a b" C:\maps\"#;
+    cvars.set("name", text).unwrap();
+    let mut loaded = self::cvars();
+    assert!(loaded.exec_config(&cvars.to_config()).is_empty());
+    assert_eq!(loaded.get_text("name"), Some(text));
+    loaded.exec_config(r#"name "C:\maps // kein Kommentar" // Kommentar"#);
+    assert_eq!(loaded.get_text("name"), Some(r"C:\maps // kein Kommentar"));
+
+    let errors = loaded.exec_config("// Kommentar\nr_ssao 1 // an\nr_samples x\nfoo 1\n");
+    assert_eq!(errors.len(), 2, "{errors:?}");
+    assert!(errors[0].starts_with("line 3:") && errors[1].starts_with("line 4:"));
+    assert_eq!(loaded.get_bool("r_ssao"), Some(true));
+}
+
+#[test]
+fn movement_reads_cvars() {
+    let mut cvars = Cvars::new();
+    Camera::register_cvars(&mut cvars);
+
+    // Standardwerte entsprechen einer neuen Kamera
+    let mut camera = Camera::default();
+    let before = camera.movement;
+    camera.apply_cvars(&cvars);
+    assert_eq!(camera.movement, before);
+    assert_eq!(camera.gravity, 80.0);
+
+    // Sprunghöhe v² / 2g
+    cvars.execute("sv_jumpspeed 40").unwrap();
+    cvars.execute("sv_gravity 100").unwrap();
+    camera.apply_cvars(&cvars);
+    let mut input = ActionState::new();
+    input.press(Action::Jump);
+    let mut highest: f32 = 0.0;
+    for _ in 0..600 {
+        camera.update_movement(1.0 / 600.0, &input, (0.0, 0.0));
+        input.release(Action::Jump);
+        highest = highest.max(camera.position.y);
+    }
+    assert!((highest - 8.0).abs() < 0.1, "{highest}");
+
+    // `cl_acceleration` skaliert die Beschleunigung am Boden, 0 heißt Stillstand
+    cvars.execute("cl_acceleration 0").unwrap();
+    camera.apply_cvars(&cvars);
+    input.press(Action::MoveForward);
+    for _ in 0..60 {
+        camera.update_movement(1.0 / 60.0, &input, (0.0, 0.0));
+    }
+    assert_eq!(camera.velocity.magnitude(), 0.0);
+}
+
+#[test]
+fn movement_cvars_stay_stable_at_their_limits() {
+    let mut cvars = Cvars::new();
+    Camera::register_cvars(&mut cvars);
+    for line in [
+        "sv_friction 2",
+        "sv_airdrag 1000",
+        "sv_accelerate 1e6",
+        "cl_acceleration 100",
+        "sv_strafeboost 100",
+        "sv_diagonalscale 100",
+    ] {
+        cvars.execute(line).unwrap();
+    }
+    assert_eq!(cvars.get_float("sv_friction"), Some(1.0));
+    assert_eq!(cvars.get_float("sv_airdrag"), Some(60.0));
+
+    // Laufen, Springen und Seitwärtslaufen über zehn Sekunden bleibt endlich und begrenzt
+    let mut camera = Camera::default();
+    camera.apply_cvars(&cvars);
+    let max_speed = camera.movement.max_speed * camera.movement.diagonal_speed_scale;
+    for tick in 0..600 {
+        let mut input = ActionState::new();
+        input.press(Action::MoveForward);
+        input.press(Action::MoveLeft);
+        if tick % 90 == 0 {
+            input.press(Action::Jump);
+        }
+        camera.update_movement(1.0 / 60.0, &input, (0.0, 0.0));
+        assert!(camera.position.magnitude().is_finite(), "{tick}");
+        assert!(camera.velocity.magnitude() < max_speed * 2.0, "{tick}");
+    }
+}
+
+#[test]
+fn console_runs_commands_and_keeps_history() {
+    let mut cvars = cvars();
+    let mut bindings = Bindings::default();
+    let mut console = Console::new();
+    for c in "sv_gravity 5".chars() {
+        console.type_char(c);
+    }
+    console.submit(&mut cvars, &mut bindings);
+    assert_eq!(cvars.get_float("sv_gravity"), Some(5.0));
+    assert_eq!(console.log, vec!["] sv_gravity 5"]);
+
+    console.input = "foo".to_string();
+    console.submit(&mut cvars, &mut bindings);
+    assert_eq!(console.log.last().unwrap(), "Unknown command or cvar: foo");
+
+    console.history_previous();
+    assert_eq!(console.input, "foo");
+    console.history_previous();
+    assert_eq!(console.input, "sv_gravity 5");
+    console.history_next();
+    console.history_next();
+    assert_eq!(console.input, "");
+
+    console.input = "r_s".to_string();
+    console.complete(&cvars);
+    assert_eq!(console.input, "r_s");
+    assert_eq!(console.log.last().unwrap(), "r_samples  r_ssao");
+    console.input = "r_ss".to_string();
+    console.complete(&cvars);
+    assert_eq!(console.input, "r_ssao ");
+
+    // Gemeinsamer Anfang mit Umlauten
+    cvars.register(Cvar::new("größe_x", 1, "Breite"));
+    cvars.register(Cvar::new("größe_y", 1, "Höhe"));
+    console.input = "g".to_string();
+    console.complete(&cvars);
+    assert_eq!(console.input, "größe_");
+
+    console.input = "clear".to_string();
+    console.submit(&mut cvars, &mut bindings);
+    assert!(console.log.is_empty());
+}
+
+#[test]
+fn console_writes_and_executes_config() {
+    let path = std::env::temp_dir().join(format!("rake_cvar_{}.cfg", std::process::id()));
+    let mut cvars = cvars();
+    let mut bindings = Bindings::default();
+    let mut console = Console::new().with_config(&path);
+
+    cvars.execute("sv_gravity 33").unwrap();
+    console.input = "writeconfig".to_string();
+    console.submit(&mut cvars, &mut bindings);
+    cvars.execute("sv_gravity 1").unwrap();
+    console.input = "exec".to_string();
+    console.submit(&mut cvars, &mut bindings);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(cvars.get_float("sv_gravity"), Some(33.0));
+}
+
+#[test]
+fn console_overlay_darkens_top_half() {
+    let mut framebuffer = Framebuffer::<Rgba32F>::with_format(200, 100);
+    framebuffer.pixels.fill(Rgba32F::WHITE);
+    let mut console = Console::new();
+    console.draw(&mut framebuffer);
+    assert!(framebuffer.pixels.iter().all(|p| *p == Rgba32F::WHITE));
+
+    console.toggle();
+    console.print("hello");
+    console.draw(&mut framebuffer);
+    let lit = |y: usize| {
+        framebuffer.pixels[y * 200..(y + 1) * 200]
+            .iter()
+            .filter(|p| p.r > 0.5)
+            .count()
+    };
+    // Unter der Konsole bleibt das Bild unverändert
+    assert_eq!(lit(75), 200);
+    // Hintergrund abgedunkelt, nur Text bleibt hell
+    assert_eq!(lit(2), 0);
+    assert!((0..50).map(lit).sum::<usize>() > 50);
+}
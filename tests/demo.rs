@@ -0,0 +1,287 @@
+//! Aufnahme und Wiedergabe von Eingaben.
+
+use rake::demo::{run_command, run_tick};
+use rake::{
+    Action, ActionState, Camera, CameraController, Cvars, Demo, DemoTick, FlyController,
+    FpsController, OrbitController, Point, Polygon, Projection, StandardView,
+};
+
+const TIMESTEP: f32 = 1.0 / 60.0;
+
+fn camera() -> Camera {
+    Camera::with_default_lens(Point::new(0.0, 0.0, -5.0), Point::new(0.0, 0.0, -1.0))
+}
+
+///Alle Steuerungen wie im Fenster, die Orbit-Kamera mit anderem Ziel als bei der Aufnahme
+fn controllers() -> Vec<Box<dyn CameraController>> {
+    vec![
+        Box::new(FpsController),
+        Box::new(OrbitController::new(Point::new(0.0, 0.0, 0.0), 1.0)),
+        Box::new(FlyController::default()),
+    ]
+}
+
+fn held(actions: &[Action]) -> ActionState {
+    let mut input = ActionState::new();
+    actions.iter().for_each(|action| input.press(*action));
+    input
+}
+
+///Läuft vorwärts, springt, dreht sich und läuft seitwärts weiter
+fn script(tick: usize) -> DemoTick {
+    let input = match tick {
+        0..30 => held(&[Action::MoveForward]),
+        30 => held(&[Action::MoveForward, Action::Jump]),
+        31..90 => held(&[Action::MoveLeft]),
+        _ => ActionState::new(),
+    };
+    DemoTick {
+        input,
+        look: if (40..60).contains(&tick) {
+            (3.0, -1.5)
+        } else {
+            (0.0, 0.0)
+        },
+        paused: (100..105).contains(&tick),
+        ..DemoTick::default()
+    }
+}
+
+///Nimmt `ticks` Schritte des Skripts live auf
+fn record(camera: &mut Camera, controller: &mut dyn CameraController, ticks: usize) -> Demo {
+    let mut demo = Demo::new(TIMESTEP, camera, controller, &Cvars::new());
+    for tick in (0..ticks).map(script) {
+        run_tick(camera, controller, &tick, TIMESTEP);
+        demo.record(tick, camera);
+    }
+    demo
+}
+
+#[test]
+fn playback_reproduces_recording() {
+    let mut camera = camera();
+    let mut fps = FpsController;
+    let demo = record(&mut camera, &mut fps, 120);
+    let end = camera.position;
+    assert!(
+        (end - Point::new(0.0, 0.0, -5.0)).magnitude() > 1.0,
+        "{end:?}"
+    );
+    assert!((demo.duration() - 2.0).abs() < 1e-5);
+
+    // Die Wiedergabe beginnt beim aufgenommenen Zustand, egal wo die Kamera gerade ist
+    let mut replay = self::camera();
+    replay.position = Point::new(10.0, 3.0, 10.0);
+    replay.look_around(200.0, 50.0);
+    let mut visited = Vec::new();
+    let desync = demo.play(&mut replay, &mut controllers(), &[], |index, camera| {
+        visited.push((index, camera.position))
+    });
+    assert_eq!(desync, Ok(None));
+    assert_eq!(replay.position, end);
+    assert_eq!(visited.len(), 120);
+    assert_eq!(visited[119], (119, end));
+}
+
+#[test]
+fn playback_reports_first_divergence() {
+    let mut camera = camera();
+    let demo = record(&mut camera, &mut FpsController, 60);
+
+    // Andere Schwerkraft ändert erst den Sprung in Schritt 30
+    let mut replay = self::camera();
+    replay.gravity *= 2.0;
+    let desync = demo.play(&mut replay, &mut controllers(), &[], |_, _| {});
+    assert_eq!(desync, Ok(Some(31)));
+}
+
+#[test]
+fn bytes_round_trip() {
+    let mut camera = camera();
+    camera.projection = Projection::Orthographic { height: 4.5 };
+    let mut cvars = Cvars::new();
+    Camera::register_cvars(&mut cvars);
+    cvars.execute("sv_gravity 50").unwrap();
+
+    let mut fly = FlyController::default();
+    let mut demo = Demo::new(TIMESTEP, &camera, &fly, &cvars);
+    for mut tick in (0..40).map(script) {
+        if tick.paused || tick.look != (0.0, 0.0) {
+            tick.zoom = 0.5;
+        }
+        if tick.look == (0.0, 0.0) && tick.input.is_down(Action::MoveLeft) {
+            tick.commands = vec![Action::View(StandardView::Top), Action::FrameScene];
+        }
+        run_tick(&mut camera, &mut fly, &tick, TIMESTEP);
+        demo.record(tick, &camera);
+    }
+
+    let bytes = demo.to_bytes();
+    let loaded = Demo::from_bytes(&bytes).unwrap();
+    assert_eq!(loaded, demo);
+    assert_eq!(loaded.controller, "Fly");
+    assert!(loaded.cvars.contains("set sv_gravity \"50\""));
+    // Ohne Mausbewegung und Befehle nur Flags, Aktionen und Position
+    assert!(bytes.len() < 200 + 40 * 40, "{}", bytes.len());
+
+    assert!(Demo::from_bytes(b"RIFF0000").is_err());
+    assert!(Demo::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    let mut version = bytes.clone();
+    version[4] = 99;
+    assert!(Demo::from_bytes(&version).unwrap_err().contains("version"));
+
+    // Mehr als 255 Befehle in einem Schritt
+    let mut many = demo.clone();
+    many.ticks[0].commands = vec![Action::FrameScene; 300];
+    assert_eq!(Demo::from_bytes(&many.to_bytes()).unwrap(), many);
+}
+
+#[test]
+fn fly_and_orbit_play_back_exactly() {
+    let mut orbit = OrbitController::new(Point::new(0.0, 0.0, 0.0), 5.0);
+    let mut camera = camera();
+    orbit.activate(&camera);
+    let mut demo = Demo::new(TIMESTEP, &camera, &orbit, &Cvars::new());
+    for index in 0..60 {
+        let mut tick = script(index);
+        tick.zoom = if index % 7 == 0 { 1.0 } else { 0.0 };
+        if index == 10 {
+            tick.commands.push(Action::View(StandardView::Isometric));
+            orbit.standard_view(&mut camera, StandardView::Isometric);
+        }
+        run_tick(&mut camera, &mut orbit, &tick, TIMESTEP);
+        demo.record(tick, &camera);
+    }
+    let mut replay = self::camera();
+    assert_eq!(
+        demo.play(&mut replay, &mut controllers(), &[], |_, _| {}),
+        Ok(None)
+    );
+    assert_eq!(replay.orientation, camera.orientation);
+
+    let mut camera = self::camera();
+    let demo = record(&mut camera, &mut FlyController::default(), 120);
+    let mut replay = self::camera();
+    assert_eq!(
+        demo.play(&mut replay, &mut controllers(), &[], |_, _| {}),
+        Ok(None)
+    );
+    assert_eq!(replay.orientation, camera.orientation);
+}
+
+#[test]
+fn orbit_target_is_restored() {
+    // Ziel abseits des Ursprungs, `activate` allein rechnet vom Ziel der Steuerung aus
+    let target = Point::new(2.0, 1.0, -3.0);
+    let mut orbit = OrbitController::new(target, 5.0);
+    let mut camera = camera();
+    orbit.activate(&camera);
+    let mut demo = Demo::new(TIMESTEP, &camera, &orbit, &Cvars::new());
+    for index in 0..60 {
+        let mut tick = script(index);
+        if index % 5 == 0 {
+            tick.input.press(Action::PanRight);
+        }
+        run_tick(&mut camera, &mut orbit, &tick, TIMESTEP);
+        demo.record(tick, &camera);
+    }
+    let demo = Demo::from_bytes(&demo.to_bytes()).unwrap();
+    assert_eq!(demo.controller_state[..3], [2.0, 1.0, -3.0]);
+
+    let mut replay = self::camera();
+    let mut controllers = controllers();
+    assert_eq!(
+        demo.play(&mut replay, &mut controllers, &[], |_, _| {}),
+        Ok(None)
+    );
+    assert_eq!(replay.position, camera.position);
+    let replayed = &controllers[1].state()[..3];
+    assert_eq!(replayed, [orbit.target.x, orbit.target.y, orbit.target.z]);
+    assert!((orbit.target - target).magnitude() > 0.1);
+}
+
+#[test]
+fn playback_applies_recorded_cvars() {
+    let mut cvars = Cvars::new();
+    Camera::register_cvars(&mut cvars);
+    cvars.execute("sv_gravity 40").unwrap();
+    let mut camera = camera();
+    camera.apply_cvars(&cvars);
+    assert_ne!(self::camera().gravity, 40.0);
+    let demo = {
+        let mut fps = FpsController;
+        let mut demo = Demo::new(TIMESTEP, &camera, &fps, &cvars);
+        for tick in (0..60).map(script) {
+            run_tick(&mut camera, &mut fps, &tick, TIMESTEP);
+            demo.record(tick, &camera);
+        }
+        demo
+    };
+
+    // Der Sprung in Schritt 30 hängt von der Schwerkraft ab
+    let mut replay = self::camera();
+    assert_eq!(
+        demo.play(&mut replay, &mut controllers(), &[], |_, _| {}),
+        Ok(None)
+    );
+    assert_eq!(replay.gravity, 40.0);
+    assert_eq!(replay.position, camera.position);
+}
+
+#[test]
+fn playback_runs_recorded_commands() {
+    let mut quad = Polygon::new(0xFFFFFFFF);
+    for (x, y) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+        quad.add_point(Point::new(x + 4.0, y, 2.0));
+    }
+    let scene = [quad];
+
+    // Mit der Orbit-Kamera einrahmen, Projektion wechseln, dann weiter zur Fly-Kamera
+    let commands = |index: usize| match index {
+        5 => vec![Action::CycleController, Action::FrameScene],
+        20 => vec![Action::ToggleProjection, Action::View(StandardView::Right)],
+        40 => vec![Action::CycleController],
+        _ => Vec::new(),
+    };
+    let mut camera = camera();
+    let mut live = controllers();
+    let mut active = 0;
+    let mut demo = Demo::new(TIMESTEP, &camera, live[active].as_ref(), &Cvars::new());
+    for index in 0..60 {
+        let mut tick = script(index);
+        tick.commands = commands(index);
+        for command in &tick.commands {
+            run_command(*command, &mut camera, &mut live, &mut active, &scene);
+        }
+        run_tick(&mut camera, live[active].as_mut(), &tick, TIMESTEP);
+        demo.record(tick, &camera);
+    }
+    assert_eq!(live[active].name(), "Fly");
+    assert!(matches!(camera.projection, Projection::Orthographic { .. }));
+
+    let demo = Demo::from_bytes(&demo.to_bytes()).unwrap();
+    let mut replay = self::camera();
+    let mut controllers = controllers();
+    assert_eq!(
+        demo.play(&mut replay, &mut controllers, &scene, |_, _| {}),
+        Ok(None)
+    );
+    assert_eq!(replay.position, camera.position);
+    assert_eq!(replay.orientation, camera.orientation);
+    assert_eq!(replay.projection, camera.projection);
+    assert_eq!(controllers[1].state(), live[1].state());
+
+    // Ohne die Szene rahmt `FrameScene` nichts ein und die Wiedergabe weicht ab
+    let mut replay = self::camera();
+    let desync = demo.play(&mut replay, &mut self::controllers(), &[], |_, _| {});
+    assert!(matches!(desync, Ok(Some(_))), "{desync:?}");
+}
+
+#[test]
+fn playback_needs_the_recorded_controller() {
+    let mut camera = camera();
+    let demo = record(&mut camera, &mut FlyController::default(), 10);
+    let mut only_fps: Vec<Box<dyn CameraController>> = vec![Box::new(FpsController)];
+    let result = demo.play(&mut self::camera(), &mut only_fps, &[], |_, _| {});
+    assert!(result.unwrap_err().contains("Fly"));
+}
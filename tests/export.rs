@@ -0,0 +1,132 @@
+//! Export von Bildfolgen und Videos.
+
+use image::{Rgba, RgbaImage};
+use rake::export::{AviWriter, PngSequence, Y4mWriter};
+use rake::{ExportFormat, FrameSink};
+use std::io::Cursor;
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+///Links oben rot, sonst weiß
+fn frame(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+        if (x, y) == (0, 0) {
+            Rgba([255, 0, 0, 255])
+        } else {
+            Rgba([255, 255, 255, 255])
+        }
+    })
+}
+
+#[test]
+fn format_from_name_and_path() {
+    assert_eq!(ExportFormat::from_name("AVI"), Some(ExportFormat::Avi));
+    assert_eq!(ExportFormat::from_name("gif"), None);
+    assert_eq!(
+        ExportFormat::from_path("out/preview.y4m"),
+        ExportFormat::Y4m
+    );
+    assert_eq!(ExportFormat::from_path("preview.MP4"), ExportFormat::Ffmpeg);
+    assert_eq!(ExportFormat::from_path("frames"), ExportFormat::Png);
+}
+
+#[test]
+fn y4m_header_and_planes() {
+    let mut writer = Y4mWriter::new(Vec::new(), 3, 2, 29.97).unwrap();
+    writer.write_frame(&frame(3, 2)).unwrap();
+    writer.write_frame(&frame(3, 2)).unwrap();
+    assert!(
+        writer
+            .write_frame(&frame(2, 2))
+            .unwrap_err()
+            .contains("3x2")
+    );
+    writer.finish().unwrap();
+    let bytes = writer.into_inner();
+
+    let header = "YUV4MPEG2 W3 H2 F2997:100 Ip A1:1 C444\n";
+    assert!(bytes.starts_with(header.as_bytes()));
+    let frame_size = b"FRAME\n".len() + 3 * 2 * 3;
+    assert_eq!(bytes.len(), header.len() + 2 * frame_size);
+
+    let planes = &bytes[header.len() + 6..header.len() + frame_size];
+    // Y, U und V je 6 Werte: Rot ist dunkler mit hohem V, Weiß ist 235/128/128
+    assert_eq!(planes[..6], [81, 235, 235, 235, 235, 235]);
+    assert_eq!(planes[6..12], [90, 128, 128, 128, 128, 128]);
+    assert_eq!(planes[12..], [240, 128, 128, 128, 128, 128]);
+}
+
+#[test]
+fn avi_structure_and_index() {
+    let mut writer = AviWriter::new(Cursor::new(Vec::new()), 3, 2, 25.0).unwrap();
+    for _ in 0..4 {
+        writer.write_frame(&frame(3, 2)).unwrap();
+    }
+    writer.finish().unwrap();
+    let bytes = writer.into_inner().into_inner();
+
+    assert_eq!(&bytes[..4], b"RIFF");
+    assert_eq!(u32_at(&bytes, 4) as usize, bytes.len() - 8);
+    assert_eq!(&bytes[8..12], b"AVI ");
+    assert_eq!(u32_at(&bytes, 32), 40_000); // Mikrosekunden pro Bild
+    assert_eq!(u32_at(&bytes, 48), 4); // Bilder in `avih`
+    assert_eq!(&bytes[108..112], b"vids");
+    assert_eq!((u32_at(&bytes, 128), u32_at(&bytes, 132)), (1, 25));
+    assert_eq!(u32_at(&bytes, 140), 4); // Bilder in `strh`
+
+    // Zeilen mit 9 Byte werden auf 12 aufgefüllt
+    let frame_size = 12 * 2;
+    assert_eq!(&bytes[220..224], b"movi");
+    assert_eq!(u32_at(&bytes, 216) as usize, 4 + 4 * (8 + frame_size));
+    assert_eq!(&bytes[224..228], b"00db");
+    assert_eq!(u32_at(&bytes, 228) as usize, frame_size);
+    // Die unterste Zeile kommt zuerst, Rot liegt als BGR am Anfang der zweiten
+    let pixels = &bytes[232..232 + frame_size];
+    assert_eq!(pixels[..3], [255, 255, 255]);
+    assert_eq!(pixels[12..15], [0, 0, 255]);
+
+    let index = 224 + 4 * (8 + frame_size);
+    assert_eq!(&bytes[index..index + 4], b"idx1");
+    assert_eq!(u32_at(&bytes, index + 4), 4 * 16);
+    assert_eq!(bytes.len(), index + 8 + 4 * 16);
+    for frame in 0..4 {
+        let entry = index + 8 + frame * 16;
+        assert_eq!(&bytes[entry..entry + 4], b"00db");
+        let offset = u32_at(&bytes, entry + 8) as usize;
+        assert_eq!(offset, 4 + frame * (8 + frame_size));
+        assert_eq!(&bytes[220 + offset..224 + offset], b"00db");
+    }
+}
+
+#[test]
+fn png_sequence_numbers_frames() {
+    let directory = std::env::temp_dir().join(format!("rake_export_{}", std::process::id()));
+    let mut sink = ExportFormat::Png.create(&directory, 3, 2, 30.0).unwrap();
+    for _ in 0..3 {
+        sink.write_frame(&frame(3, 2)).unwrap();
+    }
+    sink.finish().unwrap();
+
+    let sequence = PngSequence::new(&directory).unwrap();
+    let loaded = image::open(sequence.frame_path(2)).unwrap().to_rgba8();
+    assert_eq!(loaded, frame(3, 2));
+    assert!(!sequence.frame_path(3).exists());
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    // Ungültige Bildrate oder Größe, bevor eine Datei entsteht
+    for (width, height, fps) in [
+        (3, 2, 0.0),
+        (3, 2, 1e12),
+        (3, 2, 1e-5),
+        (0, 2, 30.0),
+        (3, 0, 30.0),
+    ] {
+        let error = ExportFormat::Y4m.create("unused.y4m", width, height, fps);
+        assert!(error.is_err(), "{width}x{height} {fps}");
+    }
+    assert!(!std::path::Path::new("unused.y4m").exists());
+    assert!(Y4mWriter::new(Vec::new(), 0, 2, 30.0).is_err());
+    assert!(AviWriter::new(Cursor::new(Vec::new()), 3, 0, 30.0).is_err());
+}
@@ -0,0 +1,911 @@
+//! Golden-Image-Tests für den Rasterizer.
+//!
+//! Jede Szene wird ohne Fenster gerendert und mit einem eingecheckten Referenzbild aus
+//! `tests/golden/` verglichen. Bei Abweichungen landen das tatsächliche Bild und ein
+//! Differenzbild in `target/golden-diff/`.
+//!
+//! Referenzbilder neu erzeugen: `RAKE_BLESS=1 cargo test --test golden`
+
+use image::{Rgba, RgbaImage};
+use rake::post::{Bloom, ChromaticAberration, ColorGradingLut, DepthFog, Sharpen, Vignette};
+use rake::render::render_scene;
+use rake::ssao::Ssao;
+use rake::{
+    Background, Bindings, BlendMode, Camera, CameraController, Color, ColorSpace, Console, Cubemap,
+    Cvars, DirectionalLight, Environment, Framebuffer, HdrTexture, Material, Msaa, OrbitController,
+    PbrMaterial, Point, Polygon, PostEffect, Projection, RenderMode, Rgba32F, StandardView,
+    Texture, ToneMapping, TransparencyMode, object,
+};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 180;
+
+/// Schwellwert für die Farbdifferenz eines Pixels (0 = exakt, 1 = beliebig)
+const PIXEL_THRESHOLD: f32 = 0.1;
+/// Maximaler Anteil abweichender Pixel, bevor der Test fehlschlägt
+const MAX_DIFF_RATIO: f32 = 0.001;
+
+#[test]
+fn single_triangle() {
+    let mut triangle = Polygon::new(0xFFFF8000);
+    triangle.add_point(Point::new(-1.0, -1.0, 0.0));
+    triangle.add_point(Point::new(0.0, 1.0, 0.0));
+    triangle.add_point(Point::new(1.0, -1.0, 0.0));
+
+    let camera = look_at(Point::new(0.0, 0.0, -3.0), Point::new(0.0, 0.0, 0.0));
+    assert_golden("single_triangle", &render(&[triangle], &camera));
+}
+
+#[test]
+fn textured_quad() {
+    let mut quad = Polygon::new(0xFFFFFFFF);
+    quad.add_point(Point::new(-1.0, -1.0, 0.0));
+    quad.add_point(Point::new(-1.0, 1.0, 0.0));
+    quad.add_point(Point::new(1.0, 1.0, 0.0));
+    quad.add_point(Point::new(1.0, -1.0, 0.0));
+    quad.set_tex_coords(vec![(0.0, 1.0), (0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]);
+    quad.set_texture(Arc::new(checkerboard(64, 8)));
+
+    let camera = look_at(Point::new(0.5, 0.3, -3.0), Point::new(0.0, 0.0, 0.0));
+    assert_golden("textured_quad", &render(&[quad], &camera));
+}
+
+#[test]
+fn obj_faces() {
+    // Texturiertes Dreieck, Dreieck ohne Texturkoordinaten und Sechseck ohne
+    // Texturkoordinaten (wird per Ear Clipping zerlegt)
+    let mut obj = String::from("v -3.0 0 1\nv -2.0 0 -1\nv -1.0 0 1\n");
+    obj += "v -0.6 0 1\nv 0.4 0 -1\nv 1.4 0 1\n";
+    for k in 0..6 {
+        let angle = (90.0 - k as f32 * 60.0).to_radians();
+        obj += &format!("v {} 0 {}\n", 2.8 + angle.cos(), -angle.sin());
+    }
+    obj += "vt 0 1\nvt 0.5 0\nvt 1 1\n";
+    obj += "f 1/1 2/2 3/3\nf 4 5 6\nf 7 8 9 10 11 12\n";
+
+    let path = std::env::temp_dir().join(format!("rake_obj_faces_{}.obj", std::process::id()));
+    std::fs::write(&path, obj).unwrap();
+    let (vertices, faces, tex) = object::parse_obj_file(path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(faces.len(), 3);
+    assert!(faces[1].1.is_empty() && faces[2].1.is_empty());
+
+    let mut polygons = object::process_faces(&vertices, &faces, &tex);
+    polygons[0].set_texture(Arc::new(checkerboard(64, 4)));
+    polygons[1].set_color(0xFF40C040);
+    polygons[2].set_color(0xFFC04040);
+
+    let camera = look_at(Point::new(0.0, 0.0, -4.5), Point::new(0.0, 0.0, 0.0));
+    assert_golden("obj_faces", &render(&polygons, &camera));
+}
+
+#[test]
+fn capsule_poses() {
+    let polygons = load_capsule();
+    let poses = [
+        ("capsule_front", Point::new(0.0, 0.0, -5.0)),
+        ("capsule_side", Point::new(5.0, 0.0, 0.0)),
+        ("capsule_above", Point::new(2.0, 3.0, -3.0)),
+    ];
+
+    for (name, position) in poses {
+        let camera = look_at(position, Point::new(0.0, 0.0, 0.0));
+        assert_golden(name, &render(&polygons, &camera));
+    }
+}
+
+#[test]
+fn capsule_render_modes() {
+    let polygons = load_capsule();
+    let camera = look_at(Point::new(2.0, 1.5, -4.0), Point::new(0.0, 0.0, 0.0));
+    let modes = [
+        ("capsule_wireframe", RenderMode::Wireframe, false),
+        (
+            "capsule_wireframe_over_shaded",
+            RenderMode::WireframeOverShaded,
+            true,
+        ),
+        ("capsule_hidden_line", RenderMode::HiddenLine, false),
+        ("capsule_hidden_line_aa", RenderMode::HiddenLine, true),
+    ];
+
+    for (name, mode, antialiased) in modes {
+        let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+        framebuffer.render_mode = mode;
+        framebuffer.antialiased_lines = antialiased;
+        render_scene(&polygons, &camera, &mut framebuffer);
+        assert_golden(name, &framebuffer);
+    }
+}
+
+#[test]
+fn antialiasing() {
+    let mut triangle = Polygon::new(0xFFFF8000);
+    triangle.add_point(Point::new(-1.0, -1.0, 0.0));
+    triangle.add_point(Point::new(0.2, 1.0, 0.0));
+    triangle.add_point(Point::new(1.0, -0.7, 0.0));
+    let triangle = [triangle];
+    let capsule = load_capsule();
+    let camera = look_at(Point::new(0.0, 0.0, -3.0), Point::new(0.0, 0.0, 0.0));
+
+    let settings = [
+        ("triangle_msaa2", &triangle[..], Msaa::X2, false),
+        ("triangle_msaa4", &triangle[..], Msaa::X4, false),
+        ("triangle_msaa8", &triangle[..], Msaa::X8, false),
+        ("triangle_fxaa", &triangle[..], Msaa::Off, true),
+        ("capsule_msaa4", &capsule[..], Msaa::X4, false),
+        ("capsule_fxaa", &capsule[..], Msaa::Off, true),
+    ];
+
+    for (name, polygons, msaa, fxaa) in settings {
+        let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+        framebuffer.msaa = msaa;
+        framebuffer.fxaa = fxaa;
+        render_scene(polygons, &camera, &mut framebuffer);
+        assert_golden(name, &framebuffer);
+    }
+}
+
+#[test]
+fn transparency() {
+    let checker = Arc::new(checkerboard(64, 8));
+
+    // Opaker Hintergrund
+    let mut back = quad(2.0, 0.0, 0.0, 1.0);
+    back.set_texture(checker.clone());
+
+    // Ausgestanzte Textur: die blauen Felder sind durchsichtig
+    let mut cutout_texture = checkerboard(64, 4);
+    for texel in cutout_texture.data.chunks_mut(4) {
+        if texel[2] == 200 {
+            texel[3] = 0;
+        }
+    }
+    let mut cutout = quad(0.6, -0.9, 0.4, 0.5);
+    cutout.set_texture(Arc::new(cutout_texture));
+    cutout.set_material(Material::default().with_alpha_cutoff(0.5));
+
+    // Überlappende transparente Flächen, absichtlich von vorne nach hinten übergeben
+    let mut front = quad(0.7, 0.3, -0.2, -0.5);
+    front.set_color(0x80FF2020);
+    front.set_material(Material::new(BlendMode::Alpha));
+    let mut middle = quad(0.7, 0.7, 0.2, 0.0);
+    middle.set_color(0x8020FF20);
+    middle.set_material(Material::new(BlendMode::Alpha));
+    let mut additive = quad(0.5, -0.6, -0.6, 0.2);
+    additive.set_color(0xFF303090);
+    additive.set_material(Material::new(BlendMode::Additive));
+    let mut multiply = quad(0.5, 1.1, -0.5, 0.3);
+    multiply.set_color(0xFFFFFF40);
+    multiply.set_material(Material::new(BlendMode::Multiply));
+
+    let polygons = [front, middle, additive, multiply, back, cutout];
+    let camera = look_at(Point::new(0.0, 0.0, -3.0), Point::new(0.0, 0.0, 0.0));
+    assert_golden("transparency", &render(&polygons, &camera));
+
+    // Im HDR-Format darf additives Blending über 1.0 gehen, beim Speichern wird wie bei 8 Bit begrenzt
+    let mut hdr = Framebuffer::<Rgba32F>::with_format(WIDTH, HEIGHT);
+    render_scene(&polygons, &camera, &mut hdr);
+    assert!(hdr.pixels.iter().any(|pixel| pixel.b > 1.0));
+    assert_golden("transparency", &hdr);
+}
+
+#[test]
+fn order_independent_transparency() {
+    // Zwei sich durchdringende, schräge Flächen: keine Polygonsortierung ist hier korrekt
+    let mut red = Polygon::new(0x99FF3030);
+    red.add_point(Point::new(-1.0, -0.6, -0.6));
+    red.add_point(Point::new(-1.0, 0.6, -0.6));
+    red.add_point(Point::new(1.0, 0.6, 0.6));
+    red.add_point(Point::new(1.0, -0.6, 0.6));
+    red.set_material(Material::new(BlendMode::Alpha));
+
+    let mut blue = Polygon::new(0x993060FF);
+    blue.add_point(Point::new(-1.0, -0.4, 0.6));
+    blue.add_point(Point::new(-1.0, 0.8, 0.6));
+    blue.add_point(Point::new(1.0, 0.8, -0.6));
+    blue.add_point(Point::new(1.0, -0.4, -0.6));
+    blue.set_material(Material::new(BlendMode::Alpha));
+
+    let mut back = quad(2.4, 0.0, 0.0, 1.2);
+    back.set_texture(Arc::new(checkerboard(64, 8)));
+
+    let polygons = [red, blue, back];
+    let camera = look_at(Point::new(0.0, 0.0, -3.0), Point::new(0.0, 0.0, 0.0));
+    let modes = [
+        ("oit_sorted", TransparencyMode::Sorted, Msaa::Off),
+        (
+            "oit_fragment_lists",
+            TransparencyMode::FragmentLists,
+            Msaa::Off,
+        ),
+        (
+            "oit_fragment_lists_msaa4",
+            TransparencyMode::FragmentLists,
+            Msaa::X4,
+        ),
+        (
+            "oit_weighted_blended",
+            TransparencyMode::WeightedBlended,
+            Msaa::Off,
+        ),
+    ];
+
+    for (name, transparency, msaa) in modes {
+        let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+        framebuffer.transparency = transparency;
+        framebuffer.msaa = msaa;
+        render_scene(&polygons, &camera, &mut framebuffer);
+        assert_golden(name, &framebuffer);
+    }
+}
+
+#[test]
+fn tone_mapping() {
+    let mut back = quad(4.0, 0.0, 0.0, 1.0);
+    back.set_texture(Arc::new(checkerboard(64, 8)));
+
+    // Übereinanderliegende additive Flächen erzeugen Werte weit über 1.0
+    let mut polygons = vec![back];
+    for (i, color) in [0xFFA06030u32, 0xFF3070A0, 0xFF909090]
+        .into_iter()
+        .enumerate()
+    {
+        let offset = i as f32 * 0.35;
+        let mut light = quad(1.0, -0.35 + offset, 0.2 - offset * 0.5, 0.5 - offset * 0.2);
+        light.set_color(color);
+        light.set_material(Material::new(BlendMode::Additive));
+        polygons.push(light);
+    }
+
+    let camera = look_at(Point::new(0.0, 0.0, -3.0), Point::new(0.0, 0.0, 0.0));
+    let operators = [
+        ("tone_mapping_reinhard", ToneMapping::Reinhard),
+        ("tone_mapping_aces", ToneMapping::AcesFilmic),
+        ("tone_mapping_uncharted2", ToneMapping::Uncharted2),
+    ];
+
+    for (name, tone_mapping) in operators {
+        let mut framebuffer = Framebuffer::<Rgba32F>::with_format(WIDTH, HEIGHT);
+        framebuffer.tone_mapping = tone_mapping;
+        render_scene(&polygons, &camera, &mut framebuffer);
+        assert!(framebuffer.pixels.iter().all(|pixel| pixel.r <= 1.0));
+        assert_golden(name, &framebuffer);
+    }
+}
+
+#[test]
+fn post_effects() {
+    let polygons = load_capsule();
+    let camera = look_at(Point::new(2.0, 3.0, -3.0), Point::new(0.0, 0.0, 0.0));
+
+    // Warme Farbkorrektur mit angehobenen Schatten
+    let mut grading = ColorGradingLut::from_fn(17, |c| {
+        Rgba32F::new((c.r * 1.1 + 0.05).min(1.0), c.g, c.b * 0.8 + 0.05, 1.0)
+    });
+    grading.strength = 0.8;
+
+    let chains: [(&str, Vec<Arc<dyn PostEffect>>); 3] = [
+        (
+            "post_bloom_vignette",
+            vec![
+                Arc::new(Bloom {
+                    threshold: 0.5,
+                    intensity: 1.0,
+                    radius: 6,
+                }),
+                Arc::new(Vignette::default()),
+            ],
+        ),
+        (
+            "post_depth_fog",
+            vec![Arc::new(DepthFog {
+                start: 3.0,
+                ..DepthFog::new(Rgba32F::new(0.4, 0.45, 0.5, 1.0), 0.5, &camera)
+            })],
+        ),
+        (
+            "post_grading_sharpen_aberration",
+            vec![
+                Arc::new(grading),
+                Arc::new(Sharpen { strength: 0.8 }),
+                Arc::new(ChromaticAberration { strength: 4.0 }),
+            ],
+        ),
+    ];
+
+    for (name, effects) in chains {
+        let mut framebuffer = Framebuffer::<Rgba32F>::with_format(WIDTH, HEIGHT);
+        framebuffer.post_effects = effects;
+        render_scene(&polygons, &camera, &mut framebuffer);
+        assert_golden(name, &framebuffer);
+    }
+
+    // Eine Identitäts-LUT darf das Bild nicht verändern
+    let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    framebuffer.post_effects = vec![Arc::new(ColorGradingLut::identity(33))];
+    render_scene(&polygons, &camera, &mut framebuffer);
+    assert_golden("capsule_above", &framebuffer);
+}
+
+#[test]
+fn ambient_occlusion() {
+    // Einfarbiger Würfel in einer Raumecke, ohne Verdeckung wären die Kanten kaum zu erkennen
+    let grey = 0xFFC8C8C8;
+    let mut polygons = vec![
+        face(
+            [
+                (-3.0, -1.0, -3.0),
+                (3.0, -1.0, -3.0),
+                (3.0, -1.0, 3.0),
+                (-3.0, -1.0, 3.0),
+            ],
+            Point::new(0.0, 1.0, 0.0),
+            grey,
+        ),
+        face(
+            [
+                (-3.0, -1.0, 2.0),
+                (3.0, -1.0, 2.0),
+                (3.0, 3.0, 2.0),
+                (-3.0, 3.0, 2.0),
+            ],
+            Point::new(0.0, 0.0, -1.0),
+            grey,
+        ),
+        face(
+            [
+                (-2.0, -1.0, -3.0),
+                (-2.0, -1.0, 2.0),
+                (-2.0, 3.0, 2.0),
+                (-2.0, 3.0, -3.0),
+            ],
+            Point::new(1.0, 0.0, 0.0),
+            grey,
+        ),
+    ];
+    polygons.extend(cube(Point::new(-0.6, -0.4, 1.0), 0.6, grey));
+
+    let camera = look_at(Point::new(1.5, 1.5, -3.0), Point::new(-0.5, -0.3, 1.0));
+    let mut framebuffer = Framebuffer::<Rgba32F>::with_format(WIDTH, HEIGHT);
+    framebuffer.post_effects = vec![Arc::new(Ssao::new(&camera))];
+    render_scene(&polygons, &camera, &mut framebuffer);
+    assert_golden("ssao", &framebuffer);
+}
+
+#[test]
+fn backgrounds() {
+    let polygons = load_capsule();
+    let camera = look_at(Point::new(2.0, 1.0, -4.0), Point::new(0.0, 0.0, 0.0));
+
+    let (sky, cubemap) = (sky_panorama(), grid_cubemap());
+
+    let backgrounds = [
+        (
+            "background_gradient",
+            Background::Gradient {
+                top: Rgba32F::new(0.1, 0.2, 0.5, 1.0),
+                bottom: Rgba32F::new(0.9, 0.6, 0.3, 1.0),
+            },
+            Msaa::Off,
+        ),
+        (
+            "background_equirectangular",
+            Background::Equirectangular(Arc::new(sky)),
+            Msaa::Off,
+        ),
+        (
+            "background_cubemap_msaa4",
+            Background::Cubemap(Arc::new(cubemap)),
+            Msaa::X4,
+        ),
+    ];
+
+    for (name, background, msaa) in backgrounds {
+        let mut framebuffer = Framebuffer::<Rgba32F>::with_format(WIDTH, HEIGHT);
+        framebuffer.background = background;
+        framebuffer.msaa = msaa;
+        framebuffer.tone_mapping = ToneMapping::AcesFilmic;
+        render_scene(&polygons, &camera, &mut framebuffer);
+        // Der Hintergrund schreibt keine Tiefe
+        assert!(framebuffer.z_buffer.iter().any(|z| z.is_infinite()));
+        assert_golden(name, &framebuffer);
+    }
+}
+
+#[test]
+fn reflections() {
+    let camera = look_at(Point::new(2.0, 1.0, -4.0), Point::new(0.0, 0.0, 0.0));
+
+    let sky = Background::Equirectangular(Arc::new(sky_panorama()));
+    let cubemap = Background::Cubemap(Arc::new(grid_cubemap()));
+    let environment = Environment::from_background(&sky).unwrap();
+    assert_eq!(environment.mip_levels(), 7); // 64x32 bis 1x1
+    assert!(Environment::from_background(&Background::default()).is_none());
+
+    // Polierter Chrom, matt gebürstetes Metall und lackierte Textur
+    let scenes = [
+        (
+            "reflection_mirror",
+            &cubemap,
+            Material::default().with_reflection(0.9, 0.0),
+            false,
+        ),
+        (
+            "reflection_rough_cubemap",
+            &cubemap,
+            Material::default().with_reflection(0.9, 0.5),
+            false,
+        ),
+        (
+            "reflection_textured",
+            &sky,
+            Material::default().with_reflection(0.2, 0.2),
+            true,
+        ),
+    ];
+
+    for (name, background, material, textured) in scenes {
+        let mut polygons = load_capsule();
+        object::smooth_normals(&mut polygons);
+        for polygon in polygons.iter_mut() {
+            polygon.set_material(material);
+            if !textured {
+                polygon.texture = None;
+                polygon.set_color(0xFF808080);
+            }
+        }
+
+        let mut framebuffer = Framebuffer::<Rgba32F>::with_format(WIDTH, HEIGHT);
+        framebuffer.background = background.clone();
+        framebuffer.environment = Environment::from_background(background).map(Arc::new);
+        framebuffer.tone_mapping = ToneMapping::AcesFilmic;
+        render_scene(&polygons, &camera, &mut framebuffer);
+        assert_golden(name, &framebuffer);
+    }
+}
+
+#[test]
+fn normal_mapping() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut capsule = object::load_obj(root.join("capsule.obj").to_str().unwrap()).unwrap();
+
+    // Beulen unter streifendem Licht, die Umgebung hellt nur die Schatten auf
+    let texture = Arc::new(Texture::from_file(
+        root.join("capsule0.jpg").to_str().unwrap(),
+    ));
+    let material = Arc::new(
+        PbrMaterial::new(Rgba32F::WHITE, 0.0, 0.5)
+            .with_normal_map(Arc::new(bump_normal_map(128, 12.0)), 1.0),
+    );
+    for polygon in capsule.iter_mut() {
+        polygon.set_texture(texture.clone());
+        polygon.set_pbr_material(material.clone());
+    }
+
+    let mut framebuffer = Framebuffer::<Rgba32F>::with_format(WIDTH, HEIGHT);
+    framebuffer.lights = vec![DirectionalLight::new(
+        Point::new(1.0, -0.3, 0.4),
+        Rgba32F::WHITE,
+        4.0,
+    )];
+    framebuffer.environment = Some(Arc::new(Environment::from_equirectangular(&sky_panorama())));
+    framebuffer.tone_mapping = ToneMapping::AcesFilmic;
+    let camera = look_at(Point::new(2.0, 1.0, -4.0), Point::new(0.0, 0.0, 0.0));
+    render_scene(&capsule, &camera, &mut framebuffer);
+    assert_golden("normal_mapping", &framebuffer);
+
+    // Ohne Eckentangenten wird der Tangentenraum pro Dreieck bestimmt, das Bild bleibt ähnlich
+    for polygon in capsule.iter_mut() {
+        polygon.set_tangents(Vec::new());
+    }
+    let with_tangents = framebuffer.pixels.clone();
+    render_scene(&capsule, &camera, &mut framebuffer);
+    let difference: f32 = with_tangents
+        .iter()
+        .zip(&framebuffer.pixels)
+        .map(|(a, b)| (a.luma() - b.luma()).abs())
+        .sum::<f32>()
+        / with_tangents.len() as f32;
+    assert!(difference < 0.02, "mittlere Abweichung {difference}");
+}
+
+#[test]
+fn pbr_materials() {
+    let sky = sky_panorama();
+    let sun = DirectionalLight::new(
+        Point::new(-0.4, -0.6, 0.7),
+        Rgba32F::new(1.0, 0.95, 0.85, 1.0),
+        3.0,
+    );
+    let render_pbr = |polygons: &[Polygon], camera: &Camera| {
+        let mut framebuffer = Framebuffer::<Rgba32F>::with_format(WIDTH, HEIGHT);
+        framebuffer.background = Background::Equirectangular(Arc::new(sky.clone()));
+        framebuffer.environment =
+            Environment::from_background(&framebuffer.background).map(Arc::new);
+        framebuffer.lights = vec![sun];
+        framebuffer.tone_mapping = ToneMapping::AcesFilmic;
+        render_scene(polygons, camera, &mut framebuffer);
+        framebuffer
+    };
+
+    // Rauer roter Kunststoff, Gold und polierter Chrom nebeneinander
+    let materials = [
+        PbrMaterial::new(Rgba32F::new(0.8, 0.1, 0.1, 1.0), 0.0, 0.6),
+        PbrMaterial::new(Rgba32F::new(1.0, 0.77, 0.34, 1.0), 1.0, 0.35),
+        PbrMaterial::new(Rgba32F::new(0.95, 0.95, 0.95, 1.0), 1.0, 0.05),
+    ];
+    let mut polygons = Vec::new();
+    for (i, material) in materials.into_iter().enumerate() {
+        let offset = Point::new((i as f32 - 1.0) * 2.5, 0.0, 0.0);
+        let material = Arc::new(material);
+        let mut capsule = load_capsule();
+        for polygon in capsule.iter_mut() {
+            polygon.texture = None;
+            polygon.set_color(0xFFFFFFFF);
+            polygon.vertices.iter_mut().for_each(|v| *v += offset);
+            polygon.set_pbr_material(material.clone());
+        }
+        object::smooth_normals(&mut capsule);
+        polygons.extend(capsule);
+    }
+    let camera = look_at(Point::new(1.0, 1.5, -8.0), Point::new(0.0, 0.0, 0.0));
+    assert_golden("pbr_metal_roughness", &render_pbr(&polygons, &camera));
+
+    // Alle Maps auf der texturierten Kapsel: Beulen, Metallstreifen, Verdeckung und Leuchten
+    let size = 64;
+    let texel = |f: &dyn Fn(f32, f32) -> [u8; 4]| Arc::new(data_texture(size, f));
+    let normal_map = Arc::new(bump_normal_map(size, 8.0));
+    let metallic_roughness = texel(&|u, _| {
+        let metal = ((u * 6.0) as usize).is_multiple_of(2);
+        [
+            0,
+            if metal { 60 } else { 200 },
+            if metal { 255 } else { 0 },
+            255,
+        ]
+    });
+    let occlusion = texel(&|_, v| [(255.0 * (0.3 + 0.7 * v)) as u8, 0, 0, 255]);
+    let emissive = Arc::new(checkerboard(size, 8));
+    let material = Arc::new(
+        PbrMaterial::new(Rgba32F::WHITE, 1.0, 1.0)
+            .with_normal_map(normal_map, 1.0)
+            .with_metallic_roughness_map(metallic_roughness)
+            .with_occlusion_map(occlusion, 1.0)
+            .with_emissive(Rgba32F::new(0.05, 0.01, 0.0, 1.0), Some(emissive)),
+    );
+    let mut capsule = load_capsule();
+    object::smooth_normals(&mut capsule);
+    for polygon in capsule.iter_mut() {
+        polygon.set_pbr_material(material.clone());
+    }
+    let camera = look_at(Point::new(2.0, 1.0, -4.0), Point::new(0.0, 0.0, 0.0));
+    assert_golden("pbr_maps", &render_pbr(&capsule, &camera));
+}
+
+/// Lineare Datentextur, `f` bekommt die UV-Koordinaten der Texelmitte
+#[test]
+fn orthographic_views() {
+    // Gleich große Würfel hintereinander: parallel projiziert bleiben sie gleich groß
+    let mut polygons = load_capsule();
+    for (i, color) in [0xFFD04030u32, 0xFF40A040, 0xFF3060D0]
+        .into_iter()
+        .enumerate()
+    {
+        polygons.extend(cube(Point::new(1.8, -0.5, i as f32 * 1.5), 0.5, color));
+    }
+
+    let mut camera = look_at(Point::new(0.0, 0.0, -5.0), Point::new(0.0, 0.0, 0.0));
+    camera.projection = Projection::Orthographic { height: 1.0 };
+    let mut orbit = OrbitController::new(Point::new(0.0, 0.0, 0.0), 5.0);
+    orbit.frame(&mut camera, &polygons);
+
+    for (name, view) in [
+        ("ortho_front", StandardView::Front),
+        ("ortho_top", StandardView::Top),
+        ("ortho_isometric", StandardView::Isometric),
+    ] {
+        orbit.standard_view(&mut camera, view);
+        assert_golden(name, &render(&polygons, &camera));
+    }
+}
+
+#[test]
+fn console_overlay() {
+    let mut cvars = Cvars::new();
+    Camera::register_cvars(&mut cvars);
+    let mut console = Console::new();
+    console.scale = 1;
+    console.toggle();
+    for line in ["sv_gravity 40", "cvarlist sv_air", "fov"] {
+        console.input = line.to_string();
+        console.submit(&mut cvars, &mut Bindings::default());
+    }
+    console.input = "!\"#$%&'()*+,-./0123456789:;<=>?@[\\]^_".to_string();
+
+    let camera = look_at(Point::new(0.0, 0.0, -5.0), Point::new(0.0, 0.0, 0.0));
+    let mut framebuffer = render(&load_capsule(), &camera);
+    console.draw(&mut framebuffer);
+    assert_golden("console_overlay", &framebuffer);
+}
+
+fn data_texture(size: usize, f: &dyn Fn(f32, f32) -> [u8; 4]) -> Texture {
+    let mut data = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let (u, v) = (
+                (x as f32 + 0.5) / size as f32,
+                (y as f32 + 0.5) / size as f32,
+            );
+            data.extend_from_slice(&f(u, v));
+        }
+    }
+    Texture::new(size, size, data).with_color_space(ColorSpace::Linear)
+}
+
+/// Normal Map für Beulen mit der Höhe sin(u) * sin(v), `cells` Perioden pro Richtung
+fn bump_normal_map(size: usize, cells: f32) -> Texture {
+    let frequency = cells * std::f32::consts::TAU;
+    let encode = |value: f32| ((value * 0.5 + 0.5) * 255.0).round() as u8;
+    data_texture(size, &|u, v| {
+        // Normale aus den Ableitungen, +y zeigt zu kleinerem v
+        let du = (frequency * u).cos() * (frequency * v).sin() * 0.5;
+        let dv = (frequency * u).sin() * (frequency * v).cos() * 0.5;
+        let n = Point::new(-du, dv, 1.0).normalize();
+        [encode(n.x), encode(n.y), encode(n.z), 255]
+    })
+}
+
+/// Himmel mit Horizont und heller Sonne, als Panorama
+fn sky_panorama() -> HdrTexture {
+    let (width, height) = (64, 32);
+    HdrTexture::new(
+        width,
+        height,
+        (0..width * height)
+            .map(|i| {
+                let (u, v) = (
+                    (i % width) as f32 / width as f32,
+                    (i / width) as f32 / height as f32,
+                );
+                if (u - 0.55).abs() < 0.03 && (v - 0.35).abs() < 0.05 {
+                    Rgba32F::new(20.0, 18.0, 12.0, 1.0)
+                } else if v < 0.5 {
+                    Rgba32F::new(0.2, 0.4, 0.9, 1.0)
+                        .lerp(Rgba32F::new(0.8, 0.85, 0.9, 1.0), v * 2.0)
+                } else {
+                    Rgba32F::new(0.25, 0.2, 0.15, 1.0)
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Jede Würfelseite einfarbig mit Gitter, damit Ausrichtung und Übergänge sichtbar sind
+fn grid_cubemap() -> Cubemap {
+    let face_colors = [
+        Rgba32F::new(0.8, 0.2, 0.2, 1.0),
+        Rgba32F::new(0.2, 0.8, 0.8, 1.0),
+        Rgba32F::new(0.2, 0.8, 0.2, 1.0),
+        Rgba32F::new(0.8, 0.2, 0.8, 1.0),
+        Rgba32F::new(0.2, 0.2, 0.8, 1.0),
+        Rgba32F::new(0.8, 0.8, 0.2, 1.0),
+    ];
+    Cubemap::new(face_colors.map(|color| {
+        let data = (0..16 * 16)
+            .map(|i| {
+                if i % 4 == 0 || (i / 16) % 4 == 0 {
+                    color * 0.3
+                } else {
+                    color
+                }
+            })
+            .collect();
+        HdrTexture::new(16, 16, data)
+    }))
+}
+
+/// Achsenparalleles Quadrat mit Kantenlänge `size`, Mittelpunkt (x, y) auf der Tiefe z
+fn quad(size: f32, x: f32, y: f32, z: f32) -> Polygon {
+    let h = size / 2.0;
+    let mut quad = Polygon::new(0xFFFFFFFF);
+    quad.add_point(Point::new(x - h, y - h, z));
+    quad.add_point(Point::new(x - h, y + h, z));
+    quad.add_point(Point::new(x + h, y + h, z));
+    quad.add_point(Point::new(x + h, y - h, z));
+    quad.set_tex_coords(vec![(0.0, 1.0), (0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]);
+    quad
+}
+
+/// Kamera an `position`, die auf `target` schaut.
+/// `forward` zeigt bei dieser Kamera vom Ziel weg (siehe `Camera::move_forward`).
+/// Viereck, dessen Umlaufrichtung so gewählt wird, dass die Vorderseite nach `outward` zeigt
+fn face(corners: [(f32, f32, f32); 4], outward: Point, color: u32) -> Polygon {
+    let mut points = corners.map(|(x, y, z)| Point::new(x, y, z));
+    let normal = (points[1] - points[0]).cross(points[2] - points[0]);
+    if normal.dot(outward) < 0.0 {
+        points.reverse();
+    }
+
+    let mut polygon = Polygon::new(color);
+    for point in points {
+        polygon.add_point(point);
+    }
+    polygon
+}
+
+/// Würfel mit Mittelpunkt `center` und halber Kantenlänge `half`
+fn cube(center: Point, half: f32, color: u32) -> Vec<Polygon> {
+    let (x, y, z) = (center.x, center.y, center.z);
+    let (x0, x1, y0, y1, z0, z1) = (x - half, x + half, y - half, y + half, z - half, z + half);
+    vec![
+        face(
+            [(x0, y0, z0), (x1, y0, z0), (x1, y1, z0), (x0, y1, z0)],
+            Point::new(0.0, 0.0, -1.0),
+            color,
+        ),
+        face(
+            [(x0, y0, z1), (x1, y0, z1), (x1, y1, z1), (x0, y1, z1)],
+            Point::new(0.0, 0.0, 1.0),
+            color,
+        ),
+        face(
+            [(x0, y0, z0), (x0, y0, z1), (x0, y1, z1), (x0, y1, z0)],
+            Point::new(-1.0, 0.0, 0.0),
+            color,
+        ),
+        face(
+            [(x1, y0, z0), (x1, y0, z1), (x1, y1, z1), (x1, y1, z0)],
+            Point::new(1.0, 0.0, 0.0),
+            color,
+        ),
+        face(
+            [(x0, y0, z0), (x1, y0, z0), (x1, y0, z1), (x0, y0, z1)],
+            Point::new(0.0, -1.0, 0.0),
+            color,
+        ),
+        face(
+            [(x0, y1, z0), (x1, y1, z0), (x1, y1, z1), (x0, y1, z1)],
+            Point::new(0.0, 1.0, 0.0),
+            color,
+        ),
+    ]
+}
+
+fn look_at(position: Point, target: Point) -> Camera {
+    let mut camera = Camera::new(
+        position,
+        (position - target).normalize(),
+        Point::new(0.0, 1.0, 0.0),
+        60.0,
+        WIDTH as f32 / HEIGHT as f32,
+        0.1,
+        100.0,
+    );
+    let forward = camera.forward;
+    camera.yaw = forward.z.atan2(forward.x);
+    camera.pitch = forward.y.asin();
+    camera.update_forward();
+    camera
+}
+
+fn render(polygons: &[Polygon], camera: &Camera) -> Framebuffer {
+    let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    render_scene(polygons, camera, &mut framebuffer);
+    framebuffer
+}
+
+fn load_capsule() -> Vec<Polygon> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let texture = Texture::from_file(root.join("capsule0.jpg").to_str().unwrap());
+    let (vertices, faces, tex) =
+        object::parse_obj_file(root.join("capsule.obj").to_str().unwrap()).unwrap();
+
+    let mut polygons = object::process_faces(&vertices, &faces, &tex);
+    let texture = Arc::new(texture);
+    for polygon in polygons.iter_mut() {
+        polygon.set_texture(texture.clone());
+    }
+    polygons
+}
+
+fn checkerboard(size: usize, cells: usize) -> Texture {
+    let cell = size / cells;
+    let mut data = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let rgba = if (x / cell + y / cell).is_multiple_of(2) {
+                [230, 230, 230, 255]
+            } else {
+                [40, 90, 200, 255]
+            };
+            data.extend_from_slice(&rgba);
+        }
+    }
+    Texture::new(size, size, data)
+}
+
+fn assert_golden<C: Color>(name: &str, framebuffer: &Framebuffer<C>) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let reference_path = root.join("tests/golden").join(format!("{name}.png"));
+    let actual = framebuffer.to_image();
+
+    if std::env::var_os("RAKE_BLESS").is_some() {
+        std::fs::create_dir_all(reference_path.parent().unwrap()).unwrap();
+        actual.save(&reference_path).unwrap();
+        return;
+    }
+
+    let expected = image::open(&reference_path)
+        .unwrap_or_else(|e| panic!("{name}: Referenzbild {reference_path:?} fehlt ({e})"))
+        .to_rgba8();
+    assert_eq!(
+        expected.dimensions(),
+        actual.dimensions(),
+        "{name}: Bildgröße weicht vom Referenzbild ab"
+    );
+
+    let (diff, differing) = diff_images(&expected, &actual);
+    let ratio = differing as f32 / (WIDTH * HEIGHT) as f32;
+    if ratio > MAX_DIFF_RATIO {
+        let out_dir = diff_dir();
+        std::fs::create_dir_all(&out_dir).unwrap();
+        actual
+            .save(out_dir.join(format!("{name}.actual.png")))
+            .unwrap();
+        diff.save(out_dir.join(format!("{name}.diff.png"))).unwrap();
+        panic!(
+            "{name}: {differing} Pixel ({:.3}%) weichen ab, erlaubt sind {:.3}%. Siehe {out_dir:?}",
+            ratio * 100.0,
+            MAX_DIFF_RATIO * 100.0
+        );
+    }
+}
+
+fn diff_dir() -> PathBuf {
+    let target = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("target"));
+    target.join("golden-diff")
+}
+
+/// Vergleicht zwei Bilder im YIQ-Farbraum (gewichtet nach Wahrnehmung, wie pixelmatch).
+/// Liefert ein Differenzbild (abweichende Pixel rot auf abgedunkeltem Referenzbild)
+/// und die Anzahl abweichender Pixel.
+fn diff_images(expected: &RgbaImage, actual: &RgbaImage) -> (RgbaImage, usize) {
+    const MAX_DELTA: f32 = 35215.0;
+    let threshold = MAX_DELTA * PIXEL_THRESHOLD * PIXEL_THRESHOLD;
+
+    let mut differing = 0;
+    let mut diff = RgbaImage::new(expected.width(), expected.height());
+    for (x, y, pixel) in diff.enumerate_pixels_mut() {
+        let a = expected.get_pixel(x, y);
+        let b = actual.get_pixel(x, y);
+
+        if color_delta(a, b) > threshold {
+            differing += 1;
+            *pixel = Rgba([255, 0, 0, 255]);
+        } else {
+            let gray = (luma(a) * 0.25 + 191.0) as u8;
+            *pixel = Rgba([gray, gray, gray, 255]);
+        }
+    }
+    (diff, differing)
+}
+
+fn color_delta(a: &Rgba<u8>, b: &Rgba<u8>) -> f32 {
+    let [r1, g1, b1] = [a[0] as f32, a[1] as f32, a[2] as f32];
+    let [r2, g2, b2] = [b[0] as f32, b[1] as f32, b[2] as f32];
+    let (dr, dg, db) = (r1 - r2, g1 - g2, b1 - b2);
+
+    let y = dr * 0.298_895_3 + dg * 0.586_622_5 + db * 0.114_482_23;
+    let i = dr * 0.595_977_99 - dg * 0.274_176_1 - db * 0.321_801_9;
+    let q = dr * 0.211_470_17 - dg * 0.522_617_1 + db * 0.311_146_94;
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
+
+fn luma(pixel: &Rgba<u8>) -> f32 {
+    pixel[0] as f32 * 0.299 + pixel[1] as f32 * 0.587 + pixel[2] as f32 * 0.114
+}
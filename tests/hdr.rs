@@ -0,0 +1,39 @@
+//! Laden von HDR-Bildern für Umgebungslicht.
+
+use image::{Rgb, Rgb32FImage, Rgba, Rgba32FImage};
+use rake::HdrTexture;
+use std::path::PathBuf;
+
+#[test]
+fn loads_radiance_hdr() {
+    let path = output_dir().join("gradient.hdr");
+    Rgb32FImage::from_fn(8, 4, |x, _| Rgb([x as f32 * 2.0, 1.0, 0.25]))
+        .save(&path)
+        .unwrap();
+
+    let texture = HdrTexture::from_file(path.to_str().unwrap());
+    assert_eq!((texture.width, texture.height), (8, 4));
+    // RGBE speichert nur 8 Bit Mantisse, Werte über 1.0 bleiben aber erhalten
+    let brightest = texture.sample(1.0, 0.5);
+    assert!((brightest.r - 14.0).abs() < 0.1, "{brightest:?}");
+    assert!((brightest.b - 0.25).abs() < 0.01, "{brightest:?}");
+}
+
+#[test]
+fn loads_openexr() {
+    let path = output_dir().join("gradient.exr");
+    Rgba32FImage::from_fn(8, 4, |x, y| Rgba([x as f32 * 4.0, y as f32, 0.5, 1.0]))
+        .save(&path)
+        .unwrap();
+
+    let texture = HdrTexture::from_file(path.to_str().unwrap());
+    assert_eq!((texture.width, texture.height), (8, 4));
+    let texel = texture.sample(0.99, 0.99);
+    assert_eq!((texel.r, texel.g, texel.b), (28.0, 3.0, 0.5));
+}
+
+fn output_dir() -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("hdr");
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
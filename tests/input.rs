@@ -0,0 +1,138 @@
+//! Tastenbelegung und Aktionen.
+
+use rake::{
+    Action, ActionState, Bindings, Camera, CameraController, Console, Cvars, FlyController, Key,
+    Point, StandardView,
+};
+
+#[test]
+fn key_and_action_names_round_trip() {
+    for name in [
+        "w",
+        "7",
+        "f5",
+        "space",
+        "uparrow",
+        "kp_plus",
+        "mouse1",
+        "mwheeldown",
+    ] {
+        assert_eq!(Key::from_name(name).unwrap().name(), name);
+    }
+    assert_eq!(Key::from_name("W"), Some(Key::Char('W')));
+    assert_eq!(Key::from_name("mouse4"), Some(Key::Mouse4));
+    assert_eq!(Key::from_name("f13"), None);
+    assert_eq!(Key::from_name("ä"), None);
+    assert_eq!(Key::from_name("hyper"), None);
+
+    assert_eq!(Action::from_name("Jump"), Some(Action::Jump));
+    assert_eq!(
+        Action::from_name("view_iso"),
+        Some(Action::View(StandardView::Isometric))
+    );
+    assert_eq!(Action::ToggleMouseLook.name(), "mouselook");
+    assert_eq!(Action::from_name("fly"), None);
+}
+
+#[test]
+fn default_bindings_match_previous_layout() {
+    let bindings = Bindings::default();
+    assert_eq!(bindings.action(Key::Char('W')), Some(Action::MoveForward));
+    assert_eq!(bindings.action(Key::Char('V')), Some(Action::Jump));
+    assert_eq!(
+        bindings.action(Key::Char('L')),
+        Some(Action::ToggleMouseLook)
+    );
+    assert_eq!(bindings.action(Key::WheelUp), Some(Action::ZoomIn));
+    assert_eq!(
+        bindings.action(Key::Char('1')),
+        Some(Action::View(StandardView::Front))
+    );
+    assert_eq!(
+        bindings.keys_for(Action::ToggleConsole),
+        vec![Key::Grave, Key::Backslash]
+    );
+    assert_eq!(bindings.action(Key::MouseLeft), None);
+}
+
+#[test]
+fn config_round_trip_and_errors() {
+    let mut bindings = Bindings::default();
+    bindings.execute("bind mouse2 jump").unwrap();
+    bindings.execute("unbind v").unwrap();
+    let config = bindings.to_config();
+
+    let mut loaded = Bindings::new();
+    assert!(loaded.exec_config(&config).is_empty());
+    assert_eq!(loaded, bindings);
+    assert_eq!(
+        loaded.execute("bind mouse2").unwrap(),
+        "mouse2 is bound to jump"
+    );
+    assert_eq!(loaded.execute("bind v").unwrap(), "v is not bound");
+
+    let errors = loaded
+        .exec_config("// Kommentar\nbind x jump // springen\nbind y fly\nbind hyper jump\nbind\n");
+    assert_eq!(errors.len(), 3, "{errors:?}");
+    assert!(errors[0].starts_with("line 3:") && errors[0].contains("Unknown action"));
+    assert!(errors[1].starts_with("line 4:") && errors[1].contains("Unknown key"));
+    assert_eq!(loaded.action(Key::Char('X')), Some(Action::Jump));
+
+    loaded.execute("unbindall").unwrap();
+    assert_eq!(loaded.iter().count(), 0);
+}
+
+#[test]
+fn action_state_from_held_keys() {
+    let mut bindings = Bindings::default();
+    let held = [Key::Char('W'), Key::Char('A'), Key::Char('D'), Key::F(9)];
+    let input = ActionState::from_keys(&bindings, &held);
+    assert!(input.is_down(Action::MoveForward));
+    assert_eq!(input.axis(Action::MoveRight, Action::MoveLeft), 0.0);
+    assert_eq!(input.axis(Action::MoveForward, Action::MoveBack), 1.0);
+    assert_eq!(input.iter().count(), 3);
+
+    bindings.bind(Key::F(9), Action::MoveBack);
+    let input = ActionState::from_keys(&bindings, &held);
+    assert_eq!(input.axis(Action::MoveForward, Action::MoveBack), 0.0);
+}
+
+#[test]
+fn rebinding_moves_camera_with_other_key() {
+    let mut bindings = Bindings::default();
+    bindings.execute("bind uparrow forward").unwrap();
+    let mut fly = FlyController::default();
+
+    let mut camera = Camera::default();
+    let input = ActionState::from_keys(&bindings, &[Key::Up]);
+    fly.update(&mut camera, 1.0, &input);
+    // `forward` zeigt von der Blickrichtung weg
+    assert!(
+        (camera.position.z + 5.0).abs() < 1e-4,
+        "{:?}",
+        camera.position
+    );
+
+    bindings.execute("unbind uparrow").unwrap();
+    let mut camera = Camera::default();
+    fly.update(
+        &mut camera,
+        1.0,
+        &ActionState::from_keys(&bindings, &[Key::Up]),
+    );
+    assert_eq!(camera.position, Point::new(0.0, 0.0, 0.0));
+}
+
+#[test]
+fn console_changes_bindings() {
+    let mut cvars = Cvars::new();
+    let mut bindings = Bindings::default();
+    let mut console = Console::new();
+    console.input = "bind mouse1 jump".to_string();
+    console.submit(&mut cvars, &mut bindings);
+    assert_eq!(bindings.action(Key::MouseLeft), Some(Action::Jump));
+
+    console.input = "bind mouse9 jump".to_string();
+    console.submit(&mut cvars, &mut bindings);
+    assert_eq!(console.log.last().unwrap(), "Unknown key: mouse9");
+}
@@ -0,0 +1,296 @@
+//! Eigenschaften der Vektoren und Matrizen, mit zufälligen Eingaben geprüft.
+
+use proptest::prelude::*;
+use rake::{Mat3, Mat4, Point, Polygon, Quat, Transform, Vec3, Vec4};
+use std::f32::consts::PI;
+
+const EPSILON: f32 = 1e-3;
+
+fn vec3(range: f32) -> impl Strategy<Value = Vec3> {
+    (-range..range, -range..range, -range..range).prop_map(|(x, y, z)| Vec3::new(x, y, z))
+}
+
+fn direction() -> impl Strategy<Value = Vec3> {
+    vec3(1.0).prop_filter("nicht null", |v| v.magnitude() > 0.1)
+}
+
+///Gut konditionierte affine Transformation aus Skalierung, Drehung und Verschiebung
+fn transform() -> impl Strategy<Value = Mat4> {
+    (vec3(2.0), direction(), -PI..PI, vec3(10.0)).prop_map(|(scale, axis, angle, offset)| {
+        let scale = Mat4::scale(
+            scale.x.abs() + 0.5,
+            scale.y.abs() + 0.5,
+            scale.z.abs() + 0.5,
+        );
+        scale
+            * Mat4::rotation_around_axis(axis, angle)
+            * Mat4::translate(offset.x, offset.y, offset.z)
+    })
+}
+
+fn rotation() -> impl Strategy<Value = Quat> {
+    (direction(), -PI..PI).prop_map(|(axis, angle)| Quat::from_axis_angle(axis, angle))
+}
+
+///q und -q beschreiben dieselbe Drehung
+fn assert_quat_close(a: Quat, b: Quat) -> Result<(), TestCaseError> {
+    prop_assert!(a.dot(b).abs() > 1.0 - EPSILON, "{a:?} != {b:?}");
+    Ok(())
+}
+
+fn assert_vec_close(a: Vec3, b: Vec3) -> Result<(), TestCaseError> {
+    prop_assert!((a - b).magnitude() < EPSILON, "{a:?} != {b:?}");
+    Ok(())
+}
+
+fn assert_mat_close(a: &Mat4, b: &Mat4) -> Result<(), TestCaseError> {
+    for i in 0..4 {
+        for j in 0..4 {
+            prop_assert!(
+                (a.data[i][j] - b.data[i][j]).abs() < EPSILON,
+                "{a:?} != {b:?}"
+            );
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn inverse_undoes_transform(m in transform(), p in vec3(10.0)) {
+        let inverse = m.inverse().unwrap();
+        assert_mat_close(&(m * inverse), &Mat4::IDENTITY)?;
+        assert_mat_close(&(inverse * m), &Mat4::IDENTITY)?;
+        assert_vec_close(inverse.transform_point(m.transform_point(p)), p)?;
+    }
+
+    #[test]
+    fn determinant_is_multiplicative(a in transform(), b in transform()) {
+        let expected = a.determinant() * b.determinant();
+        let tolerance = EPSILON * expected.abs().max(1.0);
+        prop_assert!(((a * b).determinant() - expected).abs() < tolerance);
+        prop_assert!((a.transpose().determinant() - a.determinant()).abs() < EPSILON);
+    }
+
+    #[test]
+    fn transpose_is_involution(m in transform()) {
+        prop_assert_eq!(m.transpose().transpose(), m);
+    }
+
+    #[test]
+    fn product_applies_left_first(a in transform(), b in transform(), p in vec3(10.0)) {
+        let combined = (a * b).transform_point(p);
+        let sequential = b.transform_point(a.transform_point(p));
+        let tolerance = EPSILON * sequential.magnitude().max(1.0);
+        prop_assert!((combined - sequential).magnitude() < tolerance);
+    }
+
+    #[test]
+    fn translate_moves_points_but_not_vectors(offset in vec3(10.0), p in vec3(10.0)) {
+        let m = Mat4::translate(offset.x, offset.y, offset.z);
+        assert_vec_close(m.transform_point(p), p + offset)?;
+        assert_vec_close(m.transform_vector(p), p)?;
+    }
+
+    #[test]
+    fn axis_rotations_match_rotation_around_axis(angle in -PI..PI, p in vec3(10.0)) {
+        let axes = [
+            (Mat4::rotate_x(angle), Vec3::new(1.0, 0.0, 0.0)),
+            (Mat4::rotate_y(angle), Vec3::new(0.0, 1.0, 0.0)),
+            (Mat4::rotate_z(angle), Vec3::new(0.0, 0.0, 1.0)),
+        ];
+        for (rotation, axis) in axes {
+            let general = Mat4::rotation_around_axis(axis, angle);
+            assert_vec_close(rotation.transform_point(p), general.transform_point(p))?;
+            // Drehungen erhalten Längen und Achse
+            let length = rotation.transform_point(p).magnitude();
+            prop_assert!((length - p.magnitude()).abs() < EPSILON);
+            assert_vec_close(rotation.transform_vector(axis), axis)?;
+        }
+    }
+
+    #[test]
+    fn normal_matrix_keeps_normals_perpendicular(
+        m in transform(),
+        a in direction(),
+        b in direction(),
+    ) {
+        let normal = a.cross(b);
+        prop_assume!(normal.magnitude() > 0.1);
+
+        let transformed = normal * m.normal_matrix().unwrap();
+        for tangent in [m.transform_vector(a), m.transform_vector(b)] {
+            let tolerance = EPSILON * transformed.magnitude() * tangent.magnitude();
+            prop_assert!(transformed.dot(tangent).abs() < tolerance);
+        }
+    }
+
+    #[test]
+    fn mat3_inverse_matches_mat4(m in transform()) {
+        let inverse = m.to_mat3().inverse().unwrap();
+        let product = m.to_mat3() * inverse;
+        for i in 0..3 {
+            for j in 0..3 {
+                let expected = Mat3::IDENTITY.data[i][j];
+                prop_assert!((product.data[i][j] - expected).abs() < EPSILON);
+            }
+        }
+    }
+
+    #[test]
+    fn look_at_puts_target_on_positive_z(eye in vec3(10.0), offset in direction()) {
+        let target = eye + offset * 5.0;
+        prop_assume!(offset.normalize().y.abs() < 0.99);
+        let view = Mat4::look_at(eye, target, Vec3::new(0.0, 1.0, 0.0));
+
+        assert_vec_close(view.transform_point(eye), Vec3::ZERO)?;
+        let distance = (target - eye).magnitude();
+        assert_vec_close(view.transform_point(target), Vec3::new(0.0, 0.0, distance))?;
+    }
+
+    #[test]
+    fn quat_rotation_matches_matrix(q in rotation(), p in vec3(10.0)) {
+        assert_vec_close(q * p, q.to_mat4().transform_point(p))?;
+        assert_vec_close(q * p, p * q.to_mat3())?;
+        assert_quat_close(Quat::from_mat3(&q.to_mat3()), q)?;
+    }
+
+    #[test]
+    fn quat_product_composes_rotations(a in rotation(), b in rotation(), p in vec3(10.0)) {
+        assert_vec_close((a * b) * p, a * (b * p))?;
+        assert_mat_close(&(a * b).to_mat4(), &(b.to_mat4() * a.to_mat4()))?;
+        assert_quat_close(a * a.inverse(), Quat::IDENTITY)?;
+    }
+
+    #[test]
+    fn axis_angle_round_trip(axis in direction(), angle in 0.01f32..3.1) {
+        let (result_axis, result_angle) = Quat::from_axis_angle(axis, angle).to_axis_angle();
+        assert_vec_close(result_axis, axis.normalize())?;
+        prop_assert!((result_angle - angle).abs() < EPSILON);
+
+        // Gleiche Drehrichtung wie die Matrizen
+        let matrix = Mat4::rotation_around_axis(axis, angle);
+        let p = Vec3::new(1.0, 2.0, 3.0);
+        assert_vec_close(Quat::from_axis_angle(axis, angle) * p, matrix.transform_point(p))?;
+    }
+
+    #[test]
+    fn euler_round_trip(yaw in -3.1f32..3.1, pitch in -1.5f32..1.5, roll in -3.1f32..3.1) {
+        let q = Quat::from_euler(yaw, pitch, roll);
+        let (y, p, r) = q.to_euler();
+        prop_assert!((y - yaw).abs() < EPSILON, "{y} != {yaw}");
+        prop_assert!((p - pitch).abs() < EPSILON, "{p} != {pitch}");
+        prop_assert!((r - roll).abs() < EPSILON, "{r} != {roll}");
+        assert_quat_close(Quat::from_euler(y, p, r), q)?;
+    }
+
+    #[test]
+    fn slerp_moves_at_constant_speed(a in rotation(), b in rotation(), t in 0.0f32..1.0) {
+        assert_quat_close(a.slerp(b, 0.0), a)?;
+        assert_quat_close(a.slerp(b, 1.0), b)?;
+
+        let step = a.slerp(b, t);
+        prop_assert!((step.magnitude() - 1.0).abs() < 1e-4);
+        // Winkel zwischen zwei Drehungen auf dem kürzeren Weg
+        let angle = |p: Quat, q: Quat| 2.0 * p.dot(q).abs().min(1.0).acos();
+        let total = angle(a, b);
+        prop_assume!(total > 0.05);
+        prop_assert!((angle(a, step) - t * total).abs() < 2.0 * EPSILON * PI);
+    }
+
+    #[test]
+    fn transform_scales_rotates_then_translates(
+        q in rotation(),
+        offset in vec3(10.0),
+        p in vec3(10.0),
+    ) {
+        let transform = Transform::new(offset, q, Vec3::new(2.0, 2.0, 2.0));
+        assert_vec_close(transform.to_mat4().transform_point(p), q * (p * 2.0) + offset)?;
+
+        let halfway = Transform::IDENTITY.interpolate(&transform, 0.5);
+        assert_vec_close(halfway.translation, offset * 0.5)?;
+        assert_quat_close(halfway.rotation, Quat::IDENTITY.slerp(q, 0.5))?;
+    }
+
+    #[test]
+    fn vector_identities(a in vec3(10.0), b in vec3(10.0)) {
+        let cross = a.cross(b);
+        let tolerance = EPSILON * a.magnitude().powi(2).max(1.0) * b.magnitude().max(1.0);
+        prop_assert!(cross.dot(a).abs() < tolerance);
+        assert_vec_close(a + b - b, a)?;
+        assert_vec_close(-(-a), a)?;
+        assert_vec_close(a * 2.0, 2.0 * a)?;
+        assert_vec_close(a.lerp(b, 1.0), b)?;
+        if a.magnitude() > 0.1 {
+            prop_assert!((a.normalize().magnitude() - 1.0).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn singular_matrices_have_no_inverse() {
+    assert!(Mat4::scale(1.0, 0.0, 1.0).inverse().is_none());
+    assert!(Mat4::scale(1.0, 0.0, 1.0).normal_matrix().is_none());
+}
+
+#[test]
+fn perspective_maps_planes_to_depth_range() {
+    let projection = Mat4::perspective(PI / 2.0, 2.0, 0.5, 50.0);
+    let near = projection.transform_point(Vec3::new(0.0, 0.0, 0.5));
+    let far = projection.transform_point(Vec3::new(0.0, 0.0, 50.0));
+    assert!(near.z.abs() < 1e-6 && (far.z - 1.0).abs() < 1e-6);
+
+    // Bei 90° reicht das Bild in der Entfernung d genau d nach oben
+    let top = projection.transform_point(Vec3::new(0.0, 10.0, 10.0));
+    assert!((top.y - 1.0).abs() < 1e-5);
+    assert!(projection.is_perspective());
+}
+
+#[test]
+fn orthographic_maps_box_to_unit_cube() {
+    let projection = Mat4::orthographic(-4.0, 2.0, -1.0, 3.0, 1.0, 11.0);
+    let min = projection.transform_point(Vec3::new(-4.0, -1.0, 1.0));
+    let max = projection.transform_point(Vec3::new(2.0, 3.0, 11.0));
+    assert_eq!((min.x, min.y, min.z), (-1.0, -1.0, 0.0));
+    assert_eq!((max.x, max.y, max.z), (1.0, 1.0, 1.0));
+    assert!(!projection.is_perspective());
+}
+
+#[test]
+fn homogeneous_division() {
+    assert_eq!(
+        Vec4::new(2.0, 4.0, 6.0, 2.0).project(),
+        Vec3::new(1.0, 2.0, 3.0)
+    );
+    assert_eq!(
+        Vec4::new(2.0, 4.0, 6.0, 0.0).project(),
+        Vec3::new(2.0, 4.0, 6.0)
+    );
+}
+
+#[test]
+fn euler_at_pole_keeps_view() {
+    let q = Quat::from_euler(0.7, std::f32::consts::FRAC_PI_2, 0.0);
+    let (yaw, pitch, roll) = q.to_euler();
+    assert_eq!(roll, 0.0);
+    let p = Vec3::new(0.3, -1.0, 2.0);
+    let round_trip = Quat::from_euler(yaw, pitch, roll);
+    assert!((round_trip * p - q * p).magnitude() < 1e-4);
+}
+
+#[test]
+fn polygon_transform_moves_vertices() {
+    let mut polygon = Polygon::new(0xFFFFFFFF);
+    polygon.add_point(Point::new(0.0, 0.0, 0.0));
+    polygon.add_point(Point::new(1.0, 0.0, 0.0));
+    polygon.add_point(Point::new(0.0, 1.0, 0.0));
+    let normal = polygon.face_normal();
+
+    let rotation = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), std::f32::consts::FRAC_PI_2);
+    let transform = Transform::new(Vec3::new(0.0, 5.0, 0.0), rotation, Vec3::new(3.0, 3.0, 3.0));
+    polygon.transform(&transform.to_mat4());
+
+    assert!((polygon.vertices[0] - Vec3::new(0.0, 5.0, 0.0)).magnitude() < 1e-5);
+    assert!((polygon.vertices[1] - Vec3::new(0.0, 5.0, -3.0)).magnitude() < 1e-5);
+    assert!((polygon.face_normal() - rotation * normal).magnitude() < 1e-5);
+}
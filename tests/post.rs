@@ -0,0 +1,31 @@
+//! Nachbearbeitung: Farbkorrektur über LUTs.
+
+use rake::Texture;
+use rake::post::ColorGradingLut;
+
+#[test]
+fn lut_from_texture_reads_strip_layout() {
+    // 2 Kacheln zu 2x2 Pixeln, Rot steigt innerhalb, Blau von Kachel zu Kachel
+    let mut data = Vec::new();
+    for g in 0..2u8 {
+        for b in 0..2u8 {
+            for r in 0..2u8 {
+                data.extend_from_slice(&[r * 255, g * 255, b * 255, 255]);
+            }
+        }
+    }
+    let lut = ColorGradingLut::from_texture(&Texture::new(4, 2, data));
+    assert_eq!(lut, ColorGradingLut::identity(2));
+}
+
+#[test]
+#[should_panic(expected = "mindestens 2")]
+fn lut_from_fn_rejects_single_entry() {
+    ColorGradingLut::from_fn(1, |color| color);
+}
+
+#[test]
+#[should_panic(expected = "mindestens 2")]
+fn lut_from_texture_rejects_single_pixel() {
+    ColorGradingLut::from_texture(&Texture::new(1, 1, vec![255; 4]));
+}
@@ -0,0 +1,141 @@
+//! Parallelprojektion und Standardansichten.
+
+use rake::polygon::project_polygon;
+use rake::{Camera, Point, Polygon, Projection, StandardView};
+
+const WIDTH: usize = 200;
+const HEIGHT: usize = 100;
+
+/// Kamera im Ursprung, die entlang +Z schaut
+fn camera() -> Camera {
+    let mut camera = Camera::new(
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(0.0, 0.0, -1.0),
+        Point::new(0.0, 1.0, 0.0),
+        60.0,
+        WIDTH as f32 / HEIGHT as f32,
+        0.1,
+        100.0,
+    );
+    camera.set_standard_view(StandardView::Front);
+    camera
+}
+
+///Bildschirmkoordinaten und Tiefe der drei Ecken, in der Reihenfolge der Eingabe
+fn project(camera: &Camera, corners: [Point; 3]) -> Vec<(f32, f32, f32)> {
+    let mut triangle = Polygon::new(0xFFFFFFFF);
+    for corner in corners {
+        triangle.add_point(corner);
+    }
+    let projected = project_polygon(
+        &triangle,
+        &camera.view_matrix(),
+        &camera.projection_matrix(),
+        camera.position,
+        camera.near,
+        WIDTH,
+        HEIGHT,
+    );
+    projected.vertices.iter().map(|v| (v.x, v.y, v.z)).collect()
+}
+
+fn corners_at(z: f32, half_width: f32, half_height: f32) -> [Point; 3] {
+    [
+        Point::new(-half_width, half_height, z),
+        Point::new(half_width, half_height, z),
+        Point::new(half_width, -half_height, z),
+    ]
+}
+
+#[test]
+fn orthographic_extent_fills_screen_at_any_depth() {
+    let mut camera = camera();
+    camera.projection = Projection::Orthographic { height: 4.0 };
+
+    for z in [1.0, 10.0, 50.0] {
+        let vertices = project(&camera, corners_at(z, 4.0, 2.0));
+        let screen: Vec<_> = vertices.iter().map(|v| (v.0, v.1)).collect();
+        assert_eq!(
+            screen,
+            vec![
+                (0.0, 0.0),
+                (WIDTH as f32, 0.0),
+                (WIDTH as f32, HEIGHT as f32)
+            ],
+            "z = {z}"
+        );
+
+        // Tiefe linear zwischen Near- und Far-Plane
+        let expected = (z - camera.near) / (camera.far - camera.near);
+        assert!((vertices[0].2 - expected).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn near_plane_comes_from_the_camera() {
+    for projection in [
+        Projection::Perspective,
+        Projection::Orthographic { height: 4.0 },
+    ] {
+        let mut camera = camera();
+        camera.projection = projection;
+        camera.near = 2.0;
+        // Vor der Near-Plane verschwindet das Dreieck, dahinter beginnt die Tiefe bei 0
+        assert!(project(&camera, corners_at(1.0, 0.5, 0.5)).is_empty());
+        let vertices = project(&camera, corners_at(2.0, 0.5, 0.5));
+        assert_eq!(vertices.len(), 3);
+        assert!(vertices.iter().all(|v| v.2.abs() < 1e-5), "{vertices:?}");
+    }
+}
+
+#[test]
+fn toggle_keeps_size_at_focus_distance() {
+    let mut camera = camera();
+    let corners = corners_at(5.0, 1.0, 0.5);
+    let perspective = project(&camera, corners);
+
+    camera.toggle_projection(5.0);
+    assert!(matches!(camera.projection, Projection::Orthographic { .. }));
+    let orthographic = project(&camera, corners);
+    for (a, b) in perspective.iter().zip(&orthographic) {
+        assert!(
+            (a.0 - b.0).abs() <= 1.0 && (a.1 - b.1).abs() <= 1.0,
+            "{a:?} {b:?}"
+        );
+    }
+
+    camera.toggle_projection(5.0);
+    assert_eq!(camera.projection, Projection::Perspective);
+}
+
+#[test]
+fn standard_views_look_along_axes() {
+    // Blickrichtung (-forward) und Bild-rechts jeder Ansicht
+    let expected = [
+        (StandardView::Front, (0.0, 0.0, 1.0), (1.0, 0.0, 0.0)),
+        (StandardView::Back, (0.0, 0.0, -1.0), (-1.0, 0.0, 0.0)),
+        (StandardView::Left, (1.0, 0.0, 0.0), (0.0, 0.0, -1.0)),
+        (StandardView::Right, (-1.0, 0.0, 0.0), (0.0, 0.0, 1.0)),
+        (StandardView::Top, (0.0, -1.0, 0.0), (1.0, 0.0, 0.0)),
+        (StandardView::Bottom, (0.0, 1.0, 0.0), (1.0, 0.0, 0.0)),
+    ];
+
+    let mut camera = camera();
+    for (view, direction, right) in expected {
+        camera.set_standard_view(view);
+        let view_direction = camera.forward * -1.0;
+        let screen_right = camera.forward.cross(camera.up);
+        let (direction, right) = (
+            Point::new(direction.0, direction.1, direction.2),
+            Point::new(right.0, right.1, right.2),
+        );
+        assert!((view_direction - direction).magnitude() < 1e-5, "{view:?}");
+        assert!((screen_right - right).magnitude() < 1e-5, "{view:?}");
+    }
+
+    // Isometrisch: alle drei Achsen gleich stark verkürzt
+    camera.set_standard_view(StandardView::Isometric);
+    let f = camera.forward;
+    assert!((f.x.abs() - f.y.abs()).abs() < 1e-5 && (f.y.abs() - f.z.abs()).abs() < 1e-5);
+    assert!(f.x > 0.0 && f.y > 0.0 && f.z < 0.0, "{f:?}");
+}